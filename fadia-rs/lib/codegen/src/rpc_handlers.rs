@@ -50,25 +50,33 @@ pub fn impl_rpc_handler_trait(impl_items: &[ImplItem], self_ty: &Type) -> TokenS
                 #rep_index => Some(Self::#wrapper_name),
             });
 
-            let mut rep_class_serialized_flag = TokenStream::new();
-            let mut arg_deserialization = Vec::new();
-
-            if item_fn.sig.inputs.len() > 1 {
-                rep_class_serialized_flag.extend(quote! {
-                    ::bitstream_io::BitRead::read_bit(&mut r)?;
-                });
-
-                for _ in 1..item_fn.sig.inputs.len() {
-                    arg_deserialization.push(quote! {
-                        crate::logic::rpc::RpcArgument::deserialize(&mut r)?
-                    });
-                }
-            }
+            // Like `FRepLayout::ReceivePropertiesForRPC`, every parameter except booleans
+            // is preceded by a bit which tells if it differs from the default value.
+            let arg_deserialization = item_fn
+                .sig
+                .inputs
+                .iter()
+                .skip(1)
+                .map(|arg| {
+                    if is_bool_arg(arg) {
+                        quote! {
+                            ::bitstream_io::BitRead::read_bit(&mut r)?
+                        }
+                    } else {
+                        quote! {
+                            if ::bitstream_io::BitRead::read_bit(&mut r)? {
+                                crate::logic::rpc::RpcArgument::deserialize(&mut r)?
+                            } else {
+                                ::std::default::Default::default()
+                            }
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
 
             fn_wrappers.push(quote! {
                 fn #wrapper_name(context: crate::logic::rpc::RpcContext, rpc: crate::logic::replication::InRPC) -> ::std::io::Result<()> {
                     let mut r = ::fadia_engine::util::InBitReader::new(::std::io::Cursor::new(rpc.data.as_ref()));
-                    #rep_class_serialized_flag
                     Self::#fn_name(context, #(#arg_deserialization),*);
                     Ok(())
                 }
@@ -86,18 +94,18 @@ pub fn impl_rpc_handler_trait(impl_items: &[ImplItem], self_ty: &Type) -> TokenS
 
             let mut arg_serialization = TokenStream::new();
 
-            // If RPC doesn't have arguments at all, bool flag isn't encoded
-            if item_fn.sig.inputs.len() > 1 {
-                arg_serialization.extend(quote! {
-                    out.write_bit(true).unwrap();
-                });
-
-                for arg in item_fn.sig.inputs.iter().skip(1) {
-                    let FnArg::Typed(PatType { pat, .. }) = arg else {
-                        panic!("invalid argument encountered");
-                    };
+            for arg in item_fn.sig.inputs.iter().skip(1) {
+                let FnArg::Typed(PatType { pat, .. }) = arg else {
+                    panic!("invalid argument encountered");
+                };
 
+                if is_bool_arg(arg) {
+                    arg_serialization.extend(quote! {
+                        out.write_bit(#pat).unwrap();
+                    });
+                } else {
                     arg_serialization.extend(quote! {
+                        out.write_bit(true).unwrap();
                         crate::logic::rpc::RpcArgument::serialize(&#pat, &mut out).unwrap();
                     });
                 }
@@ -138,6 +146,14 @@ pub fn impl_rpc_handler_trait(impl_items: &[ImplItem], self_ty: &Type) -> TokenS
     }
 }
 
+/// Boolean parameters are serialized as a single bit, without the "has data" bit.
+fn is_bool_arg(arg: &FnArg) -> bool {
+    matches!(
+        arg,
+        FnArg::Typed(PatType { ty, .. }) if matches!(ty.as_ref(), Type::Path(path) if path.path.is_ident("bool"))
+    )
+}
+
 impl Parse for RpcAttr {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         const RPC_DIRECTION_CLIENT: &str = "client";
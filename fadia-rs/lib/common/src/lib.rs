@@ -1,3 +1,5 @@
 pub mod config_util;
 pub mod log_util;
+pub mod session_ticket;
 pub mod time_util;
+pub mod version_profile;
@@ -0,0 +1,208 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::time_util;
+
+/// Proof that a player logged in at the gateway, persisted in `<dir>/<player_uid>.toml`.
+/// The directory is set by both the gamesdk and game server configs, the former writes
+/// tickets at login and the latter consumes them when the client joins.
+///
+/// The client gets the token with `ClientTravelCmd` and hands it to the game server
+/// as the encryption token of `NMT_Hello`. A ticket lets in a single login.
+///
+/// Packets are encrypted with a key derived from `secret`, which is never sent over UDP.
+/// The gateway hands the key to the client along with the token, see `encryption_key`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionTicket {
+    pub player_uid: String,
+    /// Random hex string, sent in cleartext in `NMT_Hello`.
+    pub token: String,
+    /// Random hex string the packet encryption key is derived from.
+    pub secret: String,
+    /// Unix time in milliseconds after which the ticket is rejected.
+    pub expires_at: u64,
+}
+
+impl SessionTicket {
+    /// Issues a new ticket for a player, replacing the previous one.
+    pub fn issue(dir: &Path, player_uid: &str, lifetime: Duration) -> io::Result<Self> {
+        let ticket = Self {
+            player_uid: player_uid.to_string(),
+            token: random_hex(16),
+            secret: random_hex(32),
+            expires_at: time_util::unix_timestamp_ms() + lifetime.as_millis() as u64,
+        };
+
+        ticket.save(dir)?;
+        Ok(ticket)
+    }
+
+    /// Issues the next ticket of a player who is already in, e.g. for the login which follows
+    /// a `ClientTravel`. The secret is kept, so the client keeps its encryption key.
+    pub fn renew(&self, dir: &Path, lifetime: Duration) -> io::Result<Self> {
+        let ticket = Self {
+            player_uid: self.player_uid.clone(),
+            token: random_hex(16),
+            secret: self.secret.clone(),
+            expires_at: time_util::unix_timestamp_ms() + lifetime.as_millis() as u64,
+        };
+
+        ticket.save(dir)?;
+        Ok(ticket)
+    }
+
+    fn save(&self, dir: &Path) -> io::Result<()> {
+        let data = toml::to_string_pretty(self).map_err(io::Error::other)?;
+
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(Self::file_path(dir, &self.player_uid), data)
+    }
+
+    /// Ticket of a player, `None` if none was issued or it has expired.
+    pub fn load(dir: &Path, player_uid: &str) -> io::Result<Option<Self>> {
+        let data = match std::fs::read_to_string(Self::file_path(dir, player_uid)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let ticket: Self = toml::from_str(&data).map_err(io::Error::other)?;
+
+        Ok((ticket.player_uid == player_uid && !ticket.is_expired()).then_some(ticket))
+    }
+
+    /// Ticket with the given token, for connections which didn't tell their player yet.
+    pub fn find_by_token(dir: &Path, token: &str) -> io::Result<Option<Self>> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+
+            // Tickets are replaced and consumed concurrently, skip the ones which went away.
+            let Ok(data) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            if let Ok(ticket) = toml::from_str::<Self>(&data)
+                && !ticket.is_expired()
+                && ticket.matches_token(token)
+            {
+                return Ok(Some(ticket));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// AES-256 key of the packets of the player. With packet encryption, the gamesdk server
+    /// sends it hex-encoded as the `EncryptionKey` option of the travel URL.
+    pub fn encryption_key(&self) -> [u8; 32] {
+        Sha256::digest(self.secret.as_bytes()).into()
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at <= time_util::unix_timestamp_ms()
+    }
+
+    /// Compares the token in constant time, it's a bearer secret.
+    pub fn matches_token(&self, token: &str) -> bool {
+        self.token.as_bytes().ct_eq(token.as_bytes()).into()
+    }
+
+    /// Invalidates the ticket once it has let a player in.
+    pub fn consume(&self, dir: &Path) -> io::Result<()> {
+        match std::fs::remove_file(Self::file_path(dir, &self.player_uid)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn file_path(dir: &Path, player_uid: &str) -> PathBuf {
+        // Player UIDs come from clients, keep only characters which are safe in file names.
+        let file_name = player_uid
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        dir.join(format!("{file_name}.toml"))
+    }
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::rng().fill_bytes(&mut bytes);
+
+    hex::encode_upper(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fadia-session-tickets-{name}-{}", random_hex(4)))
+    }
+
+    #[test]
+    fn ticket_lets_in_a_single_login() {
+        let dir = ticket_dir("single");
+        let ticket = SessionTicket::issue(&dir, "player", Duration::from_secs(60)).unwrap();
+
+        let loaded = SessionTicket::load(&dir, "player").unwrap().unwrap();
+        assert!(loaded.matches_token(&ticket.token));
+
+        loaded.consume(&dir).unwrap();
+        assert!(SessionTicket::load(&dir, "player").unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn renewed_ticket_keeps_the_secret() {
+        let dir = ticket_dir("renew");
+        let ticket = SessionTicket::issue(&dir, "player", Duration::from_secs(60)).unwrap();
+        ticket.consume(&dir).unwrap();
+
+        let renewed = ticket.renew(&dir, Duration::from_secs(60)).unwrap();
+        assert_eq!(renewed.secret, ticket.secret);
+        assert!(!renewed.matches_token(&ticket.token));
+
+        let found = SessionTicket::find_by_token(&dir, &renewed.token)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.player_uid, "player");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn expired_ticket_is_rejected() {
+        let dir = ticket_dir("expired");
+        SessionTicket::issue(&dir, "player", Duration::ZERO).unwrap();
+
+        assert!(SessionTicket::load(&dir, "player").unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,5 +1,7 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+const UTC_OFFSET_MS: u64 = 3 * 3600 * 1000; // TODO: make it configurable or use some crate
+
 pub fn unix_time() -> Duration {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
 }
@@ -9,8 +11,6 @@ pub fn unix_timestamp_ms() -> u64 {
 }
 
 pub fn unix_utc_timestamp_ms() -> u64 {
-    const UTC_OFFSET_MS: u64 = 3 * 3600 * 1000; // TODO: make it configurable or use some crate
-
     unix_timestamp_ms() - UTC_OFFSET_MS
 }
 
@@ -20,3 +20,55 @@ pub fn current_time_in_ticks() -> u64 {
 
     (unix_timestamp_ms() * TICKS_IN_MILLISECOND) + UNIX_EPOCH_TICKS
 }
+
+/// Clock of a game server, the host clock moved forward by an offset.
+/// Gameplay code reads the time from it, so time can be skipped without touching the host clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ServerClock {
+    offset: Duration,
+}
+
+impl ServerClock {
+    pub fn new(offset: Duration) -> Self {
+        Self { offset }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&mut self, duration: Duration) {
+        self.offset += duration;
+    }
+
+    pub fn unix_time(&self) -> Duration {
+        unix_time() + self.offset
+    }
+
+    pub fn unix_timestamp_ms(&self) -> u64 {
+        self.unix_time().as_millis() as _
+    }
+
+    pub fn unix_utc_timestamp_ms(&self) -> u64 {
+        self.unix_timestamp_ms() - UTC_OFFSET_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_clock_is_ahead_by_its_offset() {
+        let mut clock = ServerClock::new(Duration::from_secs(3600));
+
+        let before = unix_timestamp_ms();
+        let now = clock.unix_timestamp_ms();
+        let after = unix_timestamp_ms();
+        assert!((before + 3_600_000..=after + 3_600_000).contains(&now));
+
+        clock.advance(Duration::from_secs(24 * 3600));
+
+        let before = unix_timestamp_ms();
+        let now = clock.unix_timestamp_ms();
+        let after = unix_timestamp_ms();
+        assert!((before + 25 * 3_600_000..=after + 25 * 3_600_000).contains(&now));
+    }
+}
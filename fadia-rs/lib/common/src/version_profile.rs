@@ -0,0 +1,68 @@
+use serde::Deserialize;
+
+/// Versions of the client build the servers talk to, so a new build only needs a config change.
+/// Every server config has a `[version]` table with the values it uses,
+/// missing values are the ones of the original build.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VersionProfile {
+    /// `FNetworkVersion::GetLocalNetworkVersion`, the checksum sent in the handshake and `NMT_Upgrade`.
+    pub network_version: u32,
+    /// Other network versions of `NMT_Hello` which are accepted,
+    /// e.g. of client builds which only changed content.
+    pub compatible_network_versions: Vec<u32>,
+    /// `EEngineNetworkRuntimeFeatures`, must match the ones of the client.
+    pub network_features: u16,
+    /// Lowest version of the stateless handshake the server speaks.
+    pub handshake_min_version: u8,
+    /// Build ID of the server list, `ServerVersionCmd::server_version`.
+    pub server_version: String,
+    /// `ServerVersionCmd::server_id`
+    pub server_id: i32,
+}
+
+impl Default for VersionProfile {
+    fn default() -> Self {
+        Self {
+            network_version: 2566650454,
+            compatible_network_versions: Vec::new(),
+            network_features: 0,
+            handshake_min_version: 3,
+            server_version: String::from("20200704"),
+            server_id: 11000,
+        }
+    }
+}
+
+impl VersionProfile {
+    /// Whether a client reporting these versions in `NMT_Hello` can play.
+    pub fn is_compatible(&self, network_version: u32, network_features: u16) -> bool {
+        (network_version == self.network_version
+            || self.compatible_network_versions.contains(&network_version))
+            && network_features == self.network_features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_own_and_compatible_versions() {
+        let profile = VersionProfile {
+            compatible_network_versions: vec![1234],
+            ..Default::default()
+        };
+
+        assert!(profile.is_compatible(profile.network_version, profile.network_features));
+        assert!(profile.is_compatible(1234, profile.network_features));
+        assert!(!profile.is_compatible(4321, profile.network_features));
+    }
+
+    #[test]
+    fn rejects_other_network_features() {
+        let profile = VersionProfile::default();
+
+        assert!(!profile.is_compatible(profile.network_version, profile.network_features | 1));
+    }
+}
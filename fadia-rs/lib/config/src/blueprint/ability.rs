@@ -1,15 +1,87 @@
 use std::path::Path;
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
-use crate::{util, LoadDataError};
+use crate::{LoadDataError, util};
 
-use super::ClassReference;
+use super::{ClassReference, ObjectReference, ScalableFloat};
+
+/// Activation requirements of a `UGameplayAbility`, read from its class default object.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PlayerCharacterAbilityProperties {
+    pub ability_tags: Vec<String>,
+    /// The ability can't be activated while the owner has any of these tags.
+    pub activation_blocked_tags: Vec<String>,
+    /// Granted to the owner for the duration of the cooldown.
+    pub cooldown_tags: Vec<String>,
+    pub cooldown_duration: ScalableFloat,
+    pub cooldown_gameplay_effect_class: Option<ObjectReference>,
+    pub cost_gameplay_effect_class: Option<ObjectReference>,
+    /// Costs paid in addition to the cost effect, e.g. `HTAbilityChargeCost` of ultimates.
+    pub additional_costs: Vec<Option<ObjectReference>>,
+    /// Effects applied to the targets hit on montage events of the ability.
+    pub effect_container_map: Vec<AbilityEffectContainerEntry>,
+}
+
+/// An entry of `EffectContainerMap`, keyed by a montage event tag,
+/// e.g. `Event.Montage.Player.Melee.1`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AbilityEffectContainerEntry {
+    #[serde(deserialize_with = "deserialize_gameplay_tag")]
+    pub key: String,
+    pub value: AbilityEffectContainer,
+}
+
+/// `FHTGameplayEffectContainer`
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct AbilityEffectContainer {
+    /// Damage range blueprint which selects the targets, e.g. `DamageRange_Fadia_Melee1`.
+    pub target_type: Option<ObjectReference>,
+    /// Damage and hit reaction effects applied to every target.
+    pub target_gameplay_effect_classes: Vec<ObjectReference>,
+}
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct PlayerCharacterAbilityConfig {
     pub name: String,
+    #[serde(default)]
+    pub properties: PlayerCharacterAbilityProperties,
+}
+
+impl PlayerCharacterAbilityProperties {
+    /// Checks whether the ability consumes charge, the energy of ultimate skills.
+    pub fn has_charge_cost(&self) -> bool {
+        self.additional_costs
+            .iter()
+            .flatten()
+            .any(|cost| cost.object_name.starts_with("HTAbilityChargeCost'"))
+    }
+
+    /// Finds the effect container which targets are selected by the damage range class
+    /// `damage_range`, e.g. `DamageRange_Fadia_Melee1_C`.
+    pub fn find_effect_container(&self, damage_range: &str) -> Option<&AbilityEffectContainer> {
+        self.effect_container_map
+            .iter()
+            .map(|entry| &entry.value)
+            .find(|container| {
+                container
+                    .target_type
+                    .as_ref()
+                    .is_some_and(|target_type| target_type.name() == damage_range)
+            })
+    }
+}
+
+impl PlayerCharacterAbilityConfig {
+    /// Name of the ability blueprint, e.g. `GA_Fadia_Skill` for `Default__GA_Fadia_Skill_C`.
+    pub fn ability_name(&self) -> &str {
+        let name = self.name.strip_prefix("Default__").unwrap_or(&self.name);
+        name.strip_suffix("_C").unwrap_or(name)
+    }
 }
 
 pub fn load_player_character_ability(
@@ -29,3 +101,16 @@ pub fn load_player_character_ability(
         inner.strip_suffix("_C").unwrap(),
     )
 }
+
+fn deserialize_gameplay_tag<'de, D>(de: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct GameplayTag {
+        tag_name: String,
+    }
+
+    Ok(GameplayTag::deserialize(de)?.tag_name)
+}
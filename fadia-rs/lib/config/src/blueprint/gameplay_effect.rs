@@ -0,0 +1,974 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{LoadConfigError, LoadDataError, util};
+
+use super::ObjectReference;
+
+const GAMEPLAY_EFFECT_CONFIG_DIR: &str = "Blueprints/Abilities";
+const GAME_PACKAGE_PREFIX: &str = "/Game/";
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayEffectDurationType {
+    #[default]
+    #[serde(rename = "EGameplayEffectDurationType::Instant")]
+    Instant,
+    #[serde(rename = "EGameplayEffectDurationType::Infinite")]
+    Infinite,
+    #[serde(rename = "EGameplayEffectDurationType::HasDuration")]
+    HasDuration,
+}
+
+/// `EBreakableLevel`, how much a damage effect breaks the tenacity of the target.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EBreakableLevel {
+    #[serde(rename = "EBreakableLevel::BL_None")]
+    None,
+    #[default]
+    #[serde(rename = "EBreakableLevel::BL_Low")]
+    Low,
+    #[serde(rename = "EBreakableLevel::BL_Middle")]
+    Middle,
+    #[serde(rename = "EBreakableLevel::BL_High")]
+    High,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayEffectStackingType {
+    #[default]
+    #[serde(rename = "EGameplayEffectStackingType::None")]
+    None,
+    #[serde(rename = "EGameplayEffectStackingType::AggregateBySource")]
+    AggregateBySource,
+    #[serde(rename = "EGameplayEffectStackingType::AggregateByTarget")]
+    AggregateByTarget,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayEffectStackingDurationPolicy {
+    #[default]
+    #[serde(rename = "EGameplayEffectStackingDurationPolicy::RefreshOnSuccessfulApplication")]
+    RefreshOnSuccessfulApplication,
+    #[serde(rename = "EGameplayEffectStackingDurationPolicy::NeverRefresh")]
+    NeverRefresh,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayEffectStackingPeriodPolicy {
+    #[default]
+    #[serde(rename = "EGameplayEffectStackingPeriodPolicy::ResetOnSuccessfulApplication")]
+    ResetOnSuccessfulApplication,
+    #[serde(rename = "EGameplayEffectStackingPeriodPolicy::NeverReset")]
+    NeverReset,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayEffectStackingExpirationPolicy {
+    #[default]
+    #[serde(rename = "EGameplayEffectStackingExpirationPolicy::ClearEntireStack")]
+    ClearEntireStack,
+    #[serde(
+        rename = "EGameplayEffectStackingExpirationPolicy::RemoveSingleStackAndRefreshDuration"
+    )]
+    RemoveSingleStackAndRefreshDuration,
+    #[serde(rename = "EGameplayEffectStackingExpirationPolicy::RefreshDuration")]
+    RefreshDuration,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EGameplayModOp {
+    #[default]
+    #[serde(rename = "EGameplayModOp::AddBase", alias = "EGameplayModOp::Additive")]
+    AddBase,
+    #[serde(
+        rename = "EGameplayModOp::MultiplyAdditive",
+        alias = "EGameplayModOp::Multiplicitive"
+    )]
+    MultiplyAdditive,
+    #[serde(
+        rename = "EGameplayModOp::DivideAdditive",
+        alias = "EGameplayModOp::Division"
+    )]
+    DivideAdditive,
+    #[serde(rename = "EGameplayModOp::Override")]
+    Override,
+    #[serde(rename = "EGameplayModOp::MultiplyCompound")]
+    MultiplyCompound,
+    #[serde(rename = "EGameplayModOp::AddFinal")]
+    AddFinal,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum EGameplayEffectMagnitudeCalculation {
+    #[default]
+    #[serde(rename = "EGameplayEffectMagnitudeCalculation::ScalableFloat")]
+    ScalableFloat,
+    #[serde(rename = "EGameplayEffectMagnitudeCalculation::AttributeBased")]
+    AttributeBased,
+    #[serde(rename = "EGameplayEffectMagnitudeCalculation::CustomCalculationClass")]
+    CustomCalculationClass,
+    #[serde(rename = "EGameplayEffectMagnitudeCalculation::SetByCaller")]
+    SetByCaller,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct CurveTableRowHandle {
+    pub curve_table: Option<ObjectReference>,
+    pub row_name: String,
+}
+
+/// `FScalableFloat`: a base value optionally scaled by a curve table row.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ScalableFloat {
+    pub value: f32,
+    pub curve: CurveTableRowHandle,
+}
+
+impl ScalableFloat {
+    pub fn has_curve(&self) -> bool {
+        self.curve.curve_table.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameplayEffectMagnitude {
+    ScalableFloat(ScalableFloat),
+    AttributeBased {
+        attribute: String,
+        coefficient: ScalableFloat,
+        pre_multiply_additive_value: ScalableFloat,
+        post_multiply_additive_value: ScalableFloat,
+    },
+    CustomCalculationClass {
+        calculation_class: String,
+        coefficient: ScalableFloat,
+        pre_multiply_additive_value: ScalableFloat,
+        post_multiply_additive_value: ScalableFloat,
+    },
+    SetByCaller {
+        data_name: String,
+        data_tag: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameplayModifierInfo {
+    pub attribute: String,
+    pub op: EGameplayModOp,
+    pub magnitude: GameplayEffectMagnitude,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameplayEffectExecution {
+    pub calculation_class: String,
+    pub modifiers: Vec<GameplayModifierInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameplayEffectConfig {
+    pub name: String,
+    /// Name of the class default object, which is referenced by replicated effect specs.
+    pub default_object_name: String,
+    /// Package path of the effect blueprint, e.g.
+    /// `/Game/Blueprints/Abilities/Player/Ability_004_Lacrimosa/Buff/Buff_Lacrimosa_AddAttack`.
+    pub path: String,
+    /// Package path of the parent effect blueprint, if it's present in the dump.
+    pub parent: Option<String>,
+    /// Name of the first class in the inheritance chain which is absent from the dump.
+    pub root_class: String,
+    pub duration_policy: EGameplayEffectDurationType,
+    pub duration_magnitude: GameplayEffectMagnitude,
+    pub period: ScalableFloat,
+    pub execute_periodic_effect_on_application: bool,
+    pub stacking_type: EGameplayEffectStackingType,
+    pub stack_limit_count: i32,
+    pub stack_duration_refresh_policy: EGameplayEffectStackingDurationPolicy,
+    pub stack_period_reset_policy: EGameplayEffectStackingPeriodPolicy,
+    pub stack_expiration_policy: EGameplayEffectStackingExpirationPolicy,
+    pub modifiers: Vec<GameplayModifierInfo>,
+    pub executions: Vec<GameplayEffectExecution>,
+    /// Tags describing the effect itself.
+    pub asset_tags: Vec<String>,
+    /// Tags granted to the target while the effect is active.
+    pub granted_tags: Vec<String>,
+    /// Imbalance dealt by damage effects, `AttackBreakLevel`.
+    pub attack_break_level: EBreakableLevel,
+}
+
+impl GameplayEffectMagnitude {
+    pub fn scalable_floats(&self) -> Vec<&ScalableFloat> {
+        match self {
+            Self::ScalableFloat(value) => vec![value],
+            Self::AttributeBased {
+                coefficient,
+                pre_multiply_additive_value,
+                post_multiply_additive_value,
+                ..
+            }
+            | Self::CustomCalculationClass {
+                coefficient,
+                pre_multiply_additive_value,
+                post_multiply_additive_value,
+                ..
+            } => vec![
+                coefficient,
+                pre_multiply_additive_value,
+                post_multiply_additive_value,
+            ],
+            Self::SetByCaller { .. } => Vec::new(),
+        }
+    }
+}
+
+impl GameplayEffectConfig {
+    /// Package paths of every curve table referenced by the effect.
+    pub fn referenced_curve_tables(&self) -> impl Iterator<Item = &str> {
+        self.duration_magnitude
+            .scalable_floats()
+            .into_iter()
+            .chain([&self.period])
+            .chain(
+                self.modifiers
+                    .iter()
+                    .chain(
+                        self.executions
+                            .iter()
+                            .flat_map(|exec| exec.modifiers.iter()),
+                    )
+                    .flat_map(|modifier| modifier.magnitude.scalable_floats()),
+            )
+            .filter_map(|value| value.curve.curve_table.as_ref())
+            .filter_map(|table| table.package_and_index().map(|(package, _)| package))
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InheritedTagContainer {
+    combined_tags: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct GameplayAttribute {
+    attribute_name: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct AttributeCaptureDefinition {
+    attribute_to_capture: GameplayAttribute,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct AttributeBasedFloat {
+    coefficient: ScalableFloat,
+    pre_multiply_additive_value: ScalableFloat,
+    post_multiply_additive_value: ScalableFloat,
+    backing_attribute: AttributeCaptureDefinition,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct CustomCalculationBasedFloat {
+    calculation_class_magnitude: Option<ObjectReference>,
+    coefficient: ScalableFloat,
+    pre_multiply_additive_value: ScalableFloat,
+    post_multiply_additive_value: ScalableFloat,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct GameplayTag {
+    tag_name: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct SetByCallerFloat {
+    data_name: String,
+    data_tag: GameplayTag,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ModifierMagnitude {
+    magnitude_calculation_type: EGameplayEffectMagnitudeCalculation,
+    scalable_float_magnitude: ScalableFloat,
+    attribute_based_magnitude: AttributeBasedFloat,
+    custom_magnitude: CustomCalculationBasedFloat,
+    set_by_caller_magnitude: SetByCallerFloat,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ModifierInfo {
+    attribute: GameplayAttribute,
+    modifier_op: EGameplayModOp,
+    modifier_magnitude: ModifierMagnitude,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct CustomApplicationModifierInfo {
+    gameplay_modifier_infos: Vec<ModifierInfo>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ExecutionModifierInfo {
+    captured_attribute: AttributeCaptureDefinition,
+    modifier_op: EGameplayModOp,
+    modifier_magnitude: ModifierMagnitude,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct ExecutionDefinition {
+    calculation_class: Option<ObjectReference>,
+    calculation_modifiers: Vec<ExecutionModifierInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct GameplayEffectProperties {
+    duration_policy: EGameplayEffectDurationType,
+    duration_magnitude: ModifierMagnitude,
+    period: ScalableFloat,
+    #[serde(rename = "bExecutePeriodicEffectOnApplication")]
+    execute_periodic_effect_on_application: bool,
+    stacking_type: EGameplayEffectStackingType,
+    stack_limit_count: i32,
+    stack_duration_refresh_policy: EGameplayEffectStackingDurationPolicy,
+    stack_period_reset_policy: EGameplayEffectStackingPeriodPolicy,
+    stack_expiration_policy: EGameplayEffectStackingExpirationPolicy,
+    modifiers: Vec<ModifierInfo>,
+    custom_application_modifier_infos: Vec<CustomApplicationModifierInfo>,
+    executions: Vec<ExecutionDefinition>,
+    inheritable_gameplay_effect_tags: InheritedTagContainer,
+    inheritable_owned_tags_container: InheritedTagContainer,
+    #[serde(rename = "GEComponents")]
+    components: Vec<Option<ObjectReference>>,
+    attack_break_level: EBreakableLevel,
+}
+
+impl Default for GameplayEffectProperties {
+    fn default() -> Self {
+        Self {
+            duration_policy: Default::default(),
+            duration_magnitude: Default::default(),
+            period: Default::default(),
+            execute_periodic_effect_on_application: true,
+            stacking_type: Default::default(),
+            stack_limit_count: 0,
+            stack_duration_refresh_policy: Default::default(),
+            stack_period_reset_policy: Default::default(),
+            stack_expiration_policy: Default::default(),
+            modifiers: Vec::new(),
+            custom_application_modifier_infos: Vec::new(),
+            executions: Vec::new(),
+            inheritable_gameplay_effect_tags: Default::default(),
+            inheritable_owned_tags_container: Default::default(),
+            components: Vec::new(),
+            attack_break_level: Default::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct GameplayEffectComponentProperties {
+    inheritable_asset_tags: InheritedTagContainer,
+    inheritable_granted_tags_container: InheritedTagContainer,
+}
+
+impl From<ModifierMagnitude> for GameplayEffectMagnitude {
+    fn from(value: ModifierMagnitude) -> Self {
+        use EGameplayEffectMagnitudeCalculation as Calc;
+
+        match value.magnitude_calculation_type {
+            Calc::ScalableFloat => Self::ScalableFloat(value.scalable_float_magnitude),
+            Calc::AttributeBased => {
+                let magnitude = value.attribute_based_magnitude;
+                Self::AttributeBased {
+                    attribute: magnitude
+                        .backing_attribute
+                        .attribute_to_capture
+                        .attribute_name,
+                    coefficient: magnitude.coefficient,
+                    pre_multiply_additive_value: magnitude.pre_multiply_additive_value,
+                    post_multiply_additive_value: magnitude.post_multiply_additive_value,
+                }
+            }
+            Calc::CustomCalculationClass => {
+                let magnitude = value.custom_magnitude;
+                Self::CustomCalculationClass {
+                    calculation_class: magnitude
+                        .calculation_class_magnitude
+                        .map(|class| class.name().to_string())
+                        .unwrap_or_default(),
+                    coefficient: magnitude.coefficient,
+                    pre_multiply_additive_value: magnitude.pre_multiply_additive_value,
+                    post_multiply_additive_value: magnitude.post_multiply_additive_value,
+                }
+            }
+            Calc::SetByCaller => Self::SetByCaller {
+                data_name: value.set_by_caller_magnitude.data_name,
+                data_tag: value.set_by_caller_magnitude.data_tag.tag_name,
+            },
+        }
+    }
+}
+
+impl From<ModifierInfo> for GameplayModifierInfo {
+    fn from(value: ModifierInfo) -> Self {
+        Self {
+            attribute: value.attribute.attribute_name,
+            op: value.modifier_op,
+            magnitude: value.modifier_magnitude.into(),
+        }
+    }
+}
+
+/// Loads every `GE_*` and `Buff_*` blueprint under `Blueprints/Abilities`,
+/// keyed by the package path of the blueprint.
+pub fn load_gameplay_effect_configs() -> Result<HashMap<String, GameplayEffectConfig>, LoadDataError>
+{
+    let config_names = util::find_config_files(GAMEPLAY_EFFECT_CONFIG_DIR, &|name| {
+        name.starts_with("GE_") || name.starts_with("Buff_")
+    })?;
+
+    let mut loader = GameplayEffectLoader::new("assets");
+    let mut output = HashMap::with_capacity(config_names.len());
+
+    for config_name in config_names {
+        let package = format!("{GAME_PACKAGE_PREFIX}{config_name}");
+
+        // Some of the effects ship standalone assets (e.g. curves) under the same prefix.
+        if !loader.is_blueprint_class(&package)? {
+            continue;
+        }
+
+        let config = loader.load(&package)?;
+        output.insert(package, config);
+    }
+
+    Ok(output)
+}
+
+struct ResolvedClass {
+    name: String,
+    parent: Option<String>,
+    root_class: String,
+    properties: Map<String, Value>,
+}
+
+struct GameplayEffectLoader {
+    /// Directory of the dump, packages under `/Game/` are files in it.
+    assets_dir: &'static str,
+    packages: HashMap<String, Option<Vec<Value>>>,
+    classes: HashMap<String, ResolvedClass>,
+    resolving: HashSet<String>,
+}
+
+impl GameplayEffectLoader {
+    fn new(assets_dir: &'static str) -> Self {
+        Self {
+            assets_dir,
+            packages: HashMap::new(),
+            classes: HashMap::new(),
+            resolving: HashSet::new(),
+        }
+    }
+
+    fn load(&mut self, package: &str) -> Result<GameplayEffectConfig, LoadDataError> {
+        let file_path = self.package_file_path(package).unwrap();
+
+        if !self.resolve_class(package)? {
+            return Err(LoadDataError {
+                path: file_path,
+                err: LoadConfigError::MissingObject(
+                    String::from("Type"),
+                    String::from("BlueprintGeneratedClass"),
+                ),
+            });
+        }
+
+        let class = &self.classes[&package.to_ascii_lowercase()];
+        let name = class.name.clone();
+        let parent = class.parent.clone();
+        let root_class = class.root_class.clone();
+
+        let properties: GameplayEffectProperties =
+            serde_json::from_value(Value::Object(class.properties.clone())).map_err(|err| {
+                LoadDataError {
+                    path: file_path.clone(),
+                    err: err.into(),
+                }
+            })?;
+
+        let mut asset_tags = properties.inheritable_gameplay_effect_tags.combined_tags;
+        let mut granted_tags = properties.inheritable_owned_tags_container.combined_tags;
+
+        for component in properties.components.iter().flatten() {
+            let component_properties = self.resolve_object(package, component)?;
+            let component_properties: GameplayEffectComponentProperties =
+                serde_json::from_value(Value::Object(component_properties)).map_err(|err| {
+                    LoadDataError {
+                        path: file_path.clone(),
+                        err: err.into(),
+                    }
+                })?;
+
+            merge_tags(
+                &mut asset_tags,
+                component_properties.inheritable_asset_tags.combined_tags,
+            );
+            merge_tags(
+                &mut granted_tags,
+                component_properties
+                    .inheritable_granted_tags_container
+                    .combined_tags,
+            );
+        }
+
+        let modifiers = properties
+            .modifiers
+            .into_iter()
+            .chain(
+                properties
+                    .custom_application_modifier_infos
+                    .into_iter()
+                    .flat_map(|info| info.gameplay_modifier_infos),
+            )
+            .map(GameplayModifierInfo::from)
+            .collect();
+
+        let executions = properties
+            .executions
+            .into_iter()
+            .map(|execution| GameplayEffectExecution {
+                calculation_class: execution
+                    .calculation_class
+                    .map(|class| class.name().to_string())
+                    .unwrap_or_default(),
+                modifiers: execution
+                    .calculation_modifiers
+                    .into_iter()
+                    .map(|modifier| GameplayModifierInfo {
+                        attribute: modifier
+                            .captured_attribute
+                            .attribute_to_capture
+                            .attribute_name,
+                        op: modifier.modifier_op,
+                        magnitude: modifier.modifier_magnitude.into(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(GameplayEffectConfig {
+            default_object_name: format!("Default__{name}_C"),
+            name,
+            path: package.to_string(),
+            parent,
+            root_class,
+            duration_policy: properties.duration_policy,
+            duration_magnitude: properties.duration_magnitude.into(),
+            period: properties.period,
+            execute_periodic_effect_on_application: properties
+                .execute_periodic_effect_on_application,
+            stacking_type: properties.stacking_type,
+            stack_limit_count: properties.stack_limit_count,
+            stack_duration_refresh_policy: properties.stack_duration_refresh_policy,
+            stack_period_reset_policy: properties.stack_period_reset_policy,
+            stack_expiration_policy: properties.stack_expiration_policy,
+            modifiers,
+            executions,
+            asset_tags,
+            granted_tags,
+            attack_break_level: properties.attack_break_level,
+        })
+    }
+
+    fn is_blueprint_class(&mut self, package: &str) -> Result<bool, LoadDataError> {
+        Ok(self.package_objects(package)?.is_some_and(|objects| {
+            objects
+                .iter()
+                .any(|object| object["Type"] == "BlueprintGeneratedClass")
+        }))
+    }
+
+    /// Resolves the blueprint class defined in `package` along with its parents.
+    /// Returns `false` if the package is not present in the dump.
+    fn resolve_class(&mut self, package: &str) -> Result<bool, LoadDataError> {
+        let key = package.to_ascii_lowercase();
+        if self.classes.contains_key(&key) {
+            return Ok(true);
+        }
+
+        let Some(file_path) = self.package_file_path(package) else {
+            return Ok(false);
+        };
+
+        if !self.resolving.insert(key.clone()) {
+            return Err(LoadDataError {
+                path: file_path,
+                err: LoadConfigError::InheritanceCycle(package.to_string()),
+            });
+        }
+
+        let result = self.resolve_class_inner(package, &file_path);
+        self.resolving.remove(&key);
+
+        let Some(class) = result? else {
+            return Ok(false);
+        };
+
+        self.classes.insert(key, class);
+        Ok(true)
+    }
+
+    fn resolve_class_inner(
+        &mut self,
+        package: &str,
+        file_path: &str,
+    ) -> Result<Option<ResolvedClass>, LoadDataError> {
+        let Some(objects) = self.package_objects(package)? else {
+            return Ok(None);
+        };
+
+        let Some(class) = objects
+            .iter()
+            .find(|object| object["Type"] == "BlueprintGeneratedClass")
+        else {
+            return Err(LoadDataError {
+                path: file_path.to_string(),
+                err: LoadConfigError::MissingObject(
+                    String::from("Type"),
+                    String::from("BlueprintGeneratedClass"),
+                ),
+            });
+        };
+
+        let name = class["Name"]
+            .as_str()
+            .map(|name| name.strip_suffix("_C").unwrap_or(name).to_string())
+            .unwrap_or_default();
+
+        let super_class = parse_reference(file_path, &class["Super"])?;
+        let default_object = parse_reference(file_path, &class["ClassDefaultObject"])?;
+
+        let Some(default_object) = default_object else {
+            return Err(LoadDataError {
+                path: file_path.to_string(),
+                err: LoadConfigError::MissingObject(
+                    String::from("ClassDefaultObject"),
+                    format!("{name}_C"),
+                ),
+            });
+        };
+
+        let own_properties = self
+            .lookup_object(file_path, &default_object)?
+            .get("Properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        let (parent, root_class, mut properties) = match super_class {
+            Some(super_class) => {
+                let (super_package, _) = reference_package(file_path, &super_class)?;
+
+                if self.resolve_class(super_package)? {
+                    let parent = &self.classes[&super_package.to_ascii_lowercase()];
+                    (
+                        Some(super_package.to_string()),
+                        parent.root_class.clone(),
+                        parent.properties.clone(),
+                    )
+                } else {
+                    (None, super_class.name().to_string(), Map::new())
+                }
+            }
+            None => (None, String::new(), Map::new()),
+        };
+
+        merge_properties(&mut properties, own_properties);
+
+        Ok(Some(ResolvedClass {
+            name,
+            parent,
+            root_class,
+            properties,
+        }))
+    }
+
+    /// Resolves properties of a sub-object (e.g. a GE component), following its templates.
+    /// Templates which are absent from the dump end the chain.
+    fn resolve_object(
+        &mut self,
+        package: &str,
+        reference: &ObjectReference,
+    ) -> Result<Map<String, Value>, LoadDataError> {
+        let mut file_path = self.package_file_path(package).unwrap();
+        let mut reference = reference.clone();
+        let mut chain = Vec::new();
+
+        loop {
+            let object = self.lookup_object(&file_path, &reference)?;
+            let properties = object
+                .get("Properties")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+            let template = object["Template"].clone();
+
+            // The object was found, so its package is backed by a file.
+            let (object_package, _) = reference_package(&file_path, &reference)?;
+            file_path = self.package_file_path(object_package).unwrap();
+            let template = parse_reference(&file_path, &template)?;
+
+            chain.push(properties);
+
+            let Some(template) = template else {
+                break;
+            };
+
+            let (template_package, _) = reference_package(&file_path, &template)?;
+            if self.package_objects(template_package)?.is_none() {
+                break;
+            }
+
+            if chain.len() > self.packages.len() {
+                return Err(LoadDataError {
+                    path: file_path,
+                    err: LoadConfigError::InheritanceCycle(template.object_path),
+                });
+            }
+
+            reference = template;
+        }
+
+        let mut properties = Map::new();
+        for object_properties in chain.into_iter().rev() {
+            merge_properties(&mut properties, object_properties);
+        }
+
+        Ok(properties)
+    }
+
+    /// Looks up the object `reference` points to. `file_path` is the file which contains the reference.
+    fn lookup_object(
+        &mut self,
+        file_path: &str,
+        reference: &ObjectReference,
+    ) -> Result<&Value, LoadDataError> {
+        let (package, index) = reference_package(file_path, reference)?;
+
+        let invalid_reference = || LoadDataError {
+            path: file_path.to_string(),
+            err: LoadConfigError::InvalidReference(reference.object_path.clone()),
+        };
+
+        if self.package_file_path(package).is_none() {
+            return Err(invalid_reference());
+        }
+
+        let object = self
+            .package_objects(package)?
+            .and_then(|objects| objects.get(index))
+            .ok_or_else(invalid_reference)?;
+
+        let expected_name = reference.name();
+        let expected_name = expected_name
+            .rsplit_once(':')
+            .map(|(_, name)| name)
+            .unwrap_or(expected_name);
+
+        if object["Name"].as_str() != Some(expected_name) {
+            return Err(invalid_reference());
+        }
+
+        Ok(object)
+    }
+
+    /// Maps a package path to its file in the dump. Package paths are case-insensitive,
+    /// while references don't always match the case of the dumped directories.
+    fn package_file_path(&self, package: &str) -> Option<String> {
+        let path = format!(
+            "{}/{}.json",
+            self.assets_dir,
+            package.strip_prefix(GAME_PACKAGE_PREFIX)?
+        );
+        Some(util::find_file_ignore_case(&path).unwrap_or(path))
+    }
+
+    fn package_objects(&mut self, package: &str) -> Result<Option<&Vec<Value>>, LoadDataError> {
+        let key = package.to_ascii_lowercase();
+        if !self.packages.contains_key(&key) {
+            let objects = match self.package_file_path(package) {
+                Some(file_path) if std::path::Path::new(&file_path).exists() => {
+                    Some(util::read_config_objects(&file_path)?)
+                }
+                _ => None,
+            };
+
+            self.packages.insert(key.clone(), objects);
+        }
+
+        Ok(self.packages[&key].as_ref())
+    }
+}
+
+fn parse_reference(
+    file_path: &str,
+    value: &Value,
+) -> Result<Option<ObjectReference>, LoadDataError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    ObjectReference::deserialize(value)
+        .map(Some)
+        .map_err(|err| LoadDataError {
+            path: file_path.to_string(),
+            err: err.into(),
+        })
+}
+
+fn reference_package<'r>(
+    file_path: &str,
+    reference: &'r ObjectReference,
+) -> Result<(&'r str, usize), LoadDataError> {
+    reference.package_and_index().ok_or_else(|| LoadDataError {
+        path: file_path.to_string(),
+        err: LoadConfigError::InvalidReference(reference.object_path.clone()),
+    })
+}
+
+/// Overrides inherited properties with the ones serialized by the child.
+/// Structs are serialized as deltas, so objects are merged recursively.
+fn merge_properties(base: &mut Map<String, Value>, overrides: Map<String, Value>) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(base)), Value::Object(value)) => merge_properties(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_tags(tags: &mut Vec<String>, other: Vec<String>) {
+    for tag in other {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Made-up effect blueprints in the layout of the dump.
+    const FIXTURE_DIR: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/GameplayEffects"
+    );
+
+    fn load(package: &str) -> Result<GameplayEffectConfig, LoadDataError> {
+        GameplayEffectLoader::new(FIXTURE_DIR).load(package)
+    }
+
+    #[test]
+    fn stops_inheritance_at_classes_missing_from_the_dump() {
+        let config = load("/Game/Effects/GE_Base").unwrap();
+
+        assert_eq!(config.name, "GE_Base");
+        assert_eq!(config.default_object_name, "Default__GE_Base_C");
+        assert_eq!(config.parent, None);
+        assert_eq!(config.root_class, "GE_MissingBase_C");
+        assert_eq!(config.asset_tags, ["Buff.Base"]);
+        assert_eq!(config.granted_tags, ["State.Base"]);
+    }
+
+    #[test]
+    fn merges_properties_of_the_super_class() {
+        let config = load("/Game/Effects/GE_Child").unwrap();
+
+        assert_eq!(config.parent.as_deref(), Some("/Game/Effects/GE_Base"));
+        assert_eq!(config.root_class, "GE_MissingBase_C");
+        assert_eq!(
+            config.duration_policy,
+            EGameplayEffectDurationType::HasDuration
+        );
+        assert_eq!(
+            config.stacking_type,
+            EGameplayEffectStackingType::AggregateByTarget
+        );
+        assert_eq!(config.stack_limit_count, 5);
+
+        let GameplayEffectMagnitude::ScalableFloat(duration) = &config.duration_magnitude else {
+            panic!(
+                "unexpected duration magnitude: {:?}",
+                config.duration_magnitude
+            );
+        };
+        assert_eq!(duration.value, 8.0);
+
+        assert_eq!(config.modifiers.len(), 1);
+        assert_eq!(config.modifiers[0].attribute, "Atk");
+        assert_eq!(config.modifiers[0].op, EGameplayModOp::AddBase);
+    }
+
+    #[test]
+    fn merges_component_properties_of_the_template() {
+        let config = load("/Game/Effects/GE_Child").unwrap();
+
+        assert_eq!(config.asset_tags, ["Buff.Base", "Asset.Child"]);
+        assert_eq!(config.granted_tags, ["State.Base"]);
+    }
+
+    #[test]
+    fn rejects_super_class_cycles() {
+        let err = load("/Game/Effects/GE_CycleA").unwrap_err();
+        assert!(
+            matches!(err.err, LoadConfigError::InheritanceCycle(_)),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn rejects_template_cycles() {
+        let err = load("/Game/Effects/GE_TemplateCycle").unwrap_err();
+        assert!(
+            matches!(err.err, LoadConfigError::InheritanceCycle(_)),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn rejects_references_out_of_the_package() {
+        let err = load("/Game/Effects/GE_BrokenIndex").unwrap_err();
+        assert!(
+            matches!(err.err, LoadConfigError::InvalidReference(ref path) if path == "/Game/Effects/GE_BrokenIndex.5"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn rejects_references_to_other_objects() {
+        let err = load("/Game/Effects/GE_BrokenName").unwrap_err();
+        assert!(
+            matches!(err.err, LoadConfigError::InvalidReference(ref path) if path == "/Game/Effects/GE_BrokenName.2"),
+            "{err}"
+        );
+    }
+}
@@ -2,10 +2,12 @@ use serde::{Deserialize, Deserializer};
 
 mod ability;
 mod character;
+mod gameplay_effect;
 mod weapon;
 
 pub use ability::*;
 pub use character::*;
+pub use gameplay_effect::*;
 pub use weapon::*;
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -16,6 +18,13 @@ pub struct ClassReference {
     pub sub_path_string: String,
 }
 
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectReference {
+    pub object_name: String,
+    pub object_path: String,
+}
+
 #[derive(Debug, Default)]
 pub struct ClassDefinition {
     pub ty: String,
@@ -23,6 +32,24 @@ pub struct ClassDefinition {
     pub name: String,
 }
 
+impl ObjectReference {
+    /// Returns the package path and the export index of the referenced object,
+    /// e.g. `("/Game/Blueprints/Share/Buff/BuffBase", 1)`.
+    pub fn package_and_index(&self) -> Option<(&str, usize)> {
+        let (package, index) = self.object_path.rsplit_once('.')?;
+        Some((package, index.parse().ok()?))
+    }
+
+    /// Returns the object name without its class prefix,
+    /// e.g. `BuffBase_C` for `BlueprintGeneratedClass'BuffBase_C'`.
+    pub fn name(&self) -> &str {
+        self.object_name
+            .split_once('\'')
+            .map(|(_, name)| name.trim_end_matches('\''))
+            .unwrap_or(&self.object_name)
+    }
+}
+
 impl ClassReference {
     pub fn is_empty(&self) -> bool {
         self.asset_path_name.0.is_empty() || self.asset_path_name.1.is_empty()
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::{LoadConfigError, LoadDataError, util};
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ERichCurveInterpMode {
+    #[default]
+    #[serde(rename = "RCIM_Linear")]
+    Linear,
+    #[serde(rename = "RCIM_Constant")]
+    Constant,
+    #[serde(rename = "RCIM_Cubic")]
+    Cubic,
+    #[serde(rename = "RCIM_None")]
+    None,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct CurveKey {
+    #[serde(default)]
+    pub interp_mode: ERichCurveInterpMode,
+    pub time: f32,
+    pub value: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Curve {
+    pub keys: Vec<CurveKey>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct CurveTable {
+    pub rows: HashMap<String, Curve>,
+}
+
+impl Curve {
+    /// Evaluates the curve at `time`, clamping to the first and the last key.
+    /// Cubic segments are approximated linearly.
+    pub fn eval(&self, time: f32) -> Option<f32> {
+        let first = self.keys.first()?;
+        let last = self.keys.last()?;
+
+        if time <= first.time {
+            return Some(first.value);
+        }
+
+        if time >= last.time {
+            return Some(last.value);
+        }
+
+        self.keys.windows(2).find_map(|keys| {
+            let [from, to] = keys else {
+                return None;
+            };
+
+            (time >= from.time && time < to.time).then(|| match from.interp_mode {
+                ERichCurveInterpMode::Constant | ERichCurveInterpMode::None => from.value,
+                ERichCurveInterpMode::Linear | ERichCurveInterpMode::Cubic => {
+                    let alpha = (time - from.time) / (to.time - from.time);
+                    from.value + (to.value - from.value) * alpha
+                }
+            })
+        })
+    }
+}
+
+impl CurveTable {
+    /// Loads a curve table by its package path, e.g.
+    /// `/Game/DataTable/Skill/GlobalCharacterData/DT_LacrimosaEffectFigure`.
+    /// Returns `None` if the table is not present in the dump.
+    pub fn load(package: &str) -> Result<Option<Self>, LoadDataError> {
+        let Some(path) = package.strip_prefix("/Game/") else {
+            return Ok(None);
+        };
+
+        let file_path = format!("assets/{path}.json");
+        let Some(file_path) = util::find_file_ignore_case(&file_path) else {
+            return Ok(None);
+        };
+
+        let Some(table) = util::read_config_objects(&file_path)?
+            .into_iter()
+            .find(|object| object["Type"] == "CurveTable")
+        else {
+            return Err(LoadDataError {
+                path: file_path,
+                err: LoadConfigError::MissingObject(
+                    String::from("Type"),
+                    String::from("CurveTable"),
+                ),
+            });
+        };
+
+        serde_json::from_value(table)
+            .map(Some)
+            .map_err(|err| LoadDataError {
+                path: file_path,
+                err: err.into(),
+            })
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+};
+
+use serde::Deserialize;
+
+use crate::{LoadDataError, util};
+
+use super::DataTable;
+
+/// Tag list of the client, extracted from the game config next to the asset dumps.
+const TAG_LIST_PATH: &str = "assets/Config/DefaultGameplayTags.ini";
+
+/// Dictionary of gameplay tags, which maps tags to their net indices.
+///
+/// Like `UGameplayTagsManager::ConstructNetIndex`, every tag (including parent tags)
+/// is indexed in the order of the case-insensitively sorted tag names.
+#[derive(Debug, Default)]
+pub struct GameplayTagTable {
+    tags: Vec<String>,
+    net_indices: HashMap<String, u16>,
+}
+
+/// `FGameplayTagTableRow`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct GameplayTagTableRow {
+    tag: String,
+}
+
+impl GameplayTagTable {
+    /// Loads the tags of the client: the `+GameplayTagList` entries of its `DefaultGameplayTags.ini`
+    /// and the rows of the tag tables listed by `+GameplayTagTableList`. Tags declared by the
+    /// native code of the game aren't in the ini and have to be added to it.
+    /// `None` if the ini is missing, net indices can't be known without it.
+    pub fn load() -> Result<Option<Self>, LoadDataError> {
+        let Some(path) = util::find_file_ignore_case(TAG_LIST_PATH) else {
+            return Ok(None);
+        };
+
+        let ini = fs::read_to_string(&path).map_err(|err| LoadDataError {
+            path: path.clone(),
+            err: err.into(),
+        })?;
+
+        let mut tags = BTreeSet::new();
+
+        for line in ini.lines().map(str::trim) {
+            if let Some(entry) = line.strip_prefix("+GameplayTagList=") {
+                // `(Tag="Ability.Skill",DevComment="")`, the tag always comes first.
+                if let Some(tag) = entry
+                    .strip_prefix("(Tag=\"")
+                    .and_then(|entry| entry.split('"').next())
+                {
+                    insert_tag(tag, &mut tags);
+                }
+            } else if let Some(table) = line.strip_prefix("+GameplayTagTableList=") {
+                // Object path of the data table, e.g. `/Game/DataTable/Tags/DT_Tags.DT_Tags`.
+                let package = table
+                    .trim_matches('"')
+                    .split('.')
+                    .next()
+                    .unwrap_or_default();
+                let table_path = format!(
+                    "assets/{}.json",
+                    package.strip_prefix("/Game/").unwrap_or(package)
+                );
+                let table_path = util::find_file_ignore_case(&table_path).unwrap_or(table_path);
+
+                let table = DataTable::<GameplayTagTableRow>::load_from_file(&table_path)?;
+                for row in table.rows.values() {
+                    insert_tag(&row.tag, &mut tags);
+                }
+            }
+        }
+
+        Ok(Some(Self::from_tags(tags)))
+    }
+
+    pub fn from_tags(tags: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        // Tag names are case-insensitive, keyed by their lowercase form.
+        let mut all_tags = BTreeMap::new();
+
+        for tag in tags {
+            let tag = tag.as_ref();
+            all_tags
+                .entry(tag.to_ascii_lowercase())
+                .or_insert_with(|| tag.to_string());
+
+            // Parent tags are implicitly registered as well.
+            let mut parent = tag;
+            while let Some((outer, _)) = parent.rsplit_once('.') {
+                all_tags
+                    .entry(outer.to_ascii_lowercase())
+                    .or_insert_with(|| outer.to_string());
+                parent = outer;
+            }
+        }
+
+        let net_indices = all_tags
+            .keys()
+            .enumerate()
+            .map(|(index, tag)| (tag.clone(), index as u16))
+            .collect();
+
+        Self {
+            tags: all_tags.into_values().collect(),
+            net_indices,
+        }
+    }
+
+    pub fn net_index(&self, tag: &str) -> Option<u16> {
+        self.net_indices.get(&tag.to_ascii_lowercase()).copied()
+    }
+
+    pub fn tag_name(&self, net_index: u16) -> Option<&str> {
+        self.tags.get(usize::from(net_index)).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Checks whether `tag` is `other` or one of its children, `FGameplayTag::MatchesTag`.
+pub fn gameplay_tag_matches(tag: &str, other: &str) -> bool {
+    tag.len() >= other.len()
+        && tag[..other.len()].eq_ignore_ascii_case(other)
+        && (tag.len() == other.len() || tag.as_bytes()[other.len()] == b'.')
+}
+
+fn insert_tag(tag: &str, tags: &mut BTreeSet<String>) {
+    if !tag.is_empty() && tag != "None" {
+        tags.insert(tag.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_tags_and_their_parents_in_sorted_order() {
+        let table = GameplayTagTable::from_tags([
+            "StateX",
+            "State.Stun",
+            "Ability.Skill.Fadia",
+            "ability.skill",
+            "CD",
+        ]);
+
+        let expected = [
+            "Ability",
+            "Ability.Skill",
+            "Ability.Skill.Fadia",
+            "CD",
+            "State",
+            "State.Stun",
+            "StateX",
+        ];
+
+        assert_eq!(table.len(), expected.len());
+        for (index, tag) in expected.into_iter().enumerate() {
+            assert_eq!(table.net_index(tag), Some(index as u16));
+            assert_eq!(table.tag_name(index as u16), Some(tag));
+        }
+    }
+
+    #[test]
+    fn looks_up_tags_case_insensitively() {
+        let table = GameplayTagTable::from_tags(["State.Stun"]);
+
+        assert_eq!(table.net_index("state.STUN"), Some(1));
+        assert_eq!(table.net_index("State.Stun.Heavy"), None);
+        assert_eq!(table.tag_name(2), None);
+    }
+
+    #[test]
+    fn matches_tags_and_their_parents() {
+        assert!(gameplay_tag_matches("State.Stun.Heavy", "State.Stun"));
+        assert!(gameplay_tag_matches("state.stun", "State.Stun"));
+        assert!(!gameplay_tag_matches("State.Stun", "State.Stun.Heavy"));
+        assert!(!gameplay_tag_matches("State.StunHeavy", "State.Stun"));
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ItemData {
+    pub item_type: EItemType,
+    #[serde(default)]
+    pub quality: u32,
+    /// Items above this count are split into several stacks.
+    pub max_stack_count: u32,
+    #[serde(rename = "bCanUse", default)]
+    pub can_use: bool,
+    #[serde(rename = "bCanDiscard", default)]
+    pub can_discard: bool,
+    /// Package paths of gameplay effects applied to the character which uses the item.
+    #[serde(default)]
+    pub use_effects: Vec<String>,
+    /// Satiety restored by food.
+    #[serde(default)]
+    pub satiety: f32,
+    /// Seconds before the item can be used again, after it was used.
+    #[serde(rename = "CDTime", default)]
+    pub cooldown: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EItemType {
+    #[serde(rename = "EItemType::Food")]
+    Food,
+    #[serde(rename = "EItemType::Consumable")]
+    Consumable,
+    #[serde(rename = "EItemType::Material")]
+    Material,
+    #[serde(rename = "EItemType::Quest")]
+    Quest,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::DataTable;
+
+    /// Made-up rows in the layout of the client's data tables, the real item table isn't dumped yet.
+    const FIXTURE: &str = include_str!("../../tests/fixtures/ItemDataTable.json");
+
+    #[test]
+    fn parses_item_rows() {
+        let [table]: [DataTable<ItemData>; 1] = serde_json::from_str(FIXTURE).unwrap();
+
+        let fish = &table.rows["food_grilled_fish"];
+        assert_eq!(fish.item_type, EItemType::Food);
+        assert_eq!(fish.max_stack_count, 99);
+        assert!(fish.can_use);
+        assert_eq!(fish.satiety, 20.0);
+        assert_eq!(fish.cooldown, 5.0);
+
+        assert_eq!(
+            table.rows["quest_sealed_letter"].item_type,
+            EItemType::Quest
+        );
+    }
+}
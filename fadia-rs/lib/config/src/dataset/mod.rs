@@ -1,14 +1,22 @@
-use std::{collections::HashMap, fs::File};
+use std::{collections::HashMap, fs::File, path::Path};
 
 use serde::{Deserialize, de::DeserializeOwned};
 
 use crate::LoadDataError;
 
 mod avatar;
+mod curve_table;
 mod function_unlock;
+mod gameplay_tags;
+mod item;
+mod vehicle;
 
 pub use avatar::*;
+pub use curve_table::*;
 pub use function_unlock::*;
+pub use gameplay_tags::*;
+pub use item::*;
+pub use vehicle::*;
 
 const DATASET_BASE_PATH: &str = "DataAssets/DataAssetSet";
 const DT_BASE_PATH: &str = "DataTable";
@@ -22,10 +30,26 @@ pub struct DataTable<Data> {
 pub struct DataAssetSet {
     pub function_unlock_table: DataTable<FunctionUnlockData>,
     pub avatar_data_table: DataTable<AvatarData>,
+    /// `None` until the item table is extracted from the client, it's not in the dumps yet.
+    pub item_data_table: Option<DataTable<ItemData>>,
+    /// `None` until the vehicle table is extracted from the client, it's not in the dumps yet.
+    pub vehicle_data_table: Option<DataTable<VehicleData>>,
 }
 
 impl DataAssetSet {
     pub fn load() -> Result<Self, LoadDataError> {
+        let mut vehicle_data_table: Option<DataTable<VehicleData>> =
+            DataTable::load_from_file_if_exists(&format!(
+                "assets/{DT_BASE_PATH}/Vehicle/VehicleDataTable.json"
+            ))?;
+
+        for vehicle in vehicle_data_table
+            .iter_mut()
+            .flat_map(|table| table.rows.values_mut())
+        {
+            vehicle.default_object_name = format!("Default__{}", vehicle.vehicle_class.name());
+        }
+
         Ok(Self {
             function_unlock_table: DataTable::load_from_file(&format!(
                 "assets/{DATASET_BASE_PATH}/FunctionUnlock/FunctionUnlockDataTable.json"
@@ -33,6 +57,10 @@ impl DataAssetSet {
             avatar_data_table: DataTable::load_from_file(&format!(
                 "assets/{DT_BASE_PATH}/Avatar/AvatarDataTable.json"
             ))?,
+            item_data_table: DataTable::load_from_file_if_exists(&format!(
+                "assets/{DT_BASE_PATH}/Item/ItemDataTable.json"
+            ))?,
+            vehicle_data_table,
         })
     }
 }
@@ -51,4 +79,12 @@ impl<T: DeserializeOwned> DataTable<T> {
             })
             .map(|[data]| data)
     }
+
+    fn load_from_file_if_exists(path: &str) -> Result<Option<Self>, LoadDataError> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        Self::load_from_file(path).map(Some)
+    }
 }
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+use crate::blueprint::ObjectReference;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct VehicleData {
+    /// Pawn blueprint spawned when the vehicle is placed.
+    pub vehicle_class: ObjectReference,
+    /// Units per second, the server rejects faster movement reported by the driver.
+    pub max_speed: f64,
+    /// Name of the class default object, the archetype of spawned vehicles.
+    #[serde(skip)]
+    pub default_object_name: String,
+}
@@ -2,6 +2,7 @@ use std::io;
 
 pub mod blueprint;
 pub mod dataset;
+pub mod spawn;
 
 mod util;
 
@@ -22,4 +23,8 @@ pub enum LoadConfigError {
     UnexpectedRootDataType,
     #[error("config is missing an object with {0}:{1}")]
     MissingObject(String, String),
+    #[error("invalid object reference: {0}")]
+    InvalidReference(String),
+    #[error("class inheritance cycle detected at {0}")]
+    InheritanceCycle(String),
 }
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fs, io};
+
+use serde::Deserialize;
+
+use crate::{LoadDataError, blueprint::ObjectReference};
+
+const SPAWN_CONFIG_DIR: &str = "assets/Spawns";
+
+/// Monsters placed on a map, loaded from `assets/Spawns/<map name>.json`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct MonsterSpawnTable {
+    pub monsters: Vec<MonsterSpawnPoint>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct MonsterSpawnPoint {
+    /// Character blueprint of the monster.
+    pub class: ObjectReference,
+    pub location: Vector,
+    #[serde(default)]
+    pub yaw: f64,
+    #[serde(default)]
+    pub stats: CharacterStats,
+    #[serde(default)]
+    pub ai: MonsterAiSettings,
+    /// Name of the class default object, the archetype of spawned monsters.
+    #[serde(skip)]
+    pub default_object_name: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "PascalCase")]
+pub struct Vector {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Initial values of the attribute set of a character.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct CharacterStats {
+    pub hp_max: f32,
+    pub atk: f32,
+    pub def: f32,
+    pub crit: f32,
+    pub crit_damage: f32,
+}
+
+/// Distances are in centimeters, like the engine units.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct MonsterAiSettings {
+    /// Players closer than this are attacked.
+    pub aggro_radius: f64,
+    pub attack_radius: f64,
+    /// The monster gives up and returns once it's this far from the spawn point.
+    pub leash_radius: f64,
+    /// Units per second.
+    pub move_speed: f64,
+    /// Seconds between two attacks.
+    pub attack_interval: f64,
+}
+
+impl Default for CharacterStats {
+    fn default() -> Self {
+        Self {
+            hp_max: 1000.0,
+            atk: 50.0,
+            def: 30.0,
+            crit: 0.0,
+            crit_damage: 0.5,
+        }
+    }
+}
+
+impl Default for MonsterAiSettings {
+    fn default() -> Self {
+        Self {
+            aggro_radius: 1500.0,
+            attack_radius: 250.0,
+            leash_radius: 3000.0,
+            move_speed: 400.0,
+            attack_interval: 2.0,
+        }
+    }
+}
+
+/// Loads the spawn tables of every map, keyed by the map name, e.g. `XL_map_bigworld_test`.
+pub fn load_monster_spawn_tables() -> Result<HashMap<String, MonsterSpawnTable>, LoadDataError> {
+    let mut output = HashMap::new();
+
+    let entries = match fs::read_dir(SPAWN_CONFIG_DIR) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(output),
+        Err(err) => {
+            return Err(LoadDataError {
+                path: SPAWN_CONFIG_DIR.to_string(),
+                err: err.into(),
+            });
+        }
+    };
+
+    for entry in entries {
+        let path = entry
+            .map_err(|err| LoadDataError {
+                path: SPAWN_CONFIG_DIR.to_string(),
+                err: err.into(),
+            })?
+            .path();
+
+        let Some(map_name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+
+        let to_load_error = |err: crate::LoadConfigError| LoadDataError {
+            path: path.display().to_string(),
+            err,
+        };
+
+        let file = fs::File::open(&path).map_err(|err| to_load_error(err.into()))?;
+        let mut table: MonsterSpawnTable = serde_json::from_reader(io::BufReader::new(file))
+            .map_err(|err| to_load_error(err.into()))?;
+
+        for monster in table.monsters.iter_mut() {
+            if monster.class.package_and_index().is_none() {
+                return Err(to_load_error(crate::LoadConfigError::InvalidReference(
+                    monster.class.object_path.clone(),
+                )));
+            }
+
+            monster.default_object_name = format!("Default__{}", monster.class.name());
+        }
+
+        output.insert(map_name.to_string(), table);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Made-up spawn points, monster blueprints aren't dumped yet.
+    const FIXTURE: &str = include_str!("../tests/fixtures/MonsterSpawnTable.json");
+
+    #[test]
+    fn parses_spawn_points() {
+        let table: MonsterSpawnTable = serde_json::from_str(FIXTURE).unwrap();
+
+        let first = &table.monsters[0];
+        assert_eq!(first.class.name(), "MonsterCharacterBase_C");
+        assert_eq!(first.location.x, -78351.5);
+        assert_eq!(first.yaw, 180.0);
+        assert_eq!(first.stats.hp_max, CharacterStats::default().hp_max);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     fs::{self, File},
+    path::{Path, PathBuf},
 };
 
 use serde::de::DeserializeOwned;
@@ -44,31 +45,7 @@ pub(crate) fn load_config<T: DeserializeOwned>(
     config_name: &str,
 ) -> Result<T, LoadDataError> {
     let file_path = format!("assets/{base_dir}/{config_name}.json");
-    let mut file = match File::open(&file_path) {
-        Ok(file) => file,
-        Err(err) => {
-            return Err(LoadDataError {
-                path: file_path,
-                err: err.into(),
-            });
-        }
-    };
-
-    let value = match serde_json::from_reader::<_, serde_json::Value>(&mut file) {
-        Ok(serde_json::Value::Array(value)) => value,
-        Ok(_) => {
-            return Err(LoadDataError {
-                path: file_path,
-                err: LoadConfigError::UnexpectedRootDataType,
-            });
-        }
-        Err(err) => {
-            return Err(LoadDataError {
-                path: file_path,
-                err: err.into(),
-            });
-        }
-    };
+    let value = read_config_objects(&file_path)?;
 
     let config_type = format!("{config_name}_C");
 
@@ -99,3 +76,101 @@ pub(crate) fn load_config<T: DeserializeOwned>(
         err: err.into(),
     })
 }
+
+pub(crate) fn read_config_objects(
+    file_path: &str,
+) -> Result<Vec<serde_json::Value>, LoadDataError> {
+    let mut file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(LoadDataError {
+                path: file_path.to_string(),
+                err: err.into(),
+            });
+        }
+    };
+
+    match serde_json::from_reader::<_, serde_json::Value>(&mut file) {
+        Ok(serde_json::Value::Array(value)) => Ok(value),
+        Ok(_) => Err(LoadDataError {
+            path: file_path.to_string(),
+            err: LoadConfigError::UnexpectedRootDataType,
+        }),
+        Err(err) => Err(LoadDataError {
+            path: file_path.to_string(),
+            err: err.into(),
+        }),
+    }
+}
+
+/// Recursively collects config names (relative to `assets/`, without the `.json` suffix)
+/// of files under `base_dir` which names pass the `filter`.
+pub(crate) fn find_config_files(
+    base_dir: &str,
+    filter: &impl Fn(&str) -> bool,
+) -> Result<Vec<String>, LoadDataError> {
+    let mut output = Vec::new();
+
+    let dir = format!("assets/{base_dir}/");
+    let entries = fs::read_dir(&dir).map_err(|err| LoadDataError {
+        path: dir.clone(),
+        err: err.into(),
+    })?;
+
+    for entry in entries {
+        let entry = entry.map_err(|err| LoadDataError {
+            path: dir.clone(),
+            err: err.into(),
+        })?;
+
+        let path = entry.path();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+
+        if path.is_dir() {
+            output.extend(find_config_files(
+                &format!("{base_dir}/{file_name}"),
+                filter,
+            )?);
+        } else if let Some(config_name) = file_name.strip_suffix(".json")
+            && filter(config_name)
+        {
+            output.push(format!("{base_dir}/{config_name}"));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Finds an existing file matching `path`, comparing path components case-insensitively.
+pub(crate) fn find_file_ignore_case(path: &str) -> Option<String> {
+    if Path::new(path).exists() {
+        return Some(path.to_string());
+    }
+
+    let mut resolved = PathBuf::new();
+    for component in Path::new(path).components() {
+        let exact = resolved.join(component);
+        if exact.exists() {
+            resolved = exact;
+            continue;
+        }
+
+        let dir = if resolved.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            resolved.as_path()
+        };
+
+        let name = component.as_os_str().to_str()?;
+        let entry = fs::read_dir(dir).ok()?.flatten().find(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|entry_name| entry_name.eq_ignore_ascii_case(name))
+        })?;
+
+        resolved.push(entry.file_name());
+    }
+
+    resolved.to_str().map(str::to_string)
+}
@@ -0,0 +1,30 @@
+use std::io;
+
+use crate::util::{ReadPrimitivesExt, WritePrimitivesExt};
+
+/// `FGuid`, serialized as its four components.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FGuid {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+impl FGuid {
+    pub fn net_serialize<W: WritePrimitivesExt>(&self, w: &mut W) -> io::Result<()> {
+        w.write_u32(self.a)?;
+        w.write_u32(self.b)?;
+        w.write_u32(self.c)?;
+        w.write_u32(self.d)
+    }
+
+    pub fn net_deserialize<R: ReadPrimitivesExt>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            a: r.read_u32()?,
+            b: r.read_u32()?,
+            c: r.read_u32()?,
+            d: r.read_u32()?,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+mod guid;
 pub mod net;
 mod net_guid;
 pub mod package_map;
@@ -6,4 +7,5 @@ pub mod rotator;
 pub mod util;
 pub mod vector;
 
+pub use guid::FGuid;
 pub use net_guid::{FNetworkGUID, NetGUIDCache, NetGUIDCacheObject};
@@ -35,7 +35,7 @@ pub struct SequenceHistory([Word; WORD_COUNT as usize]);
 #[derive(Debug)]
 pub struct FNotificationHeader {
     pub packed_header: FPackedHeader,
-    pub _history: SequenceHistory,
+    pub history: SequenceHistory,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -86,7 +86,13 @@ impl FNetPacketNotify {
         self.waiting_for_flush_seq_ack > self.out_ack_seq
     }
 
-    pub fn process_received_acks(&mut self, notification_data: &FNotificationHeader) {
+    /// Reports the delivery status of every packet acknowledged by the header to `on_ack`,
+    /// packets missing from the sequence history were lost.
+    pub fn process_received_acks(
+        &mut self,
+        notification_data: &FNotificationHeader,
+        mut on_ack: impl FnMut(SequenceNumber, bool),
+    ) {
         if notification_data.packed_header.get_acked_seq() > self.out_ack_seq {
             let mut ack_count = diff(
                 notification_data.packed_header.get_acked_seq(),
@@ -99,21 +105,24 @@ impl FNetPacketNotify {
                 self.in_ack_seq_ack = new_in_ack_seq_ack;
             }
 
-            let mut current_ack = self.out_ack_seq;
-            current_ack = current_ack.wrapping_add(1);
+            let mut current_ack = self.out_ack_seq.wrapping_add(1);
+            let history_bits = (notification_data.packed_header.get_history_word_count() as u32
+                + 1)
+                * BITS_PER_WORD;
 
-            while ack_count
-                > notification_data.packed_header.get_history_word_count() as u32 * BITS_PER_WORD
-            {
+            while ack_count > history_bits {
                 ack_count -= 1;
-                // in_func(current_ack, false)
+                on_ack(current_ack, false);
                 current_ack = current_ack.wrapping_add(1);
             }
 
             while ack_count > 0 {
                 ack_count -= 1;
-                // in_func(current_ack, notification_data.history.is_delivered);
-                current_ack += 1;
+                on_ack(
+                    current_ack,
+                    notification_data.history.is_delivered(ack_count as usize),
+                );
+                current_ack = current_ack.wrapping_add(1);
             }
 
             self.out_ack_seq = notification_data.packed_header.get_acked_seq();
@@ -257,6 +266,11 @@ impl FNetPacketNotify {
         }
     }
 
+    /// Sequence number of the next packet sent.
+    pub fn get_out_seq(&self) -> SequenceNumber {
+        self.out_seq
+    }
+
     pub fn commit_and_increment_seq(&mut self) -> SequenceNumber {
         self.ack_record.push_back(FSentAckData {
             out_seq: self.out_seq,
@@ -320,7 +334,7 @@ impl FNotificationHeader {
 
         Ok(Self {
             packed_header: header,
-            _history: SequenceHistory::read(r, header.get_history_word_count() + 1)?,
+            history: SequenceHistory::read(r, header.get_history_word_count() + 1)?,
         })
     }
 }
@@ -366,3 +380,58 @@ impl fmt::Display for FPackedHeader {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn acks(
+        history_words: &[Word],
+        acked_seq: SequenceNumber,
+        out_seq: SequenceNumber,
+    ) -> Vec<(SequenceNumber, bool)> {
+        let mut notify = FNetPacketNotify::default();
+        notify.init(0, out_seq);
+
+        let mut history = SequenceHistory::default();
+        history.0[..history_words.len()].copy_from_slice(history_words);
+
+        let header = FNotificationHeader {
+            packed_header: FPackedHeader::pack(0, acked_seq, history_words.len() as u16 - 1),
+            history,
+        };
+
+        let mut acks = Vec::new();
+        notify.process_received_acks(&header, |seq, delivered| acks.push((seq, delivered)));
+        acks
+    }
+
+    #[test]
+    fn looks_up_every_word_of_the_history() {
+        let acks = acks(&[Word::MAX, Word::MAX], 64, 1);
+
+        assert_eq!(acks.len(), 64);
+        assert_eq!(acks.first(), Some(&(1, true)));
+        assert!(acks.iter().all(|&(_, delivered)| delivered));
+    }
+
+    #[test]
+    fn reports_packets_missing_from_the_history_as_lost() {
+        // The newest packet is the lowest bit, packet 28 is missing.
+        let acks = acks(&[!(1 << 5)], 33, 1);
+
+        let lost = acks
+            .iter()
+            .filter(|(_, delivered)| !delivered)
+            .map(|&(seq, _)| seq)
+            .collect::<Vec<_>>();
+
+        assert_eq!(acks.len(), 33);
+        assert_eq!(lost, [1, 28]);
+    }
+
+    #[test]
+    fn ignores_acks_already_processed() {
+        assert!(acks(&[Word::MAX], 0, 1).is_empty());
+    }
+}
@@ -154,10 +154,10 @@ impl FNetFieldExport {
         w.write(8, self.export_flags.0)?;
         if self.export_flags.has_path() {
             w.write_packed_int(self.outer_guid.0)?;
-            if self.outer_guid.is_valid() {
-                if let Some(export) = export_map.get(&self.outer_guid) {
-                    export.export(&self.outer_guid, export_map, w)?;
-                }
+            if self.outer_guid.is_valid()
+                && let Some(export) = export_map.get(&self.outer_guid)
+            {
+                export.export(&self.outer_guid, export_map, w)?;
             }
             w.write_string(&self.path)?;
             if self.export_flags.has_network_checksum() {
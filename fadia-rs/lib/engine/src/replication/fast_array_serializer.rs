@@ -22,13 +22,16 @@ struct FastArraySerializerItem<T> {
 }
 
 impl<T: RepLayout> FastArraySerializer<T> {
-    pub fn push(&mut self, item: T) {
+    /// Adds an item and returns its replication ID.
+    pub fn push(&mut self, item: T) -> u32 {
         self.array_replication_key += 1;
         self.changed_ids.insert(self.array_replication_key);
         self.items.push(FastArraySerializerItem {
             element_id: self.array_replication_key,
             item,
         });
+
+        self.array_replication_key
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
@@ -42,11 +45,13 @@ impl<T: RepLayout> FastArraySerializer<T> {
             .map(|item| &item.item)
     }
 
+    /// Returns a mutable reference to an item and marks it dirty, `MarkItemDirty`.
     pub fn get_mut(&mut self, element_id: u32) -> Option<&mut T> {
         self.items
             .iter_mut()
             .find(|item| item.element_id == element_id)
             .map(|item| {
+                self.array_replication_key += 1;
                 self.changed_ids.insert(element_id);
                 &mut item.item
             })
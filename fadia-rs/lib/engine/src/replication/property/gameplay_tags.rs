@@ -0,0 +1,171 @@
+use bitstream_io::BitWrite;
+
+use crate::util::OutBitWriter;
+
+use super::ReplicatedProperty;
+
+/// Index of a tag in the replication dictionary, `FGameplayTagNetIndex`.
+pub type GameplayTagNetIndex = u16;
+
+/// Mirrors the net serialization settings of `UGameplayTagsManager`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameplayTagNetSettings {
+    /// Number of bits required to write any net index, `NetIndexTrueBitNum`.
+    pub net_index_true_bit_num: u32,
+    /// Size of the first segment of a packed net index, `NetIndexFirstBitSegment`.
+    pub net_index_first_bit_segment: u32,
+    /// Number of bits used to write the amount of tags in a container.
+    pub num_bits_for_container_size: u32,
+}
+
+/// Replicated as a list of net indices, which requires fast replication
+/// (`bFastReplication`) to be enabled in the gameplay tags settings.
+#[derive(Debug, Clone, Default)]
+pub struct GameplayTagContainer {
+    tags: Vec<GameplayTagNetIndex>,
+    settings: GameplayTagNetSettings,
+    changed: bool,
+}
+
+impl GameplayTagNetSettings {
+    const DEFAULT_NET_INDEX_FIRST_BIT_SEGMENT: u32 = 16;
+    const DEFAULT_NUM_BITS_FOR_CONTAINER_SIZE: u32 = 6;
+
+    /// Computes the settings for a dictionary of `num_tags` tags.
+    pub fn new(num_tags: usize) -> Self {
+        // Net index of `num_tags + 1` is reserved as invalid,
+        // the bit count is `ceil(log2(invalid_tag_net_index))`.
+        let invalid_tag_net_index = num_tags as u32 + 1;
+        let net_index_true_bit_num = u32::BITS - (invalid_tag_net_index - 1).leading_zeros();
+
+        Self {
+            net_index_true_bit_num,
+            net_index_first_bit_segment: Self::DEFAULT_NET_INDEX_FIRST_BIT_SEGMENT
+                .min(net_index_true_bit_num),
+            num_bits_for_container_size: Self::DEFAULT_NUM_BITS_FOR_CONTAINER_SIZE,
+        }
+    }
+
+    /// `SerializeTagNetIndexPacked`
+    fn write_net_index(
+        &self,
+        w: &mut OutBitWriter,
+        net_index: GameplayTagNetIndex,
+    ) -> std::io::Result<()> {
+        let net_index = u32::from(net_index);
+
+        if self.net_index_first_bit_segment >= self.net_index_true_bit_num {
+            return w.write(self.net_index_true_bit_num, net_index);
+        }
+
+        let first_segment_mask = (1 << self.net_index_first_bit_segment) - 1;
+        w.write(
+            self.net_index_first_bit_segment,
+            net_index & first_segment_mask,
+        )?;
+
+        let remaining = net_index >> self.net_index_first_bit_segment;
+        w.write_bit(remaining != 0)?;
+
+        if remaining != 0 {
+            w.write(
+                self.net_index_true_bit_num - self.net_index_first_bit_segment,
+                remaining,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for GameplayTagNetSettings {
+    fn default() -> Self {
+        Self::new(usize::from(GameplayTagNetIndex::MAX - 1))
+    }
+}
+
+impl GameplayTagContainer {
+    pub fn new(settings: GameplayTagNetSettings) -> Self {
+        Self {
+            tags: Vec::new(),
+            settings,
+            changed: false,
+        }
+    }
+
+    pub fn with_tags(
+        settings: GameplayTagNetSettings,
+        tags: impl IntoIterator<Item = GameplayTagNetIndex>,
+    ) -> Self {
+        let mut container = Self::new(settings);
+        tags.into_iter().for_each(|tag| {
+            container.add_tag(tag);
+        });
+
+        container.changed = false;
+        container
+    }
+
+    pub fn add_tag(&mut self, tag: GameplayTagNetIndex) -> bool {
+        if self.tags.contains(&tag) {
+            return false;
+        }
+
+        self.tags.push(tag);
+        self.changed = true;
+        true
+    }
+
+    pub fn remove_tag(&mut self, tag: GameplayTagNetIndex) -> bool {
+        let Some(index) = self.tags.iter().position(|&t| t == tag) else {
+            return false;
+        };
+
+        self.tags.remove(index);
+        self.changed = true;
+        true
+    }
+
+    pub fn has_tag(&self, tag: GameplayTagNetIndex) -> bool {
+        self.tags.contains(&tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = GameplayTagNetIndex> {
+        self.tags.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+impl ReplicatedProperty for GameplayTagContainer {
+    fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    fn acknowledge_changes(&mut self) {
+        self.changed = false;
+    }
+
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        w.write_bit(self.tags.is_empty())?;
+
+        if !self.tags.is_empty() {
+            let max_tags = (1 << self.settings.num_bits_for_container_size) - 1;
+            let num_tags = self.tags.len().min(max_tags);
+
+            w.write(self.settings.num_bits_for_container_size, num_tags as u32)?;
+
+            for &tag in self.tags.iter().take(num_tags) {
+                self.settings.write_net_index(w, tag)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
+mod gameplay_tags;
+mod movement;
 mod primitives;
 
 use std::io;
 
+pub use gameplay_tags::*;
+pub use movement::*;
 pub use primitives::*;
 
 use crate::util::OutBitWriter;
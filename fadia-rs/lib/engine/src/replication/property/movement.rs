@@ -0,0 +1,77 @@
+use bitstream_io::BitWrite;
+
+use crate::{
+    rotator::FRotator,
+    util::{OutBitWriter, quantized::QuantizedWriteExt},
+    vector::FVector3d,
+};
+
+use super::ReplicatedProperty;
+
+/// `AActor::ReplicatedMovement`, serialized by `FRepMovement::NetSerialize`
+/// with the default quantization: whole-number vectors and byte rotator components.
+#[derive(Debug, Default)]
+pub struct PropertyRepMovement {
+    location: FVector3d,
+    rotation: FRotator,
+    linear_velocity: FVector3d,
+    changed: bool,
+}
+
+impl PropertyRepMovement {
+    /// Number of bits of the flags bitfield, including `bRepServerFrame` and `bRepServerHandle`.
+    const FLAGS_BITS: u32 = 4;
+
+    pub fn new(location: FVector3d, rotation: FRotator) -> Self {
+        Self {
+            location,
+            rotation,
+            linear_velocity: FVector3d::default(),
+            changed: false,
+        }
+    }
+
+    pub fn set_value(
+        &mut self,
+        location: FVector3d,
+        rotation: FRotator,
+        linear_velocity: FVector3d,
+    ) {
+        if self.location != location
+            || self.rotation != rotation
+            || self.linear_velocity != linear_velocity
+        {
+            self.location = location;
+            self.rotation = rotation;
+            self.linear_velocity = linear_velocity;
+            self.changed = true;
+        }
+    }
+
+    pub fn location(&self) -> &FVector3d {
+        &self.location
+    }
+
+    pub fn rotation(&self) -> &FRotator {
+        &self.rotation
+    }
+}
+
+impl ReplicatedProperty for PropertyRepMovement {
+    fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    fn acknowledge_changes(&mut self) {
+        self.changed = false;
+    }
+
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        // bSimulatedPhysicSleep, bRepPhysics, bRepServerFrame and bRepServerHandle are unset.
+        w.write(Self::FLAGS_BITS, 0u8)?;
+
+        w.write_packed_vector(&self.location, 1)?;
+        self.rotation.net_serialize_compressed(w)?;
+        w.write_packed_vector(&self.linear_velocity, 1)
+    }
+}
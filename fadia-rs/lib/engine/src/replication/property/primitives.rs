@@ -6,7 +6,6 @@ use crate::{
     },
     vector::FVector3d,
 };
-use bitstream_io::BitWrite;
 
 use super::ReplicatedProperty;
 
@@ -96,9 +95,6 @@ pub struct PropertyVectorQuantize100 {
     changed: bool,
 }
 
-#[derive(Debug)]
-pub struct GameplayTagContainer;
-
 impl PropertyString {
     pub fn new(value: String) -> Self {
         Self {
@@ -269,18 +265,6 @@ impl ReplicatedProperty for PropertyVectorQuantize100 {
     }
 }
 
-impl ReplicatedProperty for GameplayTagContainer {
-    fn is_changed(&self) -> bool {
-        false
-    }
-
-    fn acknowledge_changes(&mut self) {}
-
-    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
-        w.write_bit(true) // is_empty
-    }
-}
-
 impl<T> PropertyArray<T> {
     pub fn push(&mut self, item: T) {
         self.is_changed = true;
@@ -293,6 +277,7 @@ impl<T> PropertyArray<T> {
     }
 
     pub fn remove(&mut self, index: usize) -> T {
+        self.is_changed = true;
         self.items.remove(index)
     }
 
@@ -308,6 +293,10 @@ impl<T> PropertyArray<T> {
         self.items.len()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
@@ -2,7 +2,7 @@ use std::io;
 
 use bitstream_io::{BitRead, BitWrite};
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct FRotator {
     pitch: f64,
     yaw: f64,
@@ -20,6 +20,10 @@ impl FRotator {
         self.roll = roll;
     }
 
+    pub fn yaw(&self) -> f64 {
+        self.yaw
+    }
+
     pub fn should_serialize(&self) -> bool {
         !matches!(
             self,
@@ -57,6 +61,20 @@ impl FRotator {
         Ok(())
     }
 
+    /// `FRotator::SerializeCompressed`, every axis is quantized to a byte.
+    pub fn net_serialize_compressed<W: BitWrite>(&self, w: &mut W) -> io::Result<()> {
+        for axis in [self.pitch, self.yaw, self.roll] {
+            let byte_axis = compress_axis_to_byte(axis);
+
+            w.write_bit(byte_axis != 0)?;
+            if byte_axis != 0 {
+                w.write(8, byte_axis)?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn net_deserialize<R: BitRead>(r: &mut R) -> io::Result<Self> {
         let short_pitch = if r.read_bit()? { r.read(16)? } else { 0 };
         let short_yaw = if r.read_bit()? { r.read(16)? } else { 0 };
@@ -70,6 +88,10 @@ impl FRotator {
     }
 }
 
+fn compress_axis_to_byte(angle: f64) -> u8 {
+    round_to_int(angle * 256.0 / 360.0) as u8
+}
+
 fn compress_axis_to_short(angle: f64) -> u16 {
     round_to_int(angle * 65536.0 / 360.0) as u16
 }
@@ -114,7 +114,7 @@ impl<T: BitRead> ReadBitsExt for T {
         let mut buffer = vec![0u8; bytes];
         self.read_bytes(&mut buffer[..(amount / 8)])?;
 
-        if amount % 8 != 0 {
+        if !amount.is_multiple_of(8) {
             for i in 0..(amount % 8) {
                 if self.read_bit()? {
                     buffer[bytes - 1] |= 1 << i;
@@ -1,6 +1,7 @@
 use std::{borrow::Cow, io};
 
-use super::{FStringReadExt, FStringWriteExt};
+use super::{FStringReadExt, FStringWriteExt, PackedBitReadExt, PackedBitWriteExt};
+use crate::FNetworkGUID;
 use bitstream_io::{BitRead, BitWrite};
 
 pub trait BitSerialize: Sized {
@@ -38,6 +39,26 @@ impl BitSerialize for u32 {
     }
 }
 
+impl BitSerialize for i32 {
+    fn read_from_bits<R: BitRead>(r: &mut R) -> io::Result<Self> {
+        r.read_signed(32)
+    }
+
+    fn write_to_bits<W: BitWrite>(&self, w: &mut W) -> io::Result<()> {
+        w.write_signed(32, *self)
+    }
+}
+
+impl BitSerialize for FNetworkGUID {
+    fn read_from_bits<R: BitRead>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(r.read_packed_int()?))
+    }
+
+    fn write_to_bits<W: BitWrite>(&self, w: &mut W) -> io::Result<()> {
+        w.write_packed_int(self.0)
+    }
+}
+
 impl BitSerialize for String {
     fn read_from_bits<R: BitRead>(r: &mut R) -> io::Result<Self> {
         r.read_string()
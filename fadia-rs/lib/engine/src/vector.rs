@@ -33,6 +33,11 @@ impl FVector3d {
             .fold(0.0, |a, b| a.max(b))
     }
 
+    /// Distance to `other` on the horizontal plane, `FVector::Dist2D`.
+    pub fn dist_2d(&self, other: &Self) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+
     pub fn get_abs_min(&self) -> f64 {
         [self.x.abs(), self.y.abs(), self.z.abs()]
             .into_iter()
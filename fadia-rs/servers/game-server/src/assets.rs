@@ -1,19 +1,28 @@
 use std::collections::HashMap;
 
+use tracing::{error, warn};
+
 use fadia_config::{
     LoadDataError,
     blueprint::{
-        ClassReference, PlayerCharacterAbilityConfig, PlayerCharacterConfig,
-        PlayerCharacterWeaponConfig,
+        ClassReference, GameplayEffectConfig, PlayerCharacterAbilityConfig, PlayerCharacterConfig,
+        PlayerCharacterWeaponConfig, ScalableFloat,
     },
-    dataset::DataAssetSet,
+    dataset::{CurveTable, DataAssetSet, GameplayTagTable, ItemData, VehicleData},
+    spawn::{MonsterSpawnPoint, MonsterSpawnTable},
 };
+use fadia_engine::replication::property::{GameplayTagContainer, GameplayTagNetSettings};
 
 pub struct GameAssets {
     pub data_asset_set: DataAssetSet,
     player_character_configs: HashMap<String, PlayerCharacterConfig>,
     player_character_abilities: HashMap<ClassReference, PlayerCharacterAbilityConfig>,
     player_character_weapons: HashMap<String, PlayerCharacterWeaponConfig>,
+    gameplay_effects: HashMap<String, GameplayEffectConfig>,
+    curve_tables: HashMap<String, CurveTable>,
+    pub gameplay_tags: GameplayTagTable,
+    gameplay_tag_net_settings: GameplayTagNetSettings,
+    monster_spawns: HashMap<String, MonsterSpawnTable>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -26,11 +35,25 @@ pub enum AssetsLoadingError {
     PlayerCharacterAbilityConfigs(LoadDataError),
     #[error("failed to load player character weapon configs: {0}")]
     PlayerCharacterWeaponConfigs(LoadDataError),
+    #[error("failed to load gameplay effect configs: {0}")]
+    GameplayEffectConfigs(LoadDataError),
+    #[error("failed to load curve tables: {0}")]
+    CurveTables(LoadDataError),
+    #[error("failed to load gameplay tags: {0}")]
+    GameplayTags(LoadDataError),
+    #[error("failed to load monster spawn tables: {0}")]
+    MonsterSpawns(LoadDataError),
 }
 
 impl GameAssets {
     pub fn load() -> Result<Self, AssetsLoadingError> {
         let data_asset_set = DataAssetSet::load().map_err(AssetsLoadingError::DataAssetSet)?;
+        if data_asset_set.item_data_table.is_none() {
+            error!("the item table is missing, players won't have any items");
+        }
+        if data_asset_set.vehicle_data_table.is_none() {
+            error!("the vehicle table is missing, players can't place vehicles");
+        }
 
         let player_character_configs = fadia_config::blueprint::load_player_character_configs()
             .map_err(AssetsLoadingError::PlayerCharacterConfigs)?;
@@ -42,14 +65,103 @@ impl GameAssets {
             fadia_config::blueprint::load_player_character_weapon_configs()
                 .map_err(AssetsLoadingError::PlayerCharacterWeaponConfigs)?;
 
+        let gameplay_effects = fadia_config::blueprint::load_gameplay_effect_configs()
+            .map_err(AssetsLoadingError::GameplayEffectConfigs)?;
+
+        let curve_tables = Self::load_curve_tables(gameplay_effects.values())
+            .map_err(AssetsLoadingError::CurveTables)?;
+
+        let gameplay_tags = GameplayTagTable::load()
+            .map_err(AssetsLoadingError::GameplayTags)?
+            .unwrap_or_else(|| {
+                error!(
+                    "assets/Config/DefaultGameplayTags.ini is missing, gameplay tags won't be replicated without the tag list of the client"
+                );
+                GameplayTagTable::default()
+            });
+        let gameplay_tag_net_settings = GameplayTagNetSettings::new(gameplay_tags.len());
+
+        let monster_spawns = fadia_config::spawn::load_monster_spawn_tables()
+            .map_err(AssetsLoadingError::MonsterSpawns)?;
+        if monster_spawns.is_empty() {
+            warn!("no monster spawn tables, maps won't have any monsters");
+        }
+
         Ok(Self {
             data_asset_set,
             player_character_configs,
             player_character_abilities,
             player_character_weapons,
+            gameplay_effects,
+            curve_tables,
+            gameplay_tags,
+            gameplay_tag_net_settings,
+            monster_spawns,
         })
     }
 
+    /// Assets without any data, for tests which build their own configs.
+    #[cfg(test)]
+    pub fn empty() -> Self {
+        use fadia_config::dataset::DataTable;
+
+        Self {
+            data_asset_set: DataAssetSet {
+                function_unlock_table: DataTable {
+                    rows: HashMap::new(),
+                },
+                avatar_data_table: DataTable {
+                    rows: HashMap::new(),
+                },
+                item_data_table: None,
+                vehicle_data_table: None,
+            },
+            player_character_configs: HashMap::new(),
+            player_character_abilities: HashMap::new(),
+            player_character_weapons: HashMap::new(),
+            gameplay_effects: HashMap::new(),
+            curve_tables: HashMap::new(),
+            gameplay_tags: GameplayTagTable::default(),
+            gameplay_tag_net_settings: GameplayTagNetSettings::new(0),
+            monster_spawns: HashMap::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_gameplay_effect(mut self, config: GameplayEffectConfig) -> Self {
+        self.gameplay_effects.insert(config.path.clone(), config);
+        self
+    }
+
+    fn load_curve_tables<'cfg>(
+        effects: impl Iterator<Item = &'cfg GameplayEffectConfig>,
+    ) -> Result<HashMap<String, CurveTable>, LoadDataError> {
+        let mut output = HashMap::new();
+        let mut missing = Vec::new();
+
+        for package in effects.flat_map(GameplayEffectConfig::referenced_curve_tables) {
+            if output.contains_key(package) || missing.contains(&package) {
+                continue;
+            }
+
+            match CurveTable::load(package)? {
+                Some(table) => {
+                    output.insert(package.to_string(), table);
+                }
+                None => missing.push(package),
+            }
+        }
+
+        if !missing.is_empty() {
+            warn!(
+                "{} curve tables referenced by gameplay effects are missing, magnitudes scaled by them will use their unscaled values: {missing:?}",
+                missing.len()
+            );
+        }
+
+        Ok(output)
+    }
+
     fn load_abilities<'cfg>(
         chars: impl Iterator<Item = &'cfg PlayerCharacterConfig>,
     ) -> Result<HashMap<ClassReference, PlayerCharacterAbilityConfig>, LoadDataError> {
@@ -101,4 +213,117 @@ impl GameAssets {
 
         self.player_character_weapons.get(&normalized_char_name)
     }
+
+    /// Looks up a gameplay effect by the package path of its blueprint.
+    pub fn get_gameplay_effect_config(&self, path: &str) -> Option<&GameplayEffectConfig> {
+        self.gameplay_effects.get(path)
+    }
+
+    /// Looks up the awakening buffs (`Upgrade/LevelN/Buff_*_LevelN`) of a character
+    /// for every level up to `upgrade_level`. The buffs live next to the character abilities.
+    pub fn get_upgrade_effects(
+        &self,
+        character: &PlayerCharacterConfig,
+        upgrade_level: u32,
+    ) -> Vec<&GameplayEffectConfig> {
+        let Some(abilities_dir) = character
+            .properties
+            .granted_abilities
+            .first()
+            .and_then(|ability| ability.ability_class.asset_path_name.0.rsplit_once('/'))
+            .map(|(dir, _)| format!("{dir}/Upgrade/").to_ascii_lowercase())
+        else {
+            return Vec::new();
+        };
+
+        (1..=upgrade_level)
+            .filter_map(|level| {
+                let dir_suffix = format!("/level{level}/");
+                let name_suffix = format!("_level{level}");
+
+                self.gameplay_effects.iter().find_map(|(path, config)| {
+                    let path = path.to_ascii_lowercase();
+                    let (dir, name) = path.rsplit_once('/')?;
+
+                    (path.starts_with(&abilities_dir)
+                        && format!("{dir}/").ends_with(&dir_suffix)
+                        && name.starts_with("buff_")
+                        && name.ends_with(&name_suffix))
+                    .then_some(config)
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluates `FScalableFloat` at the given level. The engine evaluates a value which
+    /// references a missing curve to 0, but as some curve tables are missing from the dumps,
+    /// the unscaled value is used instead and the unresolved row is logged.
+    pub fn evaluate_scalable_float(&self, value: &ScalableFloat, level: f32) -> f32 {
+        let Some(curve_table) = value.curve.curve_table.as_ref() else {
+            return value.value;
+        };
+
+        curve_table
+            .package_and_index()
+            .and_then(|(package, _)| self.curve_tables.get(package))
+            .and_then(|table| table.rows.get(&value.curve.row_name))
+            .and_then(|curve| curve.eval(level))
+            .map(|scale| value.value * scale)
+            .unwrap_or_else(|| {
+                warn!(
+                    "curve {} of {} can't be evaluated at level {level}, using the unscaled value {}",
+                    value.curve.row_name, curve_table.object_path, value.value
+                );
+                value.value
+            })
+    }
+
+    pub fn get_item_data(&self, item_id: &str) -> Option<&ItemData> {
+        self.data_asset_set
+            .item_data_table
+            .as_ref()?
+            .rows
+            .get(item_id)
+    }
+
+    pub fn get_vehicle_data(&self, vehicle_id: &str) -> Option<&VehicleData> {
+        self.data_asset_set
+            .vehicle_data_table
+            .as_ref()?
+            .rows
+            .get(vehicle_id)
+    }
+
+    /// Looks up the monsters placed on a map, `map` is the package path of its level.
+    pub fn get_monster_spawns(&self, map: &str) -> &[MonsterSpawnPoint] {
+        let map_name = map.rsplit('/').next().unwrap_or(map);
+
+        self.monster_spawns
+            .get(map_name)
+            .map(|table| table.monsters.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn gameplay_tag_net_settings(&self) -> GameplayTagNetSettings {
+        self.gameplay_tag_net_settings
+    }
+
+    /// Creates a replicated container out of tag names. Tags which are absent
+    /// from the tag table can't be replicated and are skipped.
+    pub fn make_gameplay_tag_container<'tag>(
+        &self,
+        tags: impl IntoIterator<Item = &'tag str>,
+    ) -> GameplayTagContainer {
+        GameplayTagContainer::with_tags(
+            self.gameplay_tag_net_settings,
+            tags.into_iter().filter_map(|tag| {
+                let net_index = self.gameplay_tags.net_index(tag);
+                if net_index.is_none() {
+                    warn!("gameplay tag {tag} is missing from the tag table");
+                }
+
+                net_index
+            }),
+        )
+    }
 }
@@ -1,17 +1,261 @@
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
 
+use common::version_profile::VersionProfile;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct ServerConfig {
     pub udp_addr: SocketAddr,
+    #[serde(default)]
+    pub version: VersionProfile,
     pub gameplay: GameplayGlobals,
 }
 
 #[derive(Deserialize)]
 pub struct GameplayGlobals {
+    /// Map players enter when they log in without asking for one, a name or path of `maps`.
     pub map: String,
     pub game_name: String,
     pub redirect_url: String,
     pub player_character: String,
+    /// Package paths of gameplay effects applied to the player character on spawn.
+    #[serde(default)]
+    pub startup_effects: Vec<String>,
+    /// Package path of the gameplay effect applied to characters which tenacity is broken.
+    /// Unset by default, the effect the game applies on break isn't known.
+    #[serde(default)]
+    pub break_effect: Option<String>,
+    /// Gameplay effects applied to player characters by their satiety, e.g. a hunger debuff.
+    #[serde(default)]
+    pub satiety_effects: Vec<SatietyEffect>,
+    /// Items given to new players, counts keyed by item IDs.
+    #[serde(default)]
+    pub starting_items: BTreeMap<String, u32>,
+    /// Effects which grant shields, their shield values are calculated by native code.
+    /// None are configured by default, the values the game grants aren't known.
+    #[serde(default)]
+    pub shield_effects: Vec<ShieldEffect>,
+    #[serde(default)]
+    pub clock: ClockSettings,
+    #[serde(default)]
+    pub weather: WeatherSettings,
+    /// Maps players can travel to, every scope creates a world for a map once it's entered.
+    /// Configs without maps get `map` at the player start used before maps were configurable.
+    #[serde(default)]
+    pub maps: Vec<MapSettings>,
+    /// Dungeons players can enter, every player gets its own instance of a dungeon.
+    /// None are configured by default, the maps of the clone IDs of the game aren't known.
+    #[serde(default)]
+    pub dungeons: Vec<DungeonSettings>,
+    /// Data layers of the maps, keyed by map names, e.g. `XL_map_bigworld_test`.
+    #[serde(default)]
+    pub data_layers: BTreeMap<String, MapDataLayers>,
+    /// Spawns a `GameplayDebugger` replicator for every player, so the in-game debugger
+    /// shows server state. It exposes the state of every actor, keep it off on public servers.
+    #[serde(default)]
+    pub gameplay_debugger: bool,
+    /// Replicates the properties of layouts whose handles are guessed, see
+    /// `ObjectLayout::is_unverified`. A wrong handle desyncs or crashes clients, keep it off
+    /// unless the client is known to match.
+    #[serde(default)]
+    pub replicate_unverified_layouts: bool,
+    /// Only lets in clients with a session ticket issued by the gamesdk server at login.
+    #[serde(default)]
+    pub require_session_ticket: bool,
+    /// Directory of the session tickets, the same as `session_ticket_dir` of the gamesdk server.
+    #[serde(default = "default_session_ticket_dir")]
+    pub session_ticket_dir: PathBuf,
+    /// Seconds a ticket renewed for a `ClientTravel` stays valid.
+    #[serde(default = "default_session_ticket_lifetime")]
+    pub session_ticket_lifetime: u64,
+    /// Adds the AES-GCM packet handler component, which the client must have too. Packets are
+    /// encrypted with the key of the session ticket whose token the client sends, which the
+    /// client only knows if it's patched to read it from the travel URL, see the README.
+    #[serde(default)]
+    pub packet_encryption: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MapSettings {
+    /// Package path of the level, e.g. `/Game/Maps/Map_bigworld/XL_map_bigworld_test`.
+    pub path: String,
+    /// Location at which players enter the map.
+    pub player_start: [f64; 3],
+}
+
+impl MapSettings {
+    /// Short name of the level, e.g. `XL_map_bigworld_test`.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// A dungeon, instances of it are created from the world of its map.
+/// The instance is cleared once every monster of the map is killed.
+#[derive(Deserialize)]
+pub struct DungeonSettings {
+    /// Clone ID of the dungeon, as in the clone IDs of `HottaPlayerState`.
+    pub id: String,
+    /// Map of the instances, a name or path of `maps`.
+    pub map: String,
+    /// Seconds players have to clear the instance.
+    #[serde(default = "default_dungeon_time_limit")]
+    pub time_limit: u64,
+    /// Items granted for clearing the dungeon once per week, counts keyed by item IDs.
+    #[serde(default)]
+    pub rewards: BTreeMap<String, u32>,
+    /// Clone ID of the dungeon which has to be cleared before this one unlocks.
+    #[serde(default)]
+    pub requires: Option<String>,
+}
+
+/// Player start of `XL_map_bigworld_test`, the only map before maps were configurable.
+const LEGACY_PLAYER_START: [f64; 3] = [-79551.5, 158422.4, 4939.1];
+
+impl GameplayGlobals {
+    /// Registers `map` for configs written before `maps` existed.
+    pub fn insert_legacy_map(&mut self) {
+        if self.maps.is_empty() {
+            self.maps.push(MapSettings {
+                path: self.map.clone(),
+                player_start: LEGACY_PLAYER_START,
+            });
+        }
+    }
+
+    /// Looks up a map by its name or package path.
+    pub fn find_map(&self, name_or_path: &str) -> Option<&MapSettings> {
+        let name = name_or_path.rsplit('/').next().unwrap_or(name_or_path);
+        self.maps.iter().find(|map| map.name() == name)
+    }
+
+    pub fn find_dungeon(&self, id: &str) -> Option<&DungeonSettings> {
+        self.dungeons.iter().find(|dungeon| dungeon.id == id)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SatietyEffect {
+    /// The effect is applied while satiety is below this fraction of `SatietyMax`.
+    /// Only the effect with the lowest threshold is applied.
+    pub below: f32,
+    /// Package path of the gameplay effect.
+    pub effect: String,
+}
+
+#[derive(Deserialize)]
+pub struct ShieldEffect {
+    /// Package path of the gameplay effect.
+    pub effect: String,
+    /// Value of the shield as a fraction of `HpMax` of the owner.
+    pub hp_max_ratio: f32,
+    /// Element tags of the damage absorbed by the shield, any damage is absorbed if it's empty.
+    #[serde(default)]
+    pub elements: Vec<String>,
+}
+
+/// Data layers a world starts with, by their names in the level, e.g. `DataLayer_32E40D60...`.
+/// Layers which aren't listed are unloaded.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct MapDataLayers {
+    pub active: Vec<String>,
+    pub loaded: Vec<String>,
+}
+
+/// Times are in seconds, resets happen in the time zone of `time_util::unix_timestamp_ms`.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ClockSettings {
+    /// Length of a full day and night cycle of the game world.
+    pub day_length: u64,
+    /// Hour of the day at which daily and weekly resets happen.
+    pub reset_hour: u64,
+    /// Day of the week of the weekly reset, 0 is Monday.
+    pub weekly_reset_day: u64,
+    /// Interval at which the server time is sent to clients.
+    pub sync_interval: f64,
+    /// Moves the server clock forward, e.g. to check the resets without waiting for them.
+    pub offset: u64,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        Self {
+            day_length: 7200,
+            reset_hour: 5,
+            weekly_reset_day: 0,
+            sync_interval: 10.0,
+            offset: 0,
+        }
+    }
+}
+
+/// Times are in seconds of server time, so every world shows the same weather.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct WeatherSettings {
+    /// Interval at which a new preset is picked from the table.
+    pub change_interval: u64,
+    /// Time over which the previous weather blends into the new one.
+    pub transition_time: u64,
+    /// Length of a season in days, seasons go from spring to winter.
+    pub season_length: u64,
+    /// Name of a preset shown instead of the scheduled weather, e.g. for events.
+    pub forced_preset: Option<String>,
+    pub presets: Vec<WeatherPreset>,
+}
+
+/// Parameters of `UltraDynamicWeather`, from 0 to 10 like in the editor.
+#[derive(Deserialize)]
+pub struct WeatherPreset {
+    pub name: String,
+    /// Index of the weather preset of the client, `UltraDynamicWeather::weater_index_replicate`.
+    pub index: u32,
+    /// Relative chance of the preset to be picked.
+    #[serde(default = "default_weather_weight")]
+    pub weight: f32,
+    /// Seasons in which the preset can be picked, 0 is spring. Empty means every season.
+    #[serde(default)]
+    pub seasons: Vec<u32>,
+    #[serde(default)]
+    pub cloud_coverage: f64,
+    #[serde(default)]
+    pub fog: f64,
+    #[serde(default)]
+    pub wind_intensity: f64,
+    #[serde(default)]
+    pub rain: f64,
+    #[serde(default)]
+    pub snow: f64,
+    #[serde(default)]
+    pub thunder_lightning: f64,
+}
+
+impl Default for WeatherSettings {
+    fn default() -> Self {
+        Self {
+            change_interval: 1800,
+            transition_time: 120,
+            season_length: 7,
+            forced_preset: None,
+            presets: Vec::new(),
+        }
+    }
+}
+
+fn default_weather_weight() -> f32 {
+    1.0
+}
+
+fn default_session_ticket_dir() -> PathBuf {
+    PathBuf::from("session_tickets")
+}
+
+fn default_session_ticket_lifetime() -> u64 {
+    120
+}
+
+fn default_dungeon_time_limit() -> u64 {
+    900
 }
@@ -0,0 +1,657 @@
+use std::collections::HashMap;
+
+use bitstream_io::{BitRead, BitWrite};
+use fadia_config::blueprint::PlayerCharacterAbilityConfig;
+use fadia_engine::util::{InBitReader, OutBitWriter, ReadPrimitivesExt, WritePrimitivesExt};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::{
+    assets::GameAssets,
+    logic::{
+        layout::{AbilitySystemComponent, GameplayAbilitySpec, HTAttributeSet},
+        rpc::RpcArgument,
+    },
+};
+
+use super::{CHARGE_PER_ACTIVATION, GameplayEffectSpec};
+
+/// Identifies an action predicted by a client, `FPredictionKey`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PredictionKey {
+    pub current: i16,
+    pub base: i16,
+    pub is_server_initiated: bool,
+}
+
+/// `FGameplayAbilityActivationInfo`, only the replicated part.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GameplayAbilityActivationInfo {
+    pub can_be_ended_by_other_instance: bool,
+    pub prediction_key_when_activated: PredictionKey,
+}
+
+/// `EAbilityGenericReplicatedEvent::Type`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum EAbilityGenericReplicatedEvent {
+    #[default]
+    GenericConfirm = 0,
+    GenericCancel,
+    InputPressed,
+    InputReleased,
+    GenericSignalFromClient,
+    GenericSignalFromServer,
+    GameCustom1,
+    GameCustom2,
+    GameCustom3,
+    GameCustom4,
+    GameCustom5,
+    GameCustom6,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AbilityActivationError {
+    #[error("character doesn't have an ability system")]
+    NoAbilitySystem,
+    #[error("character is dead")]
+    Dead,
+    #[error("ability with handle {0} isn't granted")]
+    UnknownAbility(u32),
+    #[error("activation is blocked by tag {0}")]
+    Blocked(String),
+    #[error("ability is on cooldown {0}")]
+    Cooldown(String),
+    #[error("cost {0} can't be afforded")]
+    Cost(String),
+    #[error("charge isn't full")]
+    Charge,
+}
+
+/// Outcome of a successful activation, which has to be applied by the caller.
+#[derive(Debug)]
+pub struct CommittedAbility {
+    pub config: &'static PlayerCharacterAbilityConfig,
+    /// Cost effect applied to the owner.
+    pub cost: Option<GameplayEffectSpec>,
+    /// Seconds the cooldown tags and the ability itself are on cooldown.
+    pub cooldown_duration: f32,
+}
+
+/// An ability activated by the client and confirmed by the server.
+#[derive(Debug)]
+pub struct AbilityActivation {
+    pub prediction_key: PredictionKey,
+    pub replicated_events: Vec<EAbilityGenericReplicatedEvent>,
+}
+
+/// Server-side activation state of the abilities granted to an `AbilitySystemComponent`.
+#[derive(Debug, Default)]
+pub struct AbilityActivations {
+    /// Configs of the granted abilities, keyed by spec handles.
+    abilities: HashMap<u32, &'static PlayerCharacterAbilityConfig>,
+    last_handle: u32,
+    active: HashMap<u32, AbilityActivation>,
+}
+
+impl EAbilityGenericReplicatedEvent {
+    /// `FMath::CeilLogTwo(EAbilityGenericReplicatedEvent::MAX)`
+    const NET_SERIALIZE_BITS: u32 = 4;
+}
+
+impl AbilityActivations {
+    /// Registers a granted ability and returns a new spec handle for it,
+    /// `FGameplayAbilitySpecHandle::GenerateNewHandle`.
+    pub fn grant(&mut self, config: &'static PlayerCharacterAbilityConfig) -> u32 {
+        self.last_handle += 1;
+        self.abilities.insert(self.last_handle, config);
+        self.last_handle
+    }
+
+    pub fn ability_config(&self, handle: u32) -> Option<&'static PlayerCharacterAbilityConfig> {
+        self.abilities.get(&handle).copied()
+    }
+
+    pub fn is_active(&self, handle: u32) -> bool {
+        self.active.contains_key(&handle)
+    }
+
+    /// Configs of the abilities which are active.
+    pub fn active_abilities(&self) -> impl Iterator<Item = &'static PlayerCharacterAbilityConfig> {
+        self.active
+            .keys()
+            .filter_map(|handle| self.abilities.get(handle).copied())
+    }
+
+    /// Ends every active ability, e.g. when the owner dies.
+    pub fn cancel_all(&mut self) {
+        self.active.clear();
+    }
+}
+
+impl AbilitySystemComponent {
+    pub fn find_ability_spec(&self, handle: u32) -> Option<&GameplayAbilitySpec> {
+        self.activatable_abilities
+            .iter()
+            .map(|(_, spec)| spec)
+            .find(|spec| spec.handle.0.get() == handle)
+    }
+
+    /// Sets the levels of the granted abilities, changed specs are replicated.
+    pub fn set_ability_levels(&mut self, level_of: impl Fn(&str) -> u32) {
+        for (&handle, config) in self.ability_activations.abilities.iter() {
+            let level = level_of(config.ability_name());
+
+            let Some(element_id) = self
+                .activatable_abilities
+                .iter()
+                .find(|(_, spec)| spec.handle.0.get() == handle && spec.level.get() != level)
+                .map(|(element_id, _)| element_id)
+            else {
+                continue;
+            };
+
+            if let Some(spec) = self.activatable_abilities.get_mut(element_id) {
+                spec.level.set_value(level);
+            }
+        }
+    }
+
+    /// Checks tags and cost of an ability and commits it, like `InternalTryActivateAbility`
+    /// does for a locally predicted ability. Cooldowns which aren't granted by effects are
+    /// tracked by the `CDManager` of the player, the caller starts them, see `CommittedAbility`.
+    pub fn try_activate_ability(
+        &mut self,
+        attributes: &HTAttributeSet,
+        handle: u32,
+        prediction_key: PredictionKey,
+        assets: &'static GameAssets,
+    ) -> Result<CommittedAbility, AbilityActivationError> {
+        if self.is_dead() {
+            return Err(AbilityActivationError::Dead);
+        }
+
+        let (Some(spec), Some(&config)) = (
+            self.find_ability_spec(handle),
+            self.ability_activations.abilities.get(&handle),
+        ) else {
+            return Err(AbilityActivationError::UnknownAbility(handle));
+        };
+
+        let level = spec.level.get() as f32;
+        let properties = &config.properties;
+
+        if let Some(tag) = properties
+            .activation_blocked_tags
+            .iter()
+            .find(|tag| self.active_gameplay_effects.has_matching_tag(tag))
+        {
+            return Err(AbilityActivationError::Blocked(tag.clone()));
+        }
+
+        if let Some(tag) = properties
+            .cooldown_tags
+            .iter()
+            .find(|tag| self.active_gameplay_effects.has_matching_tag(tag))
+        {
+            return Err(AbilityActivationError::Cooldown(tag.clone()));
+        }
+
+        let cost = properties
+            .cost_gameplay_effect_class
+            .as_ref()
+            .and_then(|class| class.package_and_index())
+            .and_then(|(package, _)| assets.get_gameplay_effect_config(package))
+            .map(|def| GameplayEffectSpec::new(def, level, self.owner_actor.get()));
+
+        if let Some(cost) = cost.as_ref()
+            && !self.can_apply_attribute_modifiers(attributes, cost, assets)
+        {
+            return Err(AbilityActivationError::Cost(cost.def.path.clone()));
+        }
+
+        let has_charge_cost = properties.has_charge_cost();
+        if has_charge_cost && !self.has_full_charge(attributes) {
+            return Err(AbilityActivationError::Charge);
+        }
+
+        if has_charge_cost {
+            self.consume_charge();
+        } else {
+            self.gain_charge(attributes, CHARGE_PER_ACTIVATION);
+        }
+
+        // Instanced abilities are retriggered, the previous activation is replaced.
+        self.ability_activations.active.insert(
+            handle,
+            AbilityActivation {
+                prediction_key,
+                replicated_events: Vec::new(),
+            },
+        );
+
+        Ok(CommittedAbility {
+            config,
+            cost,
+            cooldown_duration: assets.evaluate_scalable_float(&properties.cooldown_duration, level),
+        })
+    }
+
+    /// Ends an active ability on request of the client. Requests which refer
+    /// to a previous activation of the ability are ignored.
+    pub fn end_ability(
+        &mut self,
+        handle: u32,
+        activation_info: &GameplayAbilityActivationInfo,
+    ) -> Option<AbilityActivation> {
+        let active = &mut self.ability_activations.active;
+
+        if active.get(&handle)?.prediction_key.current
+            != activation_info.prediction_key_when_activated.current
+        {
+            return None;
+        }
+
+        active.remove(&handle)
+    }
+
+    /// Records a generic event sent by the client for an active ability.
+    pub fn set_replicated_event(
+        &mut self,
+        handle: u32,
+        original_prediction_key: PredictionKey,
+        event: EAbilityGenericReplicatedEvent,
+    ) -> bool {
+        let Some(activation) =
+            self.ability_activations
+                .active
+                .get_mut(&handle)
+                .filter(|activation| {
+                    activation.prediction_key.current == original_prediction_key.current
+                })
+        else {
+            return false;
+        };
+
+        activation.replicated_events.push(event);
+        true
+    }
+}
+
+impl RpcArgument for PredictionKey {
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        let valid = self.current > 0;
+        let has_base = valid && self.base > 0;
+
+        w.write_bit(valid)?;
+        w.write_bit(has_base)?;
+        w.write_bit(self.is_server_initiated)?;
+
+        if valid {
+            w.write_i16(self.current)?;
+
+            if has_base {
+                w.write_i16(self.base)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(r: &mut InBitReader) -> std::io::Result<Self> {
+        let valid = r.read_bit()?;
+        let has_base = r.read_bit()?;
+        let is_server_initiated = r.read_bit()?;
+
+        let mut key = Self {
+            is_server_initiated,
+            ..Default::default()
+        };
+
+        if valid {
+            key.current = r.read_u16()? as i16;
+
+            if has_base {
+                key.base = r.read_u16()? as i16;
+            }
+        }
+
+        Ok(key)
+    }
+}
+
+impl RpcArgument for GameplayAbilityActivationInfo {
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        w.write_bit(self.can_be_ended_by_other_instance)?;
+        self.prediction_key_when_activated.serialize(w)
+    }
+
+    fn deserialize(r: &mut InBitReader) -> std::io::Result<Self> {
+        Ok(Self {
+            can_be_ended_by_other_instance: r.read_bit()?,
+            prediction_key_when_activated: PredictionKey::deserialize(r)?,
+        })
+    }
+}
+
+impl RpcArgument for EAbilityGenericReplicatedEvent {
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        w.write(Self::NET_SERIALIZE_BITS, u8::from(*self))
+    }
+
+    fn deserialize(r: &mut InBitReader) -> std::io::Result<Self> {
+        let value = r.read::<u8>(Self::NET_SERIALIZE_BITS)?;
+
+        Self::try_from(value).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid replicated event: {value}"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fadia_config::{
+        blueprint::{
+            EBreakableLevel, EGameplayEffectDurationType, EGameplayModOp, GameplayEffectConfig,
+            GameplayEffectMagnitude, GameplayModifierInfo, ObjectReference,
+            PlayerCharacterAbilityProperties, ScalableFloat,
+        },
+        spawn::CharacterStats,
+    };
+    use fadia_engine::{
+        FNetworkGUID,
+        replication::{
+            FastArraySerializer,
+            property::{GameplayTagContainer, PropertyArray, PropertyObject, PropertyU32},
+        },
+    };
+
+    use crate::logic::layout::GameplayAbilityHandle;
+
+    use super::*;
+
+    const COST_EFFECT: &str = "/Game/Test/GE_Cost";
+
+    fn effect(
+        path: &str,
+        modifiers: Vec<GameplayModifierInfo>,
+        granted_tags: Vec<String>,
+    ) -> GameplayEffectConfig {
+        GameplayEffectConfig {
+            name: String::from("GE_Test"),
+            default_object_name: String::from("Default__GE_Test_C"),
+            path: path.to_string(),
+            parent: None,
+            root_class: String::from("GameplayEffect"),
+            duration_policy: EGameplayEffectDurationType::Infinite,
+            duration_magnitude: GameplayEffectMagnitude::ScalableFloat(ScalableFloat::default()),
+            period: ScalableFloat::default(),
+            execute_periodic_effect_on_application: false,
+            stacking_type: Default::default(),
+            stack_limit_count: 0,
+            stack_duration_refresh_policy: Default::default(),
+            stack_period_reset_policy: Default::default(),
+            stack_expiration_policy: Default::default(),
+            modifiers,
+            executions: Vec::new(),
+            asset_tags: Vec::new(),
+            granted_tags,
+            attack_break_level: EBreakableLevel::None,
+        }
+    }
+
+    fn stamina(amount: f32) -> GameplayModifierInfo {
+        GameplayModifierInfo {
+            attribute: String::from("Stamina"),
+            op: EGameplayModOp::AddBase,
+            magnitude: GameplayEffectMagnitude::ScalableFloat(ScalableFloat {
+                value: amount,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Assets with a cost effect which takes `cost` of the `Stamina` attribute.
+    fn assets(cost: f32) -> &'static GameAssets {
+        let cost = effect(COST_EFFECT, vec![stamina(-cost)], Vec::new());
+        Box::leak(Box::new(GameAssets::empty().with_gameplay_effect(cost)))
+    }
+
+    fn reference(object_name: &str, object_path: &str) -> ObjectReference {
+        ObjectReference {
+            object_name: object_name.to_string(),
+            object_path: object_path.to_string(),
+        }
+    }
+
+    /// A character with a single granted ability, returns its spec handle.
+    fn character(
+        properties: PlayerCharacterAbilityProperties,
+    ) -> (AbilitySystemComponent, HTAttributeSet, u32) {
+        let stats = CharacterStats {
+            hp_max: 1000.0,
+            ..Default::default()
+        };
+        let config = Box::leak(Box::new(PlayerCharacterAbilityConfig {
+            name: String::from("Default__GA_Test_C"),
+            properties,
+        }));
+
+        let mut ability_activations = AbilityActivations::default();
+        let handle = ability_activations.grant(config);
+
+        let mut activatable_abilities = FastArraySerializer::default();
+        activatable_abilities.push(GameplayAbilitySpec {
+            handle: GameplayAbilityHandle(PropertyU32::new(handle)),
+            ability: PropertyObject::default(),
+            level: PropertyU32::new(1),
+            input_id: PropertyU32::default(),
+            source_object: PropertyObject::default(),
+            gameplay_tags: GameplayTagContainer::new(
+                GameAssets::empty().gameplay_tag_net_settings(),
+            ),
+            replicated_instances: PropertyArray::default(),
+        });
+
+        (
+            AbilitySystemComponent::new(
+                FNetworkGUID::default(),
+                FNetworkGUID::default(),
+                &stats,
+                activatable_abilities,
+                ability_activations,
+            ),
+            HTAttributeSet::new(&stats),
+            handle,
+        )
+    }
+
+    fn key(current: i16) -> PredictionKey {
+        PredictionKey {
+            current,
+            ..Default::default()
+        }
+    }
+
+    fn grant_tag(asc: &mut AbilitySystemComponent, attributes: &mut HTAttributeSet, tag: &str) {
+        let def = Box::leak(Box::new(effect(
+            "/Game/Test/GE_Tag",
+            Vec::new(),
+            vec![tag.to_string()],
+        )));
+
+        asc.apply_gameplay_effect_spec(
+            attributes,
+            GameplayEffectSpec::new(def, 1.0, FNetworkGUID::default()),
+            FNetworkGUID::default(),
+            &GameAssets::empty(),
+            0.0,
+        );
+    }
+
+    #[test]
+    fn activates_and_commits_the_ability() {
+        let (mut asc, attributes, handle) = character(PlayerCharacterAbilityProperties {
+            cooldown_duration: ScalableFloat {
+                value: 4.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let committed = asc
+            .try_activate_ability(&attributes, handle, key(1), assets(0.0))
+            .unwrap();
+
+        assert_eq!(committed.cooldown_duration, 4.0);
+        assert!(committed.cost.is_none());
+        assert!(asc.ability_activations.is_active(handle));
+    }
+
+    #[test]
+    fn rejects_unknown_abilities_and_dead_owners() {
+        let (mut asc, attributes, handle) = character(Default::default());
+
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle + 1, key(1), assets(0.0)),
+            Err(AbilityActivationError::UnknownAbility(_))
+        ));
+
+        asc.hp_current.set_value(0.0);
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle, key(1), assets(0.0)),
+            Err(AbilityActivationError::Dead)
+        ));
+    }
+
+    #[test]
+    fn blocked_tags_prevent_activation() {
+        let (mut asc, mut attributes, handle) = character(PlayerCharacterAbilityProperties {
+            activation_blocked_tags: vec![String::from("State.Stun")],
+            ..Default::default()
+        });
+
+        grant_tag(&mut asc, &mut attributes, "State.Stun.Heavy");
+
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle, key(1), assets(0.0)),
+            Err(AbilityActivationError::Blocked(tag)) if tag == "State.Stun"
+        ));
+        assert!(!asc.ability_activations.is_active(handle));
+    }
+
+    #[test]
+    fn cooldown_tags_prevent_activation() {
+        let (mut asc, mut attributes, handle) = character(PlayerCharacterAbilityProperties {
+            cooldown_tags: vec![String::from("CD.Skill.Test")],
+            ..Default::default()
+        });
+
+        grant_tag(&mut asc, &mut attributes, "CD.Skill");
+        assert!(
+            asc.try_activate_ability(&attributes, handle, key(1), assets(0.0))
+                .is_ok()
+        );
+
+        grant_tag(&mut asc, &mut attributes, "CD.Skill.Test");
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle, key(2), assets(0.0)),
+            Err(AbilityActivationError::Cooldown(tag)) if tag == "CD.Skill.Test"
+        ));
+    }
+
+    #[test]
+    fn costs_have_to_be_affordable() {
+        let (mut asc, mut attributes, handle) = character(PlayerCharacterAbilityProperties {
+            cost_gameplay_effect_class: Some(reference(
+                "BlueprintGeneratedClass'GE_Cost_C'",
+                &format!("{COST_EFFECT}.1"),
+            )),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle, key(1), assets(10.0)),
+            Err(AbilityActivationError::Cost(path)) if path == COST_EFFECT
+        ));
+
+        let restore = Box::leak(Box::new(GameplayEffectConfig {
+            duration_policy: EGameplayEffectDurationType::Instant,
+            ..effect("/Game/Test/GE_Stamina", vec![stamina(10.0)], Vec::new())
+        }));
+        asc.apply_gameplay_effect_spec(
+            &mut attributes,
+            GameplayEffectSpec::new(restore, 1.0, FNetworkGUID::default()),
+            FNetworkGUID::default(),
+            &GameAssets::empty(),
+            0.0,
+        );
+
+        let committed = asc
+            .try_activate_ability(&attributes, handle, key(1), assets(10.0))
+            .unwrap();
+        assert_eq!(committed.cost.unwrap().def.path, COST_EFFECT);
+    }
+
+    #[test]
+    fn ultimates_need_and_consume_full_charge() {
+        let (mut asc, attributes, handle) = character(PlayerCharacterAbilityProperties {
+            additional_costs: vec![Some(reference(
+                "HTAbilityChargeCost'Default__HTAbilityChargeCost'",
+                "/Script/HT.Default__HTAbilityChargeCost",
+            ))],
+            ..Default::default()
+        });
+
+        assert!(asc.has_full_charge(&attributes));
+        assert!(
+            asc.try_activate_ability(&attributes, handle, key(1), assets(0.0))
+                .is_ok()
+        );
+        assert_eq!(asc.charge_current.get(), 0.0);
+
+        assert!(matches!(
+            asc.try_activate_ability(&attributes, handle, key(2), assets(0.0)),
+            Err(AbilityActivationError::Charge)
+        ));
+    }
+
+    #[test]
+    fn other_abilities_gain_charge() {
+        let (mut asc, attributes, handle) = character(Default::default());
+        asc.consume_charge();
+
+        asc.try_activate_ability(&attributes, handle, key(1), assets(0.0))
+            .unwrap();
+
+        assert_eq!(asc.charge_current.get(), CHARGE_PER_ACTIVATION);
+    }
+
+    #[test]
+    fn ends_only_the_current_activation() {
+        let (mut asc, attributes, handle) = character(Default::default());
+
+        asc.try_activate_ability(&attributes, handle, key(1), assets(0.0))
+            .unwrap();
+        asc.try_activate_ability(&attributes, handle, key(2), assets(0.0))
+            .unwrap();
+
+        let stale = GameplayAbilityActivationInfo {
+            prediction_key_when_activated: key(1),
+            ..Default::default()
+        };
+        assert!(asc.end_ability(handle, &stale).is_none());
+        assert!(asc.ability_activations.is_active(handle));
+
+        let current = GameplayAbilityActivationInfo {
+            prediction_key_when_activated: key(2),
+            ..Default::default()
+        };
+        let ended = asc.end_ability(handle, &current).unwrap();
+        assert_eq!(ended.prediction_key, key(2));
+        assert!(!asc.ability_activations.is_active(handle));
+        assert!(asc.end_ability(handle, &current).is_none());
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use fadia_config::blueprint::EGameplayModOp;
+use fadia_engine::replication::property::PropertyF32;
+
+use crate::logic::layout::HTAttributeSet;
+
+/// Stats which are replicated through `HTAttributeSet` as a `*_base`/`*_cur` pair.
+///
+/// Gameplay effects address each stat by a family of attribute names, e.g. for `Atk`:
+/// `AtkBase` modifies the base value, `AtkAdd` is a flat bonus, `AtkUp` is a percentage
+/// bonus, and `Atk` modifies the final value:
+///
+/// `atk_cur = Atk((AtkBase(atk_base) + AtkAdd) * (1 + AtkUp))`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharacterStat {
+    HpMax,
+    Atk,
+    Crit,
+    CritDamage,
+    ChargeGetEfficiency,
+    ChargeMax,
+    Def,
+    UnbalAccrueEfficiency,
+    UnbalReduceNatur,
+    TenacityRecoverSpeed,
+    TenacityResetTime,
+    SatietyMax,
+}
+
+impl CharacterStat {
+    pub const ALL: [Self; 12] = [
+        Self::HpMax,
+        Self::Atk,
+        Self::Crit,
+        Self::CritDamage,
+        Self::ChargeGetEfficiency,
+        Self::ChargeMax,
+        Self::Def,
+        Self::UnbalAccrueEfficiency,
+        Self::UnbalReduceNatur,
+        Self::TenacityRecoverSpeed,
+        Self::TenacityResetTime,
+        Self::SatietyMax,
+    ];
+
+    pub fn attribute_name(self) -> &'static str {
+        match self {
+            Self::HpMax => "HPMax",
+            Self::Atk => "Atk",
+            Self::Crit => "Crit",
+            Self::CritDamage => "CritDamage",
+            Self::ChargeGetEfficiency => "ChargeGetEfficiency",
+            Self::ChargeMax => "ChargeMax",
+            Self::Def => "Def",
+            Self::UnbalAccrueEfficiency => "UnbalAccrueEfficiency",
+            Self::UnbalReduceNatur => "UnbalReduceNatur",
+            Self::TenacityRecoverSpeed => "TenacityRecoverSpeed",
+            Self::TenacityResetTime => "TenacityResetTime",
+            Self::SatietyMax => "SatietyMax",
+        }
+    }
+
+    /// Finds the stat which base value is addressed by `attribute`, e.g. `AtkBase`.
+    pub fn from_base_attribute(attribute: &str) -> Option<Self> {
+        let name = attribute.strip_suffix("Base")?;
+        Self::ALL
+            .into_iter()
+            .find(|stat| stat.attribute_name() == name)
+    }
+
+    /// Returns the `(base, current)` properties of the stat.
+    pub fn properties(self, set: &HTAttributeSet) -> (&PropertyF32, &PropertyF32) {
+        match self {
+            Self::HpMax => (&set.hp_max_base, &set.hp_max_cur),
+            Self::Atk => (&set.atk_base, &set.atk_cur),
+            Self::Crit => (&set.crit_base, &set.crit_cur),
+            Self::CritDamage => (&set.crit_damage_base, &set.crit_damage_cur),
+            Self::ChargeGetEfficiency => (
+                &set.charge_get_efficiency_base,
+                &set.charge_get_efficiency_cur,
+            ),
+            Self::ChargeMax => (&set.charge_max_base, &set.charge_max_cur),
+            Self::Def => (&set.def_base, &set.def_cur),
+            Self::UnbalAccrueEfficiency => (
+                &set.unbal_accrue_efficiency_base,
+                &set.unbal_accrue_efficiency_cur,
+            ),
+            Self::UnbalReduceNatur => (&set.unbal_reduce_natur_base, &set.unbal_reduce_natur_cur),
+            Self::TenacityRecoverSpeed => (
+                &set.tenacity_recover_speed_base,
+                &set.tenacity_recover_speed_cur,
+            ),
+            Self::TenacityResetTime => {
+                (&set.tenacity_reset_time_base, &set.tenacity_reset_time_cur)
+            }
+            Self::SatietyMax => (&set.satiety_max_base, &set.satiety_max_cur),
+        }
+    }
+
+    pub fn properties_mut(self, set: &mut HTAttributeSet) -> (&mut PropertyF32, &mut PropertyF32) {
+        match self {
+            Self::HpMax => (&mut set.hp_max_base, &mut set.hp_max_cur),
+            Self::Atk => (&mut set.atk_base, &mut set.atk_cur),
+            Self::Crit => (&mut set.crit_base, &mut set.crit_cur),
+            Self::CritDamage => (&mut set.crit_damage_base, &mut set.crit_damage_cur),
+            Self::ChargeGetEfficiency => (
+                &mut set.charge_get_efficiency_base,
+                &mut set.charge_get_efficiency_cur,
+            ),
+            Self::ChargeMax => (&mut set.charge_max_base, &mut set.charge_max_cur),
+            Self::Def => (&mut set.def_base, &mut set.def_cur),
+            Self::UnbalAccrueEfficiency => (
+                &mut set.unbal_accrue_efficiency_base,
+                &mut set.unbal_accrue_efficiency_cur,
+            ),
+            Self::UnbalReduceNatur => (
+                &mut set.unbal_reduce_natur_base,
+                &mut set.unbal_reduce_natur_cur,
+            ),
+            Self::TenacityRecoverSpeed => (
+                &mut set.tenacity_recover_speed_base,
+                &mut set.tenacity_recover_speed_cur,
+            ),
+            Self::TenacityResetTime => (
+                &mut set.tenacity_reset_time_base,
+                &mut set.tenacity_reset_time_cur,
+            ),
+            Self::SatietyMax => (&mut set.satiety_max_base, &mut set.satiety_max_cur),
+        }
+    }
+}
+
+/// Mirrors `FAggregatorModChannel::EvaluateWithBase`.
+#[derive(Debug, Clone)]
+pub struct AttributeAggregator {
+    add_base: f32,
+    multiply_additive: f32,
+    divide_additive: f32,
+    multiply_compound: f32,
+    add_final: f32,
+    override_value: Option<f32>,
+}
+
+impl Default for AttributeAggregator {
+    fn default() -> Self {
+        Self {
+            add_base: 0.0,
+            multiply_additive: 0.0,
+            divide_additive: 0.0,
+            multiply_compound: 1.0,
+            add_final: 0.0,
+            override_value: None,
+        }
+    }
+}
+
+impl AttributeAggregator {
+    pub fn add_modifier(&mut self, op: EGameplayModOp, magnitude: f32) {
+        match op {
+            EGameplayModOp::AddBase => self.add_base += magnitude,
+            EGameplayModOp::MultiplyAdditive => self.multiply_additive += magnitude,
+            EGameplayModOp::DivideAdditive => self.divide_additive += magnitude,
+            EGameplayModOp::MultiplyCompound => self.multiply_compound *= magnitude,
+            EGameplayModOp::AddFinal => self.add_final += magnitude,
+            EGameplayModOp::Override => self.override_value = Some(magnitude),
+        }
+    }
+
+    pub fn evaluate(&self, base: f32) -> f32 {
+        if let Some(value) = self.override_value {
+            return value;
+        }
+
+        let divisor = 1.0 + self.divide_additive;
+        let divisor = if divisor.abs() > f32::EPSILON {
+            divisor
+        } else {
+            1.0
+        };
+
+        ((base + self.add_base) * (1.0 + self.multiply_additive) / divisor) * self.multiply_compound
+            + self.add_final
+    }
+}
+
+/// Applies a single modifier to a value right away, the way instant effects modify base values.
+pub fn apply_modifier(value: f32, op: EGameplayModOp, magnitude: f32) -> f32 {
+    let mut aggregator = AttributeAggregator::default();
+    aggregator.add_modifier(op, magnitude);
+    aggregator.evaluate(value)
+}
+
+/// Current values of every attribute, computed from base values and active modifiers.
+#[derive(Debug, Default, Clone)]
+pub struct AttributeValues {
+    pub stats: HashMap<CharacterStat, f32>,
+    /// Attributes outside of `HTAttributeSet`, e.g. `DamageUpGeneralBase` or `DefIgnore`.
+    pub extra: HashMap<String, f32>,
+}
+
+impl AttributeValues {
+    pub fn stat(&self, stat: CharacterStat) -> f32 {
+        self.stats.get(&stat).copied().unwrap_or_default()
+    }
+
+    pub fn extra(&self, attribute: &str) -> f32 {
+        self.extra.get(attribute).copied().unwrap_or_default()
+    }
+
+    pub fn evaluate(
+        base_set: &HTAttributeSet,
+        extra_bases: &HashMap<String, f32>,
+        aggregators: &HashMap<String, AttributeAggregator>,
+    ) -> Self {
+        let extra_base = |attribute: &str| extra_bases.get(attribute).copied().unwrap_or_default();
+        let evaluate = |attribute: &str, base: f32| {
+            aggregators
+                .get(attribute)
+                .map(|aggregator| aggregator.evaluate(base))
+                .unwrap_or(base)
+        };
+
+        let stats = CharacterStat::ALL
+            .into_iter()
+            .map(|stat| {
+                let name = stat.attribute_name();
+                let (base, _) = stat.properties(base_set);
+
+                let base = evaluate(&format!("{name}Base"), base.get());
+                let (flat, percent) = (format!("{name}Add"), format!("{name}Up"));
+                let flat = evaluate(&flat, extra_base(&flat));
+                let percent = evaluate(&percent, extra_base(&percent));
+
+                (stat, evaluate(name, (base + flat) * (1.0 + percent)))
+            })
+            .collect();
+
+        let extra = aggregators
+            .keys()
+            .chain(extra_bases.keys())
+            .map(|attribute| {
+                (
+                    attribute.clone(),
+                    evaluate(attribute, extra_base(attribute)),
+                )
+            })
+            .collect();
+
+        Self { stats, extra }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::logic::layout::{AbilitySystemComponent, HTAttributeSet};
+
+use super::attribute::CharacterStat;
+
+/// Charge gained by the attacker for every hit.
+pub const CHARGE_PER_HIT: f32 = 0.5;
+/// Charge gained for every activated ability which doesn't consume charge.
+pub const CHARGE_PER_ACTIVATION: f32 = 1.0;
+
+impl AbilitySystemComponent {
+    /// Adds charge to the owner, scaled by its `ChargeGetEfficiency` and capped by `ChargeMax`.
+    pub fn gain_charge(&mut self, attributes: &HTAttributeSet, amount: f32) {
+        let efficiency = CharacterStat::ChargeGetEfficiency
+            .properties(attributes)
+            .1
+            .get();
+        let charge = self.charge_current.get() + amount * efficiency.max(0.0);
+
+        self.charge_current
+            .set_value(charge.min(Self::max_charge(attributes)));
+    }
+
+    /// Ultimates can be activated only with full charge, which they consume.
+    pub fn has_full_charge(&self, attributes: &HTAttributeSet) -> bool {
+        self.charge_current.get() >= Self::max_charge(attributes)
+    }
+
+    pub fn consume_charge(&mut self) {
+        self.charge_current.set_value(0.0);
+    }
+
+    fn max_charge(attributes: &HTAttributeSet) -> f32 {
+        CharacterStat::ChargeMax.properties(attributes).1.get()
+    }
+}
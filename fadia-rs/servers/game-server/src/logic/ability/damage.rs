@@ -0,0 +1,571 @@
+use fadia_config::{
+    blueprint::{EBreakableLevel, EGameplayModOp, GameplayEffectConfig, GameplayEffectExecution},
+    dataset::gameplay_tag_matches,
+};
+use fadia_engine::FNetworkGUID;
+use tracing::debug;
+
+use crate::{
+    assets::GameAssets,
+    logic::layout::{AbilitySystemComponent, HTAttributeSet},
+};
+
+use super::{
+    GameplayEffectSpec,
+    attribute::{AttributeValues, CharacterStat},
+    shield::element_tags,
+};
+
+/// Asset tag of the effects which deal damage, other effects of a container are hit reactions.
+const DAMAGE_TAG: &str = "State.Damage";
+/// Parent of the asset tags which select the hit reaction of the target, e.g. `State.AniState.Back`.
+const HIT_REACTION_TAG: &str = "State.AniState";
+/// Asset tag of the damage effects which bypass shields.
+const IGNORE_SHIELD_TAG: &str = "Event.Common.IgnoreShield";
+/// Unverified: defense at which half of the attack damage is absorbed. The defense
+/// formula of the client's damage executions isn't known, this constant is a guess.
+const DEFENSE_CONSTANT: f32 = 1000.0;
+/// Attributes read by the client's damage executions which formulas aren't known,
+/// e.g. how much of the target's defense `DefIgnore` ignores. Buffs and executions
+/// which modify them don't change the damage calculated by the server.
+const UNSUPPORTED_DAMAGE_ATTRIBUTES: [&str; 4] = [
+    "DamageUpGeneralBase",
+    "DamageUpGeneralAdd",
+    "DefIgnore",
+    "CoefModify",
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum HitReportError {
+    #[error("attacker doesn't have an ability system")]
+    NoAbilitySystem,
+    #[error("target {0:?} can't be damaged")]
+    InvalidTarget(FNetworkGUID),
+    #[error("ability {0} isn't active")]
+    InactiveAbility(u32),
+    #[error("ability {0} doesn't hit with {1}")]
+    UnknownDamageRange(u32, String),
+}
+
+/// Damage calculation of an effect, selected by its execution class.
+/// Damage effects without executions inherit the attack-based one from `GE_DamageBase`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DamageExecution {
+    Attack,
+    MaxHp,
+    CurrentHp,
+    Fixed,
+}
+
+/// Damage of a single effect, evaluated with the attributes of the attacker.
+#[derive(Debug, Clone)]
+struct DamageSpec {
+    execution: DamageExecution,
+    coefficient: f32,
+    flat: f32,
+}
+
+/// A hit reported by the client of the attacker, ready to be received by the target.
+#[derive(Debug, Clone)]
+pub struct HitSpec {
+    pub source: FNetworkGUID,
+    /// Level of the ability which dealt the hit.
+    pub level: f32,
+    damage: Vec<DamageSpec>,
+    atk: f32,
+    crit: f32,
+    crit_damage: f32,
+    /// Highest break level of the damage effects.
+    break_level: EBreakableLevel,
+    unbal_accrue_efficiency: f32,
+    /// Element tag of the first damage effect which has one, it selects the shields which absorb the hit.
+    element: Option<&'static str>,
+    ignore_shield: bool,
+    /// Hit reaction effects applied to the target, e.g. `GE_Player_Fadia_Melee1_HitBack`.
+    pub reactions: Vec<&'static GameplayEffectConfig>,
+}
+
+/// Outcome of a hit, replicated to clients to show hit numbers and reactions.
+#[derive(Debug, Clone, Default)]
+pub struct HitResult {
+    pub damage: f32,
+    pub is_critical: bool,
+    pub is_killing_blow: bool,
+    /// The hit broke the tenacity of the target.
+    pub is_break: bool,
+    /// Hit reaction tag of the first reaction effect, e.g. `State.AniState.Back`.
+    pub reaction_tag: Option<&'static str>,
+}
+
+impl DamageExecution {
+    fn from_calculation_class(calculation_class: &str) -> Option<Self> {
+        match calculation_class {
+            "MaxHPDamageExecution" => Some(Self::MaxHp),
+            "CurrentHPDamageExecution" => Some(Self::CurrentHp),
+            "FixedDamageExecution" => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+}
+
+impl DamageSpec {
+    fn new(
+        execution: DamageExecution,
+        effect: &GameplayEffectSpec,
+        definition: Option<&GameplayEffectExecution>,
+        assets: &GameAssets,
+        attributes: &AttributeValues,
+    ) -> Self {
+        let parameter = |name: &str, default: f32| {
+            let modifiers = definition
+                .iter()
+                .flat_map(|definition| definition.modifiers.iter())
+                .filter(|modifier| modifier.attribute == name)
+                .map(|modifier| {
+                    let magnitude =
+                        effect.calculate_magnitude(&modifier.magnitude, assets, attributes);
+
+                    (modifier.op, magnitude)
+                });
+
+            execution_parameter(modifiers, default)
+        };
+
+        for modifier in definition
+            .iter()
+            .flat_map(|definition| definition.modifiers.iter())
+            .filter(|modifier| UNSUPPORTED_DAMAGE_ATTRIBUTES.contains(&modifier.attribute.as_str()))
+        {
+            debug!(
+                "ignoring {} of {}, its effect on damage isn't known",
+                modifier.attribute, effect.def.name
+            );
+        }
+
+        Self {
+            execution,
+            coefficient: parameter("SkillCoefficient", 1.0)
+                * parameter("SkillCoefficientMult", 1.0),
+            flat: parameter("Damage", 0.0) + parameter("FixedDamageValue", 0.0),
+        }
+    }
+}
+
+impl HitSpec {
+    fn is_damage_effect(def: &GameplayEffectConfig) -> bool {
+        def.asset_tags
+            .iter()
+            .any(|tag| gameplay_tag_matches(tag, DAMAGE_TAG))
+    }
+
+    /// A plain attack scaled by the attack of the source, dealt by characters without abilities.
+    pub fn basic_attack(source: FNetworkGUID, attributes: &HTAttributeSet) -> Self {
+        Self {
+            source,
+            level: 1.0,
+            damage: vec![DamageSpec {
+                execution: DamageExecution::Attack,
+                coefficient: 1.0,
+                flat: 0.0,
+            }],
+            atk: CharacterStat::Atk.properties(attributes).1.get(),
+            crit: CharacterStat::Crit.properties(attributes).1.get(),
+            crit_damage: CharacterStat::CritDamage.properties(attributes).1.get(),
+            break_level: EBreakableLevel::Low,
+            unbal_accrue_efficiency: CharacterStat::UnbalAccrueEfficiency
+                .properties(attributes)
+                .1
+                .get(),
+            element: None,
+            ignore_shield: false,
+            reactions: Vec::new(),
+        }
+    }
+
+    pub fn reaction_tag(&self) -> Option<&'static str> {
+        self.reactions
+            .iter()
+            .flat_map(|def| def.asset_tags.iter())
+            .find(|tag| gameplay_tag_matches(tag, HIT_REACTION_TAG))
+            .map(String::as_str)
+    }
+}
+
+impl AbilitySystemComponent {
+    pub fn is_dead(&self) -> bool {
+        self.hp_current.get() <= 0.0
+    }
+
+    /// Validates a hit reported for an active ability and evaluates its damage
+    /// with the current attributes of the owner.
+    pub fn prepare_hit(
+        &self,
+        attributes: &HTAttributeSet,
+        handle: u32,
+        damage_range: &str,
+        assets: &'static GameAssets,
+    ) -> Result<HitSpec, HitReportError> {
+        if !self.ability_activations.is_active(handle) {
+            return Err(HitReportError::InactiveAbility(handle));
+        }
+
+        let (Some(spec), Some(config)) = (
+            self.find_ability_spec(handle),
+            self.ability_activations.ability_config(handle),
+        ) else {
+            return Err(HitReportError::InactiveAbility(handle));
+        };
+
+        let container = config
+            .properties
+            .find_effect_container(damage_range)
+            .ok_or_else(|| HitReportError::UnknownDamageRange(handle, damage_range.to_string()))?;
+
+        let source = self.owner_actor.get();
+        let values = self.active_gameplay_effects.current_values();
+
+        for attribute in UNSUPPORTED_DAMAGE_ATTRIBUTES {
+            if values.extra(attribute) != 0.0 {
+                debug!("ignoring {attribute} of {source:?}, its effect on damage isn't known");
+            }
+        }
+        let level = spec.level.get() as f32;
+        let mut damage = Vec::new();
+        let mut break_level = EBreakableLevel::None;
+        let mut element = None;
+        let mut ignore_shield = false;
+        let mut reactions = Vec::new();
+
+        for def in container
+            .target_gameplay_effect_classes
+            .iter()
+            .filter_map(|class| class.package_and_index())
+            .filter_map(|(package, _)| assets.get_gameplay_effect_config(package))
+        {
+            if !HitSpec::is_damage_effect(def) {
+                reactions.push(def);
+                continue;
+            }
+
+            break_level = break_level.max(def.attack_break_level);
+            element = element.or_else(|| element_tags(def).next());
+            ignore_shield |= def.asset_tags.iter().any(|tag| tag == IGNORE_SHIELD_TAG);
+
+            let effect = GameplayEffectSpec::new(def, level, source);
+            let executions = def
+                .executions
+                .iter()
+                .filter_map(|execution| {
+                    DamageExecution::from_calculation_class(&execution.calculation_class)
+                        .map(|kind| DamageSpec::new(kind, &effect, Some(execution), assets, values))
+                })
+                .collect::<Vec<_>>();
+
+            if executions.is_empty() {
+                damage.push(DamageSpec::new(
+                    DamageExecution::Attack,
+                    &effect,
+                    None,
+                    assets,
+                    values,
+                ));
+            } else {
+                damage.extend(executions);
+            }
+        }
+
+        Ok(HitSpec {
+            source,
+            level,
+            damage,
+            atk: CharacterStat::Atk.properties(attributes).1.get(),
+            crit: CharacterStat::Crit.properties(attributes).1.get(),
+            crit_damage: CharacterStat::CritDamage.properties(attributes).1.get(),
+            break_level,
+            unbal_accrue_efficiency: CharacterStat::UnbalAccrueEfficiency
+                .properties(attributes)
+                .1
+                .get(),
+            element,
+            ignore_shield,
+            reactions,
+        })
+    }
+
+    /// Calculates the damage of a hit and subtracts it from the current HP of the owner,
+    /// after its shields absorbed what they could, see `absorb_damage`.
+    ///
+    /// Attack damage is `atk * coefficient + flat`, multiplied by `1 + crit_damage` with
+    /// the chance of `crit` and reduced by `def / (def + DEFENSE_CONSTANT)` of the target.
+    /// HP-based and fixed damage ignore critical hits and defense.
+    /// Hits which don't kill the owner reduce its tenacity, see `accrue_imbalance`.
+    pub fn receive_hit(
+        &mut self,
+        attributes: &HTAttributeSet,
+        hit: &HitSpec,
+        now: f64,
+    ) -> HitResult {
+        let is_critical = rand::random::<f32>() < hit.crit;
+        let def = CharacterStat::Def.properties(attributes).1.get().max(0.0);
+        let max_hp = CharacterStat::HpMax.properties(attributes).1.get();
+        let hp = self.hp_current.get();
+
+        let damage = hit
+            .damage
+            .iter()
+            .map(|spec| match spec.execution {
+                DamageExecution::Attack => {
+                    let crit_multiplier = if is_critical {
+                        1.0 + hit.crit_damage
+                    } else {
+                        1.0
+                    };
+
+                    (hit.atk * spec.coefficient + spec.flat) * crit_multiplier * DEFENSE_CONSTANT
+                        / (DEFENSE_CONSTANT + def)
+                }
+                DamageExecution::MaxHp => max_hp * spec.coefficient + spec.flat,
+                DamageExecution::CurrentHp => hp * spec.coefficient + spec.flat,
+                DamageExecution::Fixed => spec.flat * spec.coefficient,
+            })
+            .sum::<f32>()
+            .max(0.0)
+            .round();
+
+        let is_critical = is_critical
+            && hit
+                .damage
+                .iter()
+                .any(|spec| spec.execution == DamageExecution::Attack);
+
+        let hp_damage = if hit.ignore_shield {
+            damage
+        } else {
+            self.absorb_damage(damage, hit.element)
+        };
+
+        self.hp_current.set_value((hp - hp_damage).max(0.0));
+
+        let is_killing_blow = hp > 0.0 && self.is_dead();
+        let is_break = if is_killing_blow {
+            self.on_death();
+            false
+        } else {
+            self.accrue_imbalance(hit.break_level, hit.unbal_accrue_efficiency, now)
+        };
+
+        HitResult {
+            damage,
+            is_critical,
+            is_killing_blow,
+            is_break,
+            reaction_tag: hit.reaction_tag(),
+        }
+    }
+
+    /// Stops every ability of the owner once its HP reaches zero.
+    ///
+    /// Zero HP is the only death state: dead characters can't activate abilities, be hit
+    /// or aggro monsters, see `is_dead`. Nothing respawns them, neither players nor monsters.
+    fn on_death(&mut self) {
+        self.ability_activations.cancel_all();
+    }
+}
+
+/// Evaluates a parameter captured by a damage execution: the sum of its additive
+/// modifiers, or `default` if there are none, scaled by the multiplicative ones.
+fn execution_parameter(
+    modifiers: impl Iterator<Item = (EGameplayModOp, f32)>,
+    default: f32,
+) -> f32 {
+    let mut value = None;
+    let mut scale = 1.0;
+
+    for (op, magnitude) in modifiers {
+        match op {
+            EGameplayModOp::MultiplyCompound => scale *= magnitude,
+            _ => *value.get_or_insert(0.0) += magnitude,
+        }
+    }
+
+    value.unwrap_or(default) * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use fadia_config::spawn::CharacterStats;
+    use fadia_engine::replication::FastArraySerializer;
+
+    use crate::logic::ability::AbilityActivations;
+
+    use super::*;
+
+    fn target(def: f32) -> (AbilitySystemComponent, HTAttributeSet) {
+        let stats = CharacterStats {
+            hp_max: 1000.0,
+            def,
+            ..Default::default()
+        };
+
+        (
+            AbilitySystemComponent::new(
+                FNetworkGUID::default(),
+                FNetworkGUID::default(),
+                &stats,
+                FastArraySerializer::default(),
+                AbilityActivations::default(),
+            ),
+            HTAttributeSet::new(&stats),
+        )
+    }
+
+    fn hit(damage: Vec<DamageSpec>, crit: f32) -> HitSpec {
+        HitSpec {
+            source: FNetworkGUID::default(),
+            level: 1.0,
+            damage,
+            atk: 100.0,
+            crit,
+            crit_damage: 0.5,
+            break_level: EBreakableLevel::None,
+            unbal_accrue_efficiency: 1.0,
+            element: None,
+            ignore_shield: false,
+            reactions: Vec::new(),
+        }
+    }
+
+    fn damage(execution: DamageExecution, coefficient: f32, flat: f32) -> DamageSpec {
+        DamageSpec {
+            execution,
+            coefficient,
+            flat,
+        }
+    }
+
+    #[test]
+    fn defense_reduces_attack_damage() {
+        let (mut asc, attributes) = target(DEFENSE_CONSTANT);
+        let result = asc.receive_hit(
+            &attributes,
+            &hit(vec![damage(DamageExecution::Attack, 1.5, 50.0)], 0.0),
+            0.0,
+        );
+
+        assert_eq!(result.damage, 100.0);
+        assert!(!result.is_critical);
+        assert_eq!(asc.hp_current.get(), 900.0);
+    }
+
+    #[test]
+    fn critical_hits_add_crit_damage() {
+        let (mut asc, attributes) = target(0.0);
+        let result = asc.receive_hit(
+            &attributes,
+            &hit(vec![damage(DamageExecution::Attack, 1.0, 0.0)], 1.0),
+            0.0,
+        );
+
+        assert_eq!(result.damage, 150.0);
+        assert!(result.is_critical);
+    }
+
+    #[test]
+    fn hp_based_and_fixed_damage_ignore_crit_and_defense() {
+        let (mut asc, attributes) = target(DEFENSE_CONSTANT);
+        let result = asc.receive_hit(
+            &attributes,
+            &hit(
+                vec![
+                    damage(DamageExecution::MaxHp, 0.1, 0.0),
+                    damage(DamageExecution::CurrentHp, 0.05, 0.0),
+                    damage(DamageExecution::Fixed, 2.0, 10.0),
+                ],
+                1.0,
+            ),
+            0.0,
+        );
+
+        assert_eq!(result.damage, 170.0);
+        assert!(!result.is_critical);
+    }
+
+    #[test]
+    fn shields_absorb_damage_before_hp() {
+        let (mut asc, attributes) = target(0.0);
+        asc.grant_shield(None, 40.0, Vec::new());
+
+        let result = asc.receive_hit(
+            &attributes,
+            &hit(vec![damage(DamageExecution::Attack, 1.0, 0.0)], 0.0),
+            0.0,
+        );
+
+        assert_eq!(result.damage, 100.0);
+        assert_eq!(asc.hp_current.get(), 940.0);
+        assert!(asc.shields.is_empty());
+    }
+
+    #[test]
+    fn shields_only_absorb_their_elements() {
+        let (mut asc, attributes) = target(0.0);
+        asc.grant_shield(None, 40.0, vec!["Element.Flame"]);
+
+        let mut frost = hit(vec![damage(DamageExecution::Attack, 1.0, 0.0)], 0.0);
+        frost.element = Some("Element.Frost");
+        asc.receive_hit(&attributes, &frost, 0.0);
+
+        assert_eq!(asc.hp_current.get(), 900.0);
+        assert_eq!(asc.shield_value(), 40.0);
+
+        let mut flame = frost.clone();
+        flame.element = Some("Element.Flame");
+        asc.receive_hit(&attributes, &flame, 0.0);
+
+        assert_eq!(asc.hp_current.get(), 840.0);
+        assert_eq!(asc.shield_value(), 0.0);
+    }
+
+    #[test]
+    fn ignore_shield_hits_go_straight_to_hp() {
+        let (mut asc, attributes) = target(0.0);
+        asc.grant_shield(None, 40.0, Vec::new());
+
+        let mut hit = hit(vec![damage(DamageExecution::Attack, 1.0, 0.0)], 0.0);
+        hit.ignore_shield = true;
+        asc.receive_hit(&attributes, &hit, 0.0);
+
+        assert_eq!(asc.hp_current.get(), 900.0);
+        assert_eq!(asc.shield_value(), 40.0);
+    }
+
+    #[test]
+    fn only_the_first_hit_to_zero_hp_is_a_killing_blow() {
+        let (mut asc, attributes) = target(0.0);
+        let hit = hit(vec![damage(DamageExecution::Fixed, 1.0, 2000.0)], 0.0);
+
+        let result = asc.receive_hit(&attributes, &hit, 0.0);
+        assert!(result.is_killing_blow);
+        assert_eq!(asc.hp_current.get(), 0.0);
+        assert!(asc.is_dead());
+
+        let result = asc.receive_hit(&attributes, &hit, 0.0);
+        assert!(!result.is_killing_blow);
+    }
+
+    #[test]
+    fn execution_parameters_sum_and_scale_modifiers() {
+        let modifiers = [
+            (EGameplayModOp::AddBase, 1.5),
+            (EGameplayModOp::AddFinal, 0.5),
+            (EGameplayModOp::MultiplyCompound, 2.0),
+        ];
+
+        assert_eq!(execution_parameter(modifiers.into_iter(), 1.0), 4.0);
+        assert_eq!(
+            execution_parameter([(EGameplayModOp::MultiplyCompound, 2.0)].into_iter(), 1.0),
+            2.0
+        );
+        assert_eq!(execution_parameter(std::iter::empty(), 1.0), 1.0);
+    }
+}
@@ -0,0 +1,913 @@
+use std::collections::HashMap;
+
+use fadia_config::blueprint::{
+    EGameplayEffectDurationType, EGameplayEffectStackingDurationPolicy,
+    EGameplayEffectStackingExpirationPolicy, EGameplayEffectStackingPeriodPolicy,
+    EGameplayEffectStackingType, EGameplayModOp, GameplayEffectConfig, GameplayEffectMagnitude,
+};
+use fadia_config::dataset::gameplay_tag_matches;
+use fadia_engine::{
+    FNetworkGUID,
+    replication::{
+        FastArraySerializer,
+        property::{
+            GameplayTagContainer, PropertyF32, PropertyI32, PropertyObject, ReplicatedProperty,
+        },
+    },
+    util::OutBitWriter,
+};
+use tracing::debug;
+
+use crate::{
+    assets::GameAssets,
+    logic::layout::{AbilitySystemComponent, ActiveGameplayEffect, HTAttributeSet},
+};
+
+use super::{
+    SATIETY_ATTRIBUTE, SHIELD_ATTRIBUTE,
+    attribute::{AttributeAggregator, AttributeValues, CharacterStat, apply_modifier},
+    shield::{element_tags, shield_amount},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ActiveGameplayEffectHandle(pub u32);
+
+/// An effect ready to be applied, `FGameplayEffectSpec`.
+#[derive(Debug, Clone)]
+pub struct GameplayEffectSpec {
+    pub def: &'static GameplayEffectConfig,
+    pub level: f32,
+    /// Actor which applies the effect.
+    pub source: FNetworkGUID,
+    pub set_by_caller_magnitudes: HashMap<String, f32>,
+    /// Tags granted in addition to the ones of the effect definition.
+    pub dynamic_granted_tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EvaluatedModifier {
+    pub attribute: &'static str,
+    pub op: EGameplayModOp,
+    pub magnitude: f32,
+}
+
+#[derive(Debug)]
+pub struct AppliedGameplayEffect {
+    pub handle: ActiveGameplayEffectHandle,
+    /// ID of the replicated `ActiveGameplayEffect` item.
+    replication_id: u32,
+    pub spec: GameplayEffectSpec,
+    pub stack_count: i32,
+    pub start_time: f64,
+    /// `None` for infinite effects.
+    pub duration: Option<f32>,
+    pub period: f32,
+    next_period_time: f64,
+    modifiers: Vec<EvaluatedModifier>,
+}
+
+/// Effects applied to an `AbilitySystemComponent`, `FActiveGameplayEffectsContainer`.
+/// Replicated to clients as a fast array of `ActiveGameplayEffect`.
+#[derive(Debug, Default)]
+pub struct ActiveGameplayEffectsContainer {
+    effects: Vec<AppliedGameplayEffect>,
+    replicated: FastArraySerializer<ActiveGameplayEffect>,
+    last_handle: u32,
+    /// Number of active effects which grant each tag.
+    owned_tags: HashMap<String, u32>,
+    /// Base values of attributes which don't live in `HTAttributeSet`.
+    extra_attribute_bases: HashMap<String, f32>,
+    current_values: AttributeValues,
+}
+
+impl GameplayEffectSpec {
+    pub fn new(def: &'static GameplayEffectConfig, level: f32, source: FNetworkGUID) -> Self {
+        Self {
+            def,
+            level,
+            source,
+            set_by_caller_magnitudes: HashMap::new(),
+            dynamic_granted_tags: Vec::new(),
+        }
+    }
+
+    pub fn calculate_magnitude(
+        &self,
+        magnitude: &GameplayEffectMagnitude,
+        assets: &GameAssets,
+        attributes: &AttributeValues,
+    ) -> f32 {
+        let level = self.level;
+        match magnitude {
+            GameplayEffectMagnitude::ScalableFloat(value) => {
+                assets.evaluate_scalable_float(value, level)
+            }
+            GameplayEffectMagnitude::AttributeBased {
+                attribute,
+                coefficient,
+                pre_multiply_additive_value,
+                post_multiply_additive_value,
+            } => {
+                let captured = CharacterStat::ALL
+                    .into_iter()
+                    .find(|stat| stat.attribute_name() == attribute)
+                    .map(|stat| attributes.stat(stat))
+                    .unwrap_or_else(|| attributes.extra(attribute));
+
+                assets.evaluate_scalable_float(coefficient, level)
+                    * (captured
+                        + assets.evaluate_scalable_float(pre_multiply_additive_value, level))
+                    + assets.evaluate_scalable_float(post_multiply_additive_value, level)
+            }
+            GameplayEffectMagnitude::CustomCalculationClass {
+                calculation_class,
+                coefficient,
+                pre_multiply_additive_value,
+                post_multiply_additive_value,
+            } => {
+                // Calculation classes are native/blueprint code, which is not available to us.
+                debug!("custom magnitude calculation {calculation_class} is not supported");
+
+                assets.evaluate_scalable_float(coefficient, level)
+                    * assets.evaluate_scalable_float(pre_multiply_additive_value, level)
+                    + assets.evaluate_scalable_float(post_multiply_additive_value, level)
+            }
+            GameplayEffectMagnitude::SetByCaller {
+                data_name,
+                data_tag,
+            } => self
+                .set_by_caller_magnitudes
+                .get(data_name)
+                .or_else(|| self.set_by_caller_magnitudes.get(data_tag))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn evaluate_modifiers(
+        &self,
+        assets: &GameAssets,
+        attributes: &AttributeValues,
+    ) -> Vec<EvaluatedModifier> {
+        self.def
+            .modifiers
+            .iter()
+            .map(|modifier| EvaluatedModifier {
+                attribute: &modifier.attribute,
+                op: modifier.op,
+                magnitude: self.calculate_magnitude(&modifier.magnitude, assets, attributes),
+            })
+            .collect()
+    }
+
+    fn can_stack_with(&self, other: &GameplayEffectSpec) -> bool {
+        if self.def.path != other.def.path {
+            return false;
+        }
+
+        match self.def.stacking_type {
+            EGameplayEffectStackingType::None => false,
+            EGameplayEffectStackingType::AggregateByTarget => true,
+            EGameplayEffectStackingType::AggregateBySource => self.source == other.source,
+        }
+    }
+}
+
+impl AppliedGameplayEffect {
+    pub fn is_periodic(&self) -> bool {
+        self.period > 0.0
+    }
+
+    pub fn expiration_time(&self) -> Option<f64> {
+        self.duration
+            .map(|duration| self.start_time + f64::from(duration))
+    }
+
+    pub fn granted_tags(&self) -> impl Iterator<Item = &str> {
+        self.spec
+            .def
+            .granted_tags
+            .iter()
+            .chain(self.spec.dynamic_granted_tags.iter())
+            .map(String::as_str)
+    }
+}
+
+impl ActiveGameplayEffectsContainer {
+    pub fn iter(&self) -> impl Iterator<Item = &AppliedGameplayEffect> {
+        self.effects.iter()
+    }
+
+    /// Iterates over the definitions of active effects, e.g. to export them to a client.
+    pub fn iter_defs(&self) -> impl Iterator<Item = FNetworkGUID> {
+        self.replicated.iter().map(|(_, effect)| effect.def.get())
+    }
+
+    /// Checks whether any of the active effects grants `tag` or one of its children.
+    pub fn has_matching_tag(&self, tag: &str) -> bool {
+        self.owned_tags
+            .keys()
+            .any(|owned_tag| gameplay_tag_matches(owned_tag, tag))
+    }
+
+    pub fn current_values(&self) -> &AttributeValues {
+        &self.current_values
+    }
+
+    fn next_handle(&mut self) -> ActiveGameplayEffectHandle {
+        self.last_handle += 1;
+        ActiveGameplayEffectHandle(self.last_handle)
+    }
+
+    fn add_effect(
+        &mut self,
+        mut effect: AppliedGameplayEffect,
+        def_guid: FNetworkGUID,
+        assets: &GameAssets,
+    ) {
+        let tag_settings = assets.gameplay_tag_net_settings();
+
+        effect.replication_id = self.replicated.push(ActiveGameplayEffect {
+            def: PropertyObject::new(def_guid),
+            duration: PropertyF32::new(
+                effect
+                    .duration
+                    .unwrap_or(ActiveGameplayEffect::INFINITE_DURATION),
+            ),
+            period: PropertyF32::new(effect.period),
+            dynamic_granted_tags: assets.make_gameplay_tag_container(
+                effect.spec.dynamic_granted_tags.iter().map(String::as_str),
+            ),
+            dynamic_asset_tags: GameplayTagContainer::new(tag_settings),
+            stack_count: PropertyI32::new(effect.stack_count),
+            level: PropertyF32::new(effect.spec.level),
+            start_server_world_time: PropertyF32::new(effect.start_time as f32),
+        });
+
+        for tag in effect.granted_tags() {
+            let count = self.owned_tags.entry(tag.to_string()).or_default();
+            *count += 1;
+
+            if *count == 1 {
+                debug!("gameplay tag {tag} added");
+            }
+        }
+
+        self.effects.push(effect);
+    }
+
+    fn on_effect_removed(&mut self, effect: &AppliedGameplayEffect) {
+        self.replicated.remove(effect.replication_id);
+
+        for tag in effect.granted_tags() {
+            if let Some(count) = self.owned_tags.get_mut(tag) {
+                *count -= 1;
+
+                if *count == 0 {
+                    self.owned_tags.remove(tag);
+                    debug!("gameplay tag {tag} removed");
+                }
+            }
+        }
+    }
+
+    /// Replicates the changed stack count and timings of an effect.
+    fn on_effect_changed(
+        replicated: &mut FastArraySerializer<ActiveGameplayEffect>,
+        effect: &AppliedGameplayEffect,
+    ) {
+        if let Some(item) = replicated.get_mut(effect.replication_id) {
+            item.stack_count.set_value(effect.stack_count);
+            item.start_server_world_time
+                .set_value(effect.start_time as f32);
+        }
+    }
+}
+
+impl ReplicatedProperty for ActiveGameplayEffectsContainer {
+    fn is_changed(&self) -> bool {
+        self.replicated.is_changed()
+    }
+
+    fn acknowledge_changes(&mut self) {
+        self.replicated.acknowledge_changes();
+    }
+
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        self.replicated.serialize(w)
+    }
+}
+
+impl AbilitySystemComponent {
+    /// Applies `spec` to the owner of this component, `ApplyGameplayEffectSpecToSelf`.
+    /// Returns a handle of the active effect, or `None` for instant effects.
+    pub fn apply_gameplay_effect_spec(
+        &mut self,
+        attributes: &mut HTAttributeSet,
+        spec: GameplayEffectSpec,
+        def_guid: FNetworkGUID,
+        assets: &GameAssets,
+        now: f64,
+    ) -> Option<ActiveGameplayEffectHandle> {
+        let def = spec.def;
+        let current_values = self.active_gameplay_effects.current_values.clone();
+
+        if def.duration_policy == EGameplayEffectDurationType::Instant {
+            let modifiers = spec.evaluate_modifiers(assets, &current_values);
+            self.execute_modifiers(attributes, &modifiers, 1, now);
+            self.recalculate_attributes(attributes);
+            self.grant_shield(None, shield_amount(&modifiers), element_tags(def).collect());
+            return None;
+        }
+
+        let container = &mut self.active_gameplay_effects;
+        if let Some(existing) = container
+            .effects
+            .iter_mut()
+            .find(|effect| effect.spec.can_stack_with(&spec))
+        {
+            if def.stack_limit_count <= 0 || existing.stack_count < def.stack_limit_count {
+                existing.stack_count += 1;
+            }
+
+            if def.stack_duration_refresh_policy
+                == EGameplayEffectStackingDurationPolicy::RefreshOnSuccessfulApplication
+            {
+                existing.start_time = now;
+            }
+
+            if def.stack_period_reset_policy
+                == EGameplayEffectStackingPeriodPolicy::ResetOnSuccessfulApplication
+            {
+                existing.next_period_time = now + f64::from(existing.period);
+            }
+
+            ActiveGameplayEffectsContainer::on_effect_changed(&mut container.replicated, existing);
+
+            let handle = existing.handle;
+            let shield = shield_amount(&existing.modifiers) * existing.stack_count as f32;
+            self.recalculate_attributes(attributes);
+            self.grant_shield(Some(handle), shield, element_tags(def).collect());
+            return Some(handle);
+        }
+
+        let duration = (def.duration_policy == EGameplayEffectDurationType::HasDuration)
+            .then(|| spec.calculate_magnitude(&def.duration_magnitude, assets, &current_values));
+        let period = assets.evaluate_scalable_float(&def.period, spec.level);
+        let modifiers = spec.evaluate_modifiers(assets, &current_values);
+        let shield = shield_amount(&modifiers);
+
+        let handle = self.active_gameplay_effects.next_handle();
+        let effect = AppliedGameplayEffect {
+            handle,
+            replication_id: 0,
+            spec,
+            stack_count: 1,
+            start_time: now,
+            duration,
+            period,
+            next_period_time: now + f64::from(period),
+            modifiers,
+        };
+
+        if effect.is_periodic() && def.execute_periodic_effect_on_application {
+            self.execute_modifiers(attributes, &effect.modifiers, 1, now);
+        }
+
+        self.active_gameplay_effects
+            .add_effect(effect, def_guid, assets);
+        self.recalculate_attributes(attributes);
+        self.grant_shield(Some(handle), shield, element_tags(def).collect());
+
+        Some(handle)
+    }
+
+    /// Removes an active effect with all of its stacks, `RemoveActiveGameplayEffect`.
+    pub fn remove_active_gameplay_effect(
+        &mut self,
+        attributes: &mut HTAttributeSet,
+        handle: ActiveGameplayEffectHandle,
+    ) {
+        let container = &mut self.active_gameplay_effects;
+        let Some(index) = container
+            .effects
+            .iter()
+            .position(|effect| effect.handle == handle)
+        else {
+            return;
+        };
+
+        let effect = container.effects.remove(index);
+        container.on_effect_removed(&effect);
+        self.remove_shield(handle);
+        self.recalculate_attributes(attributes);
+    }
+
+    /// Checks that additive modifiers of `spec` don't bring any attribute below zero,
+    /// `CanApplyAttributeModifiers`. Used to check whether an ability cost can be afforded.
+    pub fn can_apply_attribute_modifiers(
+        &self,
+        attributes: &HTAttributeSet,
+        spec: &GameplayEffectSpec,
+        assets: &GameAssets,
+    ) -> bool {
+        let current_values = &self.active_gameplay_effects.current_values;
+
+        spec.evaluate_modifiers(assets, current_values)
+            .iter()
+            .filter(|modifier| modifier.op == EGameplayModOp::AddBase)
+            .all(|modifier| {
+                let current = match CharacterStat::from_base_attribute(modifier.attribute) {
+                    Some(stat) => stat.properties(attributes).1.get(),
+                    None => current_values.extra(modifier.attribute),
+                };
+
+                current + modifier.magnitude >= 0.0
+            })
+    }
+
+    /// Executes periodic effects and removes the expired ones.
+    pub fn tick_active_gameplay_effects(&mut self, attributes: &mut HTAttributeSet, now: f64) {
+        let mut executions = Vec::new();
+        let mut changed = false;
+
+        for effect in self.active_gameplay_effects.effects.iter_mut() {
+            while effect.is_periodic() && now >= effect.next_period_time {
+                executions.push((effect.modifiers.clone(), effect.stack_count));
+                effect.next_period_time += f64::from(effect.period);
+            }
+        }
+
+        for (modifiers, stack_count) in executions {
+            self.execute_modifiers(attributes, &modifiers, stack_count, now);
+            changed = true;
+        }
+
+        let container = &mut self.active_gameplay_effects;
+        let mut removed = Vec::new();
+
+        for (index, effect) in container.effects.iter_mut().enumerate() {
+            let Some(expiration_time) = effect.expiration_time() else {
+                continue;
+            };
+
+            if now < expiration_time {
+                continue;
+            }
+
+            changed = true;
+            match effect.spec.def.stack_expiration_policy {
+                EGameplayEffectStackingExpirationPolicy::ClearEntireStack => removed.push(index),
+                EGameplayEffectStackingExpirationPolicy::RemoveSingleStackAndRefreshDuration => {
+                    effect.stack_count -= 1;
+                    effect.start_time = now;
+
+                    if effect.stack_count > 0 {
+                        ActiveGameplayEffectsContainer::on_effect_changed(
+                            &mut container.replicated,
+                            effect,
+                        );
+                    } else {
+                        removed.push(index);
+                    }
+                }
+                EGameplayEffectStackingExpirationPolicy::RefreshDuration => {
+                    effect.start_time = now;
+                    ActiveGameplayEffectsContainer::on_effect_changed(
+                        &mut container.replicated,
+                        effect,
+                    );
+                }
+            }
+        }
+
+        let mut removed_handles = Vec::new();
+        for index in removed.into_iter().rev() {
+            let effect = container.effects.remove(index);
+            container.on_effect_removed(&effect);
+            removed_handles.push(effect.handle);
+        }
+
+        for handle in removed_handles {
+            self.remove_shield(handle);
+        }
+
+        if changed {
+            self.recalculate_attributes(attributes);
+        }
+    }
+
+    /// Recomputes current values from base values and the active modifiers,
+    /// then writes them to the replicated properties.
+    pub fn recalculate_attributes(&mut self, attributes: &mut HTAttributeSet) {
+        let mut aggregators = HashMap::<String, AttributeAggregator>::new();
+
+        for effect in self
+            .active_gameplay_effects
+            .effects
+            .iter()
+            .filter(|effect| !effect.is_periodic())
+        {
+            for modifier in effect
+                .modifiers
+                .iter()
+                .filter(|modifier| modifier.attribute != SHIELD_ATTRIBUTE)
+            {
+                let aggregator = aggregators
+                    .entry(modifier.attribute.to_string())
+                    .or_default();
+
+                if modifier.op == EGameplayModOp::MultiplyCompound {
+                    for _ in 0..effect.stack_count {
+                        aggregator.add_modifier(modifier.op, modifier.magnitude);
+                    }
+                } else {
+                    aggregator
+                        .add_modifier(modifier.op, modifier.magnitude * effect.stack_count as f32);
+                }
+            }
+        }
+
+        let values = AttributeValues::evaluate(
+            attributes,
+            &self.active_gameplay_effects.extra_attribute_bases,
+            &aggregators,
+        );
+
+        for stat in CharacterStat::ALL {
+            let (_, current) = stat.properties_mut(attributes);
+            current.set_value(values.stat(stat));
+        }
+
+        let max_hp = values.stat(CharacterStat::HpMax);
+        self.max_hp.set_value(max_hp);
+        self.atk.set_value(values.stat(CharacterStat::Atk));
+        if self.hp_current.get() > max_hp {
+            self.hp_current.set_value(max_hp);
+        }
+
+        let max_charge = values.stat(CharacterStat::ChargeMax);
+        if self.charge_current.get() > max_charge {
+            self.charge_current.set_value(max_charge);
+        }
+
+        let max_tenacity = values.stat(CharacterStat::UnbalReduceNatur);
+        if self.unbal_speed.get() > max_tenacity {
+            self.unbal_speed.set_value(max_tenacity);
+        }
+
+        self.active_gameplay_effects.current_values = values;
+    }
+
+    /// Applies modifiers to base values permanently, the way instant and periodic effects do.
+    /// Satiety modifiers of food effects restore satiety instead,
+    /// shield modifiers are handled by `grant_shield`.
+    fn execute_modifiers(
+        &mut self,
+        attributes: &mut HTAttributeSet,
+        modifiers: &[EvaluatedModifier],
+        stack_count: i32,
+        now: f64,
+    ) {
+        for modifier in modifiers {
+            let magnitude = modifier.magnitude * stack_count as f32;
+
+            if modifier.attribute == SATIETY_ATTRIBUTE {
+                self.restore_satiety(attributes, magnitude, now);
+            } else if modifier.attribute == SHIELD_ATTRIBUTE {
+                continue;
+            } else if let Some(stat) = CharacterStat::from_base_attribute(modifier.attribute) {
+                let (base, _) = stat.properties_mut(attributes);
+                base.set_value(apply_modifier(base.get(), modifier.op, magnitude));
+            } else {
+                let base = self
+                    .active_gameplay_effects
+                    .extra_attribute_bases
+                    .entry(modifier.attribute.to_string())
+                    .or_default();
+
+                *base = apply_modifier(*base, modifier.op, magnitude);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fadia_config::{
+        blueprint::{EBreakableLevel, GameplayModifierInfo, ScalableFloat},
+        spawn::CharacterStats,
+    };
+
+    use crate::logic::ability::AbilityActivations;
+
+    use super::*;
+
+    fn target() -> (AbilitySystemComponent, HTAttributeSet) {
+        let stats = CharacterStats {
+            hp_max: 1000.0,
+            atk: 100.0,
+            ..Default::default()
+        };
+
+        (
+            AbilitySystemComponent::new(
+                FNetworkGUID::default(),
+                FNetworkGUID::default(),
+                &stats,
+                FastArraySerializer::default(),
+                AbilityActivations::default(),
+            ),
+            HTAttributeSet::new(&stats),
+        )
+    }
+
+    fn value(value: f32) -> ScalableFloat {
+        ScalableFloat {
+            value,
+            ..Default::default()
+        }
+    }
+
+    fn modifier(attribute: &str, op: EGameplayModOp, magnitude: f32) -> GameplayModifierInfo {
+        GameplayModifierInfo {
+            attribute: attribute.to_string(),
+            op,
+            magnitude: GameplayEffectMagnitude::ScalableFloat(value(magnitude)),
+        }
+    }
+
+    fn effect(
+        duration_policy: EGameplayEffectDurationType,
+        duration: f32,
+        modifiers: Vec<GameplayModifierInfo>,
+    ) -> GameplayEffectConfig {
+        GameplayEffectConfig {
+            name: String::from("GE_Test"),
+            default_object_name: String::from("Default__GE_Test_C"),
+            path: String::from("/Game/Test/GE_Test"),
+            parent: None,
+            root_class: String::from("GameplayEffect"),
+            duration_policy,
+            duration_magnitude: GameplayEffectMagnitude::ScalableFloat(value(duration)),
+            period: ScalableFloat::default(),
+            execute_periodic_effect_on_application: false,
+            stacking_type: EGameplayEffectStackingType::None,
+            stack_limit_count: 0,
+            stack_duration_refresh_policy: Default::default(),
+            stack_period_reset_policy: Default::default(),
+            stack_expiration_policy: Default::default(),
+            modifiers,
+            executions: Vec::new(),
+            asset_tags: Vec::new(),
+            granted_tags: vec![String::from("State.Test")],
+            attack_break_level: EBreakableLevel::None,
+        }
+    }
+
+    fn atk_bonus(amount: f32) -> GameplayEffectConfig {
+        effect(
+            EGameplayEffectDurationType::HasDuration,
+            5.0,
+            vec![modifier("AtkAdd", EGameplayModOp::AddBase, amount)],
+        )
+    }
+
+    fn leak(config: GameplayEffectConfig) -> &'static GameplayEffectConfig {
+        Box::leak(Box::new(config))
+    }
+
+    fn apply(
+        asc: &mut AbilitySystemComponent,
+        attributes: &mut HTAttributeSet,
+        def: &'static GameplayEffectConfig,
+        source: u32,
+        now: f64,
+    ) -> Option<ActiveGameplayEffectHandle> {
+        asc.apply_gameplay_effect_spec(
+            attributes,
+            GameplayEffectSpec::new(def, 1.0, FNetworkGUID(source)),
+            FNetworkGUID::default(),
+            &GameAssets::empty(),
+            now,
+        )
+    }
+
+    fn stack_count(asc: &AbilitySystemComponent, handle: ActiveGameplayEffectHandle) -> i32 {
+        asc.active_gameplay_effects
+            .iter()
+            .find(|effect| effect.handle == handle)
+            .map(|effect| effect.stack_count)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn stacks_by_target_up_to_the_limit() {
+        let def = leak(GameplayEffectConfig {
+            stacking_type: EGameplayEffectStackingType::AggregateByTarget,
+            stack_limit_count: 2,
+            ..atk_bonus(10.0)
+        });
+        let (mut asc, mut attributes) = target();
+
+        let handle = apply(&mut asc, &mut attributes, def, 1, 0.0).unwrap();
+        assert_eq!(apply(&mut asc, &mut attributes, def, 2, 0.0), Some(handle));
+        assert_eq!(apply(&mut asc, &mut attributes, def, 3, 0.0), Some(handle));
+
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 1);
+        assert_eq!(stack_count(&asc, handle), 2);
+        assert_eq!(attributes.atk_cur.get(), 120.0);
+    }
+
+    #[test]
+    fn stacks_by_source_separately_for_each_source() {
+        let def = leak(GameplayEffectConfig {
+            stacking_type: EGameplayEffectStackingType::AggregateBySource,
+            ..atk_bonus(10.0)
+        });
+        let (mut asc, mut attributes) = target();
+
+        let first = apply(&mut asc, &mut attributes, def, 1, 0.0).unwrap();
+        let second = apply(&mut asc, &mut attributes, def, 2, 0.0).unwrap();
+        assert_eq!(apply(&mut asc, &mut attributes, def, 1, 0.0), Some(first));
+
+        assert_ne!(first, second);
+        assert_eq!(stack_count(&asc, first), 2);
+        assert_eq!(stack_count(&asc, second), 1);
+        assert_eq!(attributes.atk_cur.get(), 130.0);
+    }
+
+    #[test]
+    fn unstackable_effects_are_applied_separately() {
+        let def = leak(atk_bonus(10.0));
+        let (mut asc, mut attributes) = target();
+
+        let first = apply(&mut asc, &mut attributes, def, 1, 0.0);
+        let second = apply(&mut asc, &mut attributes, def, 1, 0.0);
+
+        assert_ne!(first, second);
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 2);
+        assert_eq!(attributes.atk_cur.get(), 120.0);
+    }
+
+    #[test]
+    fn aggregates_modifier_ops_in_engine_order() {
+        let def = leak(effect(
+            EGameplayEffectDurationType::Infinite,
+            0.0,
+            vec![
+                modifier("AtkBase", EGameplayModOp::AddBase, 20.0),
+                modifier("AtkBase", EGameplayModOp::DivideAdditive, 1.0),
+                modifier("AtkUp", EGameplayModOp::AddBase, 0.5),
+                modifier("Atk", EGameplayModOp::MultiplyAdditive, 1.0),
+                modifier("Atk", EGameplayModOp::MultiplyCompound, 2.0),
+                modifier("Atk", EGameplayModOp::AddFinal, 5.0),
+            ],
+        ));
+        let (mut asc, mut attributes) = target();
+
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+
+        // AtkBase: (100 + 20) / 2 = 60, AtkUp: 60 * 1.5 = 90, Atk: 90 * 2 * 2 + 5.
+        assert_eq!(attributes.atk_base.get(), 100.0);
+        assert_eq!(attributes.atk_cur.get(), 365.0);
+        assert_eq!(asc.atk.get(), 365.0);
+    }
+
+    #[test]
+    fn override_wins_over_other_ops() {
+        let def = leak(effect(
+            EGameplayEffectDurationType::Infinite,
+            0.0,
+            vec![
+                modifier("Atk", EGameplayModOp::AddFinal, 5.0),
+                modifier("Atk", EGameplayModOp::Override, 1.0),
+            ],
+        ));
+        let (mut asc, mut attributes) = target();
+
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+
+        assert_eq!(attributes.atk_cur.get(), 1.0);
+    }
+
+    #[test]
+    fn instant_effects_modify_base_values() {
+        let def = leak(effect(
+            EGameplayEffectDurationType::Instant,
+            0.0,
+            vec![modifier("AtkBase", EGameplayModOp::AddBase, 10.0)],
+        ));
+        let (mut asc, mut attributes) = target();
+
+        assert_eq!(apply(&mut asc, &mut attributes, def, 1, 0.0), None);
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 0);
+        assert_eq!(attributes.atk_base.get(), 110.0);
+        assert_eq!(attributes.atk_cur.get(), 110.0);
+    }
+
+    #[test]
+    fn removes_expired_effects_and_their_tags() {
+        let def = leak(atk_bonus(10.0));
+        let (mut asc, mut attributes) = target();
+
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 4.9);
+        assert!(asc.active_gameplay_effects.has_matching_tag("State"));
+        assert_eq!(attributes.atk_cur.get(), 110.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 5.0);
+        assert!(!asc.active_gameplay_effects.has_matching_tag("State"));
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 0);
+        assert_eq!(attributes.atk_cur.get(), 100.0);
+    }
+
+    #[test]
+    fn infinite_effects_never_expire() {
+        let def = leak(effect(
+            EGameplayEffectDurationType::Infinite,
+            0.0,
+            vec![modifier("AtkAdd", EGameplayModOp::AddBase, 10.0)],
+        ));
+        let (mut asc, mut attributes) = target();
+
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+        asc.tick_active_gameplay_effects(&mut attributes, 1.0e6);
+
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 1);
+        assert_eq!(attributes.atk_cur.get(), 110.0);
+    }
+
+    #[test]
+    fn stacking_refreshes_duration_unless_told_not_to() {
+        let refreshed = leak(GameplayEffectConfig {
+            stacking_type: EGameplayEffectStackingType::AggregateByTarget,
+            ..atk_bonus(10.0)
+        });
+        let not_refreshed = leak(GameplayEffectConfig {
+            path: String::from("/Game/Test/GE_NeverRefresh"),
+            stacking_type: EGameplayEffectStackingType::AggregateByTarget,
+            stack_duration_refresh_policy: EGameplayEffectStackingDurationPolicy::NeverRefresh,
+            ..atk_bonus(1.0)
+        });
+        let (mut asc, mut attributes) = target();
+
+        let refreshed_handle = apply(&mut asc, &mut attributes, refreshed, 1, 0.0).unwrap();
+        let not_refreshed_handle = apply(&mut asc, &mut attributes, not_refreshed, 1, 0.0).unwrap();
+        apply(&mut asc, &mut attributes, refreshed, 1, 3.0);
+        apply(&mut asc, &mut attributes, not_refreshed, 1, 3.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 5.0);
+        assert_eq!(stack_count(&asc, refreshed_handle), 2);
+        assert_eq!(stack_count(&asc, not_refreshed_handle), 0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 8.0);
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 0);
+    }
+
+    #[test]
+    fn expiration_removes_a_single_stack_and_refreshes_the_duration() {
+        let def = leak(GameplayEffectConfig {
+            stacking_type: EGameplayEffectStackingType::AggregateByTarget,
+            stack_expiration_policy:
+                EGameplayEffectStackingExpirationPolicy::RemoveSingleStackAndRefreshDuration,
+            ..atk_bonus(10.0)
+        });
+        let (mut asc, mut attributes) = target();
+
+        let handle = apply(&mut asc, &mut attributes, def, 1, 0.0).unwrap();
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 5.0);
+        assert_eq!(stack_count(&asc, handle), 1);
+        assert_eq!(attributes.atk_cur.get(), 110.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 9.9);
+        assert_eq!(stack_count(&asc, handle), 1);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 10.0);
+        assert_eq!(asc.active_gameplay_effects.iter().count(), 0);
+        assert_eq!(attributes.atk_cur.get(), 100.0);
+    }
+
+    #[test]
+    fn periodic_effects_execute_once_per_period() {
+        let def = leak(GameplayEffectConfig {
+            period: value(1.0),
+            ..effect(
+                EGameplayEffectDurationType::Infinite,
+                0.0,
+                vec![modifier("AtkBase", EGameplayModOp::AddBase, 1.0)],
+            )
+        });
+        let (mut asc, mut attributes) = target();
+
+        apply(&mut asc, &mut attributes, def, 1, 0.0);
+        assert_eq!(attributes.atk_base.get(), 100.0);
+
+        asc.tick_active_gameplay_effects(&mut attributes, 3.5);
+        assert_eq!(attributes.atk_base.get(), 103.0);
+        assert_eq!(attributes.atk_cur.get(), 103.0);
+    }
+}
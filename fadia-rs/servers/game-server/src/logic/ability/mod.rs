@@ -0,0 +1,524 @@
+use fadia_config::blueprint::PlayerCharacterConfig;
+use fadia_engine::FNetworkGUID;
+use tracing::warn;
+
+use crate::{
+    assets::GameAssets,
+    logic::{
+        cooldown::{self, ECooldownType},
+        progression::{CharacterProgression, PlayerProgression},
+        rpc::call_rpcs,
+    },
+    net::World,
+};
+
+use super::layout::{
+    AbilitySystemComponent, HTAttributeSet, HTPlayerCharacter, MonsterCharacter, PlayerState,
+    ShieldComponent,
+};
+
+mod activation;
+mod attribute;
+mod charge;
+mod damage;
+mod effect;
+mod satiety;
+mod shield;
+mod tenacity;
+
+pub use activation::*;
+pub use charge::*;
+pub use damage::*;
+pub use effect::*;
+pub use satiety::*;
+pub use shield::*;
+pub use tenacity::*;
+
+/// Looks up the ability system of a player character or a monster.
+fn find_ability_system_component(
+    world: &World,
+    character_guid: FNetworkGUID,
+) -> Option<FNetworkGUID> {
+    if !world.actors.contains_key(&character_guid) {
+        return None;
+    }
+
+    let object = world.objects.get(&character_guid)?;
+    if object.is::<HTPlayerCharacter>() {
+        Some(
+            object
+                .layout::<HTPlayerCharacter>()
+                .ability_system_component,
+        )
+    } else if object.is::<MonsterCharacter>() {
+        Some(object.layout::<MonsterCharacter>().ability_system_component)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `character_guid` is a character with an ability system and it's not dead.
+pub fn is_alive(world: &World, character_guid: FNetworkGUID) -> bool {
+    find_ability_system_component(world, character_guid)
+        .and_then(|asc_guid| world.get_object::<AbilitySystemComponent>(asc_guid))
+        .is_some_and(|asc| !asc.data().is_dead())
+}
+
+/// Runs `f` on the ability system and the attribute set of a character.
+/// Returns `None` if `character_guid` is not a character with an ability system.
+pub fn with_ability_system<R>(
+    world: &mut World,
+    character_guid: FNetworkGUID,
+    f: impl FnOnce(&mut AbilitySystemComponent, &mut HTAttributeSet, &'static GameAssets, f64) -> R,
+) -> Option<R> {
+    let asc_guid = find_ability_system_component(world, character_guid)?;
+
+    let attribute_set_guid = world
+        .get_object::<AbilitySystemComponent>(asc_guid)?
+        .data()
+        .attribute_set;
+
+    let assets = world.assets;
+    let now = world.time_seconds();
+
+    let [Some(asc), Some(attribute_set)] = world
+        .objects
+        .get_disjoint_mut([&asc_guid, &attribute_set_guid])
+    else {
+        return None;
+    };
+
+    Some(f(asc.layout_mut(), attribute_set.layout_mut(), assets, now))
+}
+
+/// Applies a gameplay effect to a character, see `AbilitySystemComponent::apply_gameplay_effect_spec`.
+/// Effects which shield values are configured also grant their shields.
+pub fn apply_gameplay_effect(
+    world: &mut World,
+    character_guid: FNetworkGUID,
+    spec: GameplayEffectSpec,
+) -> Option<ActiveGameplayEffectHandle> {
+    let def_guid = world
+        .register_hierarchy_for_static_objects(&[&spec.def.path, &spec.def.default_object_name]);
+
+    let shield = world
+        .globals
+        .shield_effects
+        .iter()
+        .find(|shield| shield.effect == spec.def.path);
+
+    with_ability_system(world, character_guid, |asc, attributes, assets, now| {
+        let def = spec.def;
+        let handle = asc.apply_gameplay_effect_spec(attributes, spec, def_guid, assets, now);
+
+        if let Some(shield) = shield {
+            asc.grant_configured_shield(attributes, handle, def, shield);
+        }
+
+        handle
+    })
+    .flatten()
+}
+
+/// Validates an ability activation requested by the client of a character, applies
+/// the ability cost and starts its cooldowns in the `CDManager` of the player,
+/// see `AbilitySystemComponent::try_activate_ability`.
+pub fn try_activate_ability(
+    world: &mut World,
+    character_guid: FNetworkGUID,
+    handle: u32,
+    prediction_key: PredictionKey,
+) -> Result<(), AbilityActivationError> {
+    let player_state_guid = cooldown::find_player_state(world, character_guid);
+
+    if let Some(player_state_guid) = player_state_guid {
+        let config = with_ability_system(world, character_guid, |asc, _, _, _| {
+            asc.ability_activations.ability_config(handle)
+        })
+        .ok_or(AbilityActivationError::NoAbilitySystem)?
+        .ok_or(AbilityActivationError::UnknownAbility(handle))?;
+
+        let cooldown = cooldown::with_cd_manager(world, player_state_guid, |cd_manager, now| {
+            config
+                .properties
+                .cooldown_tags
+                .iter()
+                .find(|tag| cd_manager.is_on_cooldown(ECooldownType::AbilityTag, tag, now))
+                .cloned()
+                .or_else(|| {
+                    let name = config.ability_name();
+                    cd_manager
+                        .is_on_cooldown(ECooldownType::Skill, name, now)
+                        .then(|| name.to_string())
+                })
+        })
+        .flatten();
+
+        if let Some(cooldown) = cooldown {
+            return Err(AbilityActivationError::Cooldown(cooldown));
+        }
+    }
+
+    let committed = with_ability_system(world, character_guid, |asc, attributes, assets, _| {
+        asc.try_activate_ability(attributes, handle, prediction_key, assets)
+    })
+    .ok_or(AbilityActivationError::NoAbilitySystem)??;
+
+    if let Some(cost) = committed.cost {
+        apply_gameplay_effect(world, character_guid, cost);
+    }
+
+    if let Some(player_state_guid) = player_state_guid
+        && committed.cooldown_duration > 0.0
+    {
+        let properties = &committed.config.properties;
+        let keys = properties
+            .cooldown_tags
+            .iter()
+            .map(|tag| (ECooldownType::AbilityTag, tag.as_str()))
+            .chain([(ECooldownType::Skill, committed.config.ability_name())])
+            .collect::<Vec<_>>();
+
+        cooldown::start_cooldowns(
+            world,
+            player_state_guid,
+            &keys,
+            (committed.cooldown_duration * 1000.0) as u64,
+        );
+    }
+
+    Ok(())
+}
+
+/// Deals a hit reported by the client of the attacker to the target,
+/// see `AbilitySystemComponent::prepare_hit` and `deal_hit`.
+pub fn report_hit(
+    world: &mut World,
+    attacker_guid: FNetworkGUID,
+    handle: u32,
+    target_guid: FNetworkGUID,
+    damage_range: &str,
+) -> Result<HitResult, HitReportError> {
+    if attacker_guid == target_guid {
+        return Err(HitReportError::InvalidTarget(target_guid));
+    }
+
+    let hit = with_ability_system(world, attacker_guid, |asc, attributes, assets, _| {
+        asc.prepare_hit(attributes, handle, damage_range, assets)
+    })
+    .ok_or(HitReportError::NoAbilitySystem)??;
+
+    deal_hit(world, target_guid, &hit)
+}
+
+/// Deals a hit to the target, see `AbilitySystemComponent::receive_hit`, and queues
+/// `client_receive_hit` on its ability system. Hit reaction effects are applied afterwards.
+pub fn deal_hit(
+    world: &mut World,
+    target_guid: FNetworkGUID,
+    hit: &HitSpec,
+) -> Result<HitResult, HitReportError> {
+    let result = with_ability_system(world, target_guid, |asc, attributes, _, now| {
+        (!asc.is_dead()).then(|| asc.receive_hit(attributes, hit, now))
+    })
+    .flatten()
+    .ok_or(HitReportError::InvalidTarget(target_guid))?;
+
+    if !result.is_killing_blow {
+        for &def in hit.reactions.iter() {
+            apply_gameplay_effect(
+                world,
+                target_guid,
+                GameplayEffectSpec::new(def, hit.level, hit.source),
+            );
+        }
+    }
+
+    if result.is_break {
+        apply_break_effect(world, target_guid, hit.source);
+    }
+
+    with_ability_system(world, hit.source, |asc, attributes, _, _| {
+        asc.gain_charge(attributes, CHARGE_PER_HIT);
+    });
+
+    if let Some(target_asc) = find_ability_system_component(world, target_guid)
+        .and_then(|asc_guid| world.get_object_mut::<AbilitySystemComponent>(asc_guid))
+    {
+        call_rpcs!(target_asc.client_receive_hit(
+            hit.source,
+            result.damage,
+            result.is_critical,
+            result.reaction_tag.unwrap_or_default().to_string()
+        ));
+    }
+
+    Ok(result)
+}
+
+/// Applies the configured break effect to a character which tenacity was broken,
+/// it's removed once the break ends, see `AbilitySystemComponent::tick_tenacity`.
+fn apply_break_effect(world: &mut World, character_guid: FNetworkGUID, source: FNetworkGUID) {
+    let Some(path) = world.globals.break_effect.as_deref() else {
+        return;
+    };
+
+    let Some(def) = world.assets.get_gameplay_effect_config(path) else {
+        warn!("break effect {path} doesn't exist");
+        return;
+    };
+
+    let handle = apply_gameplay_effect(
+        world,
+        character_guid,
+        GameplayEffectSpec::new(def, 1.0, source),
+    );
+
+    with_ability_system(world, character_guid, |asc, _, _, _| {
+        asc.tenacity.break_effect = handle;
+    });
+}
+
+/// Computes the initial attribute values of a freshly spawned character
+/// and applies the configured startup effects.
+pub fn init_ability_system(world: &mut World, character_guid: FNetworkGUID) {
+    with_ability_system(world, character_guid, |asc, attributes, _, _| {
+        asc.recalculate_attributes(attributes);
+    });
+
+    let (globals, assets) = (world.globals, world.assets);
+
+    for path in globals.startup_effects.iter() {
+        let Some(def) = assets.get_gameplay_effect_config(path) else {
+            warn!("startup effect {path} doesn't exist");
+            continue;
+        };
+
+        apply_gameplay_effect(
+            world,
+            character_guid,
+            GameplayEffectSpec::new(def, 1.0, character_guid),
+        );
+    }
+}
+
+/// Sets the ability levels of a character and grants the awakening buffs of its upgrade level.
+/// Buffs granted by a previous call are removed, so it's also used to update the progression.
+pub fn apply_character_progression(
+    world: &mut World,
+    character_guid: FNetworkGUID,
+    config: &PlayerCharacterConfig,
+    progression: &CharacterProgression,
+) {
+    with_ability_system(world, character_guid, |asc, attributes, _, _| {
+        asc.set_ability_levels(|ability_name| progression.skill_level(ability_name));
+
+        for handle in std::mem::take(&mut asc.upgrade_effects) {
+            asc.remove_active_gameplay_effect(attributes, handle);
+        }
+    });
+
+    let upgrade_level = progression
+        .upgrade_level
+        .min(CharacterProgression::MAX_UPGRADE_LEVEL);
+
+    let upgrade_effects = world
+        .assets
+        .get_upgrade_effects(config, upgrade_level)
+        .into_iter()
+        .filter_map(|def| {
+            apply_gameplay_effect(
+                world,
+                character_guid,
+                GameplayEffectSpec::new(def, 1.0, character_guid),
+            )
+        })
+        .collect();
+
+    with_ability_system(world, character_guid, |asc, _, _, _| {
+        asc.upgrade_effects = upgrade_effects;
+    });
+}
+
+/// Gives satiety to a player character, see `AbilitySystemComponent::init_satiety`.
+pub fn init_satiety(world: &mut World, character_guid: FNetworkGUID, value: Option<f32>) {
+    with_ability_system(world, character_guid, |asc, attributes, _, now| {
+        asc.init_satiety(attributes, value, now);
+    });
+
+    update_satiety_effect(world, character_guid);
+}
+
+/// Applies the satiety effects of player characters and saves their satiety
+/// to the player profile at a coarse interval.
+pub fn tick_satiety(world: &mut World) {
+    let player_states = world
+        .actors
+        .keys()
+        .copied()
+        .filter(|guid| {
+            world
+                .objects
+                .get(guid)
+                .is_some_and(|object| object.is::<PlayerState>())
+        })
+        .collect::<Vec<_>>();
+
+    for state_guid in player_states {
+        let Some(character_guid) = world
+            .get_actor_object::<PlayerState>(state_guid)
+            .and_then(|state| Some(state.data().equipped_players.get(0)?.get()))
+        else {
+            continue;
+        };
+
+        update_satiety_effect(world, character_guid);
+
+        if with_ability_system(world, character_guid, |asc, _, _, now| {
+            asc.satiety_save_due(now)
+        })
+        .unwrap_or_default()
+        {
+            save_satiety(world, state_guid);
+        }
+    }
+}
+
+/// Writes the satiety of a player to the player profile, e.g. when it logs out.
+pub fn save_satiety(world: &mut World, player_state_guid: FNetworkGUID) {
+    let Some((unique_id, character_guid)) = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .and_then(|state| {
+            let state = state.data();
+            let character = state.equipped_players.get(0)?.get();
+
+            Some((state.unique_id.device.get().to_string(), character))
+        })
+        .filter(|(unique_id, _)| !unique_id.is_empty())
+    else {
+        return;
+    };
+
+    let Some(satiety) = with_ability_system(world, character_guid, |asc, attributes, _, now| {
+        asc.satiety(attributes, now)
+    })
+    .flatten() else {
+        return;
+    };
+
+    let mut progression = PlayerProgression::load_or_create(&unique_id);
+    progression.satiety = Some(satiety);
+
+    if let Err(err) = progression.save(&unique_id) {
+        warn!("failed to save satiety of {unique_id}: {err}");
+    }
+}
+
+/// Copies the shields of the current character of every player to the `ShieldComponent`
+/// of the player, which replicates them to the owner and to the other players.
+/// Does nothing unless unverified layouts are replicated, the component has no other use.
+pub fn tick_shields(world: &mut World) {
+    if !world.globals.replicate_unverified_layouts {
+        return;
+    }
+
+    let player_states = world
+        .actors
+        .keys()
+        .copied()
+        .filter(|guid| {
+            world
+                .objects
+                .get(guid)
+                .is_some_and(|object| object.is::<PlayerState>())
+        })
+        .collect::<Vec<_>>();
+
+    for state_guid in player_states {
+        let Some((shield_component_guid, character_guid)) = world
+            .get_actor_object::<PlayerState>(state_guid)
+            .and_then(|state| {
+                let state = state.data();
+                Some((state.shield_component, state.equipped_players.get(0)?.get()))
+            })
+        else {
+            continue;
+        };
+
+        let Some((value, max)) = with_ability_system(world, character_guid, |asc, _, _, _| {
+            (asc.shield_value(), asc.shield_max())
+        }) else {
+            continue;
+        };
+
+        if let Some(mut shield_component) =
+            world.get_object_mut::<ShieldComponent>(shield_component_guid)
+        {
+            let shield_component = shield_component.data_mut();
+            shield_component.shield_value.set_value(value);
+            shield_component.shield_max.set_value(max);
+        }
+    }
+}
+
+/// Replaces the satiety effect of a character once its satiety crosses a configured threshold.
+fn update_satiety_effect(world: &mut World, character_guid: FNetworkGUID) {
+    let effects = &world.globals.satiety_effects;
+
+    let Some(threshold) = with_ability_system(world, character_guid, |asc, attributes, _, now| {
+        let threshold = asc.satiety_threshold(attributes, effects, now);
+        let gauge = asc.satiety.as_mut()?;
+
+        if gauge.threshold == threshold {
+            return None;
+        }
+
+        gauge.threshold = threshold;
+        if let Some(handle) = gauge.effect.take() {
+            asc.remove_active_gameplay_effect(attributes, handle);
+        }
+
+        Some(threshold)
+    })
+    .flatten() else {
+        return;
+    };
+
+    let Some(path) = threshold.map(|index| effects[index].effect.as_str()) else {
+        return;
+    };
+
+    let Some(def) = world.assets.get_gameplay_effect_config(path) else {
+        warn!("satiety effect {path} doesn't exist");
+        return;
+    };
+
+    let handle = apply_gameplay_effect(
+        world,
+        character_guid,
+        GameplayEffectSpec::new(def, 1.0, character_guid),
+    );
+
+    with_ability_system(world, character_guid, |asc, _, _, _| {
+        if let Some(gauge) = asc.satiety.as_mut() {
+            gauge.effect = handle;
+        }
+    });
+}
+
+/// Executes periodic effects, expires timed ones and recovers tenacity on every character of the world.
+pub fn tick_ability_systems(world: &mut World) {
+    let characters = world
+        .actors
+        .keys()
+        .copied()
+        .filter(|&guid| find_ability_system_component(world, guid).is_some())
+        .collect::<Vec<_>>();
+
+    for character_guid in characters {
+        with_ability_system(world, character_guid, |asc, attributes, _, now| {
+            asc.tick_active_gameplay_effects(attributes, now);
+            asc.tick_tenacity(attributes, now);
+        });
+    }
+}
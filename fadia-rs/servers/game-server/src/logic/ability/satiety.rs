@@ -0,0 +1,103 @@
+use crate::{
+    config::SatietyEffect,
+    logic::layout::{AbilitySystemComponent, HTAttributeSet},
+};
+
+use super::{ActiveGameplayEffectHandle, attribute::CharacterStat};
+
+/// Attribute modified by food effects, its magnitude is the restored satiety.
+pub const SATIETY_ATTRIBUTE: &str = "Satiety";
+/// Satiety lost per second of world time, full satiety lasts for two hours.
+const SATIETY_DECAY_RATE: f32 = 100.0 / 7200.0;
+/// Interval at which satiety is written to the player profile, in seconds of world time.
+/// It's saved on logout too, so at most this much decay is lost if the server stops.
+const SATIETY_SAVE_INTERVAL: f64 = 60.0;
+
+/// Satiety of a player character. Only player characters have one, monsters don't get hungry.
+///
+/// The current value isn't replicated by itself, `AbilitySystemComponent::satiety_change_time`
+/// is the world time at which the owner was last full, so the client can derive the current
+/// value as `SatietyMax - (now - satiety_change_time) * rate`.
+#[derive(Debug, Default)]
+pub struct SatietyGauge {
+    /// Index of the configured satiety effect which is applied to the owner.
+    pub threshold: Option<usize>,
+    pub effect: Option<ActiveGameplayEffectHandle>,
+    /// World time at which the satiety is next written to the player profile.
+    pub next_save_time: f64,
+}
+
+impl AbilitySystemComponent {
+    /// Current satiety of the owner, `None` if it doesn't have satiety.
+    pub fn satiety(&self, attributes: &HTAttributeSet, now: f64) -> Option<f32> {
+        self.satiety.as_ref()?;
+
+        let max_satiety = Self::max_satiety(attributes);
+        let elapsed = (now - f64::from(self.satiety_change_time.get())) as f32;
+
+        Some((max_satiety - elapsed * SATIETY_DECAY_RATE).clamp(0.0, max_satiety))
+    }
+
+    /// Gives satiety to the owner with a value loaded from the player profile, full if it's `None`.
+    pub fn init_satiety(&mut self, attributes: &HTAttributeSet, value: Option<f32>, now: f64) {
+        let value = value.unwrap_or_else(|| Self::max_satiety(attributes));
+
+        self.satiety = Some(SatietyGauge {
+            next_save_time: now + SATIETY_SAVE_INTERVAL,
+            ..Default::default()
+        });
+
+        self.set_satiety(attributes, value, now);
+    }
+
+    /// Adds satiety to the owner, capped by `SatietyMax`. Used by food effects.
+    pub fn restore_satiety(&mut self, attributes: &HTAttributeSet, amount: f32, now: f64) {
+        if let Some(satiety) = self.satiety(attributes, now) {
+            self.set_satiety(attributes, satiety + amount, now);
+        }
+    }
+
+    /// Whether the satiety of the owner is due to be saved, the next save is scheduled then.
+    pub fn satiety_save_due(&mut self, now: f64) -> bool {
+        let Some(gauge) = self
+            .satiety
+            .as_mut()
+            .filter(|gauge| now >= gauge.next_save_time)
+        else {
+            return false;
+        };
+
+        gauge.next_save_time = now + SATIETY_SAVE_INTERVAL;
+        true
+    }
+
+    /// Returns the index of the satiety effect which should be applied to the owner:
+    /// the one with the lowest threshold which is above the current satiety.
+    pub fn satiety_threshold(
+        &self,
+        attributes: &HTAttributeSet,
+        effects: &[SatietyEffect],
+        now: f64,
+    ) -> Option<usize> {
+        let ratio = self.satiety(attributes, now)? / Self::max_satiety(attributes).max(1.0);
+
+        effects
+            .iter()
+            .enumerate()
+            .filter(|(_, effect)| ratio < effect.below)
+            .min_by(|(_, a), (_, b)| a.below.total_cmp(&b.below))
+            .map(|(index, _)| index)
+    }
+
+    fn set_satiety(&mut self, attributes: &HTAttributeSet, value: f32, now: f64) {
+        let max_satiety = Self::max_satiety(attributes);
+        let value = value.clamp(0.0, max_satiety);
+
+        self.satiety_change_time
+            .set_value((now - f64::from((max_satiety - value) / SATIETY_DECAY_RATE)) as f32);
+    }
+
+    fn max_satiety(attributes: &HTAttributeSet) -> f32 {
+        CharacterStat::SatietyMax.properties(attributes).1.get()
+    }
+}
@@ -0,0 +1,144 @@
+use fadia_config::{blueprint::GameplayEffectConfig, dataset::gameplay_tag_matches};
+
+use crate::{
+    config::ShieldEffect,
+    logic::layout::{AbilitySystemComponent, HTAttributeSet},
+};
+
+use super::{ActiveGameplayEffectHandle, EvaluatedModifier, attribute::CharacterStat};
+
+/// Attribute modified by shield effects, its magnitude is the value of the granted shield.
+pub const SHIELD_ATTRIBUTE: &str = "Shield";
+/// Bonus to the value of shields granted to the owner, e.g. by the awakening buffs of Adler.
+const SHIELD_EFFICIENCY_ATTRIBUTE: &str = "ShieldEfficiency";
+/// Parent of the element tags of damage and shield effects, e.g. `Element.Flame`.
+const ELEMENT_TAG: &str = "Element";
+
+/// A shield which absorbs damage before HP of its owner does.
+///
+/// Shields granted by effects with a duration last as long as the effect, the ones
+/// granted by instant effects last until they're broken. A shield is removed once it's broken.
+#[derive(Debug)]
+pub struct Shield {
+    /// Effect which granted the shield, `None` for instant effects.
+    pub effect: Option<ActiveGameplayEffectHandle>,
+    pub value: f32,
+    pub max: f32,
+    /// Element tags of the damage absorbed by the shield, any damage is absorbed if it's empty.
+    pub elements: Vec<&'static str>,
+}
+
+impl Shield {
+    fn absorbs(&self, element: Option<&str>) -> bool {
+        self.elements.is_empty()
+            || element.is_some_and(|element| {
+                self.elements
+                    .iter()
+                    .any(|tag| gameplay_tag_matches(element, tag))
+            })
+    }
+}
+
+/// Element tags among the asset tags of an effect.
+pub fn element_tags(def: &'static GameplayEffectConfig) -> impl Iterator<Item = &'static str> {
+    def.asset_tags
+        .iter()
+        .filter(|tag| gameplay_tag_matches(tag, ELEMENT_TAG))
+        .map(String::as_str)
+}
+
+/// Sum of the shield modifiers of an effect.
+pub fn shield_amount(modifiers: &[EvaluatedModifier]) -> f32 {
+    modifiers
+        .iter()
+        .filter(|modifier| modifier.attribute == SHIELD_ATTRIBUTE)
+        .map(|modifier| modifier.magnitude)
+        .sum()
+}
+
+impl AbilitySystemComponent {
+    /// Remaining value of all shields of the owner.
+    pub fn shield_value(&self) -> f32 {
+        self.shields.iter().map(|shield| shield.value).sum()
+    }
+
+    /// Value of all shields of the owner when they were granted.
+    pub fn shield_max(&self) -> f32 {
+        self.shields.iter().map(|shield| shield.max).sum()
+    }
+
+    /// Grants a shield of `amount`, scaled by `ShieldEfficiency` of the owner.
+    /// A shield granted earlier by the same effect is replaced, e.g. when the effect is stacked.
+    pub fn grant_shield(
+        &mut self,
+        effect: Option<ActiveGameplayEffectHandle>,
+        amount: f32,
+        elements: Vec<&'static str>,
+    ) {
+        if let Some(effect) = effect {
+            self.remove_shield(effect);
+        }
+
+        let efficiency = self
+            .active_gameplay_effects
+            .current_values()
+            .extra(SHIELD_EFFICIENCY_ATTRIBUTE);
+        let value = amount * (1.0 + efficiency).max(0.0);
+
+        if value > 0.0 {
+            self.shields.push(Shield {
+                effect,
+                value,
+                max: value,
+                elements,
+            });
+        }
+    }
+
+    /// Grants the shield of an effect which value is configured, see `ShieldEffect`.
+    pub fn grant_configured_shield(
+        &mut self,
+        attributes: &HTAttributeSet,
+        effect: Option<ActiveGameplayEffectHandle>,
+        def: &'static GameplayEffectConfig,
+        config: &'static ShieldEffect,
+    ) {
+        let max_hp = CharacterStat::HpMax.properties(attributes).1.get();
+        let elements = config
+            .elements
+            .iter()
+            .map(String::as_str)
+            .chain(element_tags(def))
+            .collect();
+
+        self.grant_shield(effect, max_hp * config.hp_max_ratio, elements);
+    }
+
+    /// Removes the shield granted by an effect, once the effect is removed.
+    pub fn remove_shield(&mut self, effect: ActiveGameplayEffectHandle) {
+        self.shields.retain(|shield| shield.effect != Some(effect));
+    }
+
+    /// Absorbs damage of `element` with the shields which accept it, oldest first.
+    /// Returns the damage left for HP.
+    pub fn absorb_damage(&mut self, damage: f32, element: Option<&str>) -> f32 {
+        let mut remaining = damage;
+
+        for shield in self
+            .shields
+            .iter_mut()
+            .filter(|shield| shield.absorbs(element))
+        {
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let absorbed = remaining.min(shield.value);
+            shield.value -= absorbed;
+            remaining -= absorbed;
+        }
+
+        self.shields.retain(|shield| shield.value > 0.0);
+        remaining
+    }
+}
@@ -0,0 +1,109 @@
+use fadia_config::blueprint::EBreakableLevel;
+
+use crate::logic::layout::{AbilitySystemComponent, HTAttributeSet};
+
+use super::{ActiveGameplayEffectHandle, attribute::CharacterStat};
+
+/// Seconds a broken character stays broken before its tenacity is restored.
+const BREAK_DURATION: f64 = 5.0;
+
+/// Break gauge of a character. The current tenacity is replicated through
+/// `AbilitySystemComponent::unbal_speed`, its maximum is `UnbalReduceNatur`.
+///
+/// Hits reduce tenacity by their imbalance, scaled by `UnbalAccrueEfficiency` of the attacker.
+/// Once no hit was received for `TenacityResetTime` seconds, tenacity recovers
+/// by `TenacityRecoverSpeed` per second. The character breaks when tenacity reaches zero.
+#[derive(Debug, Default)]
+pub struct TenacityGauge {
+    last_hit_time: f64,
+    last_tick_time: Option<f64>,
+    broken_until: Option<f64>,
+    /// Break effect applied to the owner, removed once the break ends.
+    pub break_effect: Option<ActiveGameplayEffectHandle>,
+}
+
+/// Tenacity removed by a hit before it's scaled by the attacker.
+fn break_level_imbalance(level: EBreakableLevel) -> f32 {
+    match level {
+        EBreakableLevel::None => 0.0,
+        EBreakableLevel::Low => 5.0,
+        EBreakableLevel::Middle => 10.0,
+        EBreakableLevel::High => 20.0,
+    }
+}
+
+impl TenacityGauge {
+    pub fn is_broken(&self) -> bool {
+        self.broken_until.is_some()
+    }
+}
+
+impl AbilitySystemComponent {
+    /// Reduces tenacity of the owner by the imbalance of a hit.
+    /// Returns `true` if the hit broke the owner.
+    pub fn accrue_imbalance(
+        &mut self,
+        break_level: EBreakableLevel,
+        unbal_accrue_efficiency: f32,
+        now: f64,
+    ) -> bool {
+        let imbalance = break_level_imbalance(break_level) * unbal_accrue_efficiency.max(0.0);
+        if self.tenacity.is_broken() || imbalance <= 0.0 {
+            return false;
+        }
+
+        self.tenacity.last_hit_time = now;
+
+        let tenacity = (self.unbal_speed.get() - imbalance).max(0.0);
+        self.unbal_speed.set_value(tenacity);
+
+        if tenacity > 0.0 {
+            return false;
+        }
+
+        self.tenacity.broken_until = Some(now + BREAK_DURATION);
+        true
+    }
+
+    /// Recovers tenacity of the owner and ends an expired break.
+    pub fn tick_tenacity(&mut self, attributes: &mut HTAttributeSet, now: f64) {
+        let delta = now - self.tenacity.last_tick_time.replace(now).unwrap_or(now);
+        let max_tenacity = CharacterStat::UnbalReduceNatur
+            .properties(attributes)
+            .1
+            .get();
+
+        if let Some(broken_until) = self.tenacity.broken_until {
+            if now < broken_until {
+                return;
+            }
+
+            self.tenacity.broken_until = None;
+            self.unbal_speed.set_value(max_tenacity);
+
+            if let Some(handle) = self.tenacity.break_effect.take() {
+                self.remove_active_gameplay_effect(attributes, handle);
+            }
+
+            return;
+        }
+
+        let reset_time = CharacterStat::TenacityResetTime
+            .properties(attributes)
+            .1
+            .get();
+        let tenacity = self.unbal_speed.get();
+
+        if tenacity >= max_tenacity || now - self.tenacity.last_hit_time < f64::from(reset_time) {
+            return;
+        }
+
+        let recover_speed = CharacterStat::TenacityRecoverSpeed
+            .properties(attributes)
+            .1
+            .get();
+
+        self.unbal_speed
+            .set_value((tenacity + recover_speed * delta as f32).min(max_tenacity));
+    }
+}
@@ -12,9 +12,14 @@ pub struct NetPlayerIndex(pub u8);
 #[derive(Debug)]
 pub struct Actor {
     pub self_guid: FNetworkGUID,
+    /// Class default object the client spawns the actor from. It's only exported, the
+    /// replicated properties live under `self_guid`.
     pub archetype_guid: FNetworkGUID,
     pub position: FVector3d,
     pub rotation: FRotator,
+    /// Time of the last movement update sent by the client controlling the actor.
+    /// `None` while `position` is only where the server spawned it.
+    pub last_client_move_time: Option<f64>,
     pub controls_player: Option<NetPlayerIndex>,
 }
 
@@ -36,6 +41,7 @@ impl Actor {
             archetype_guid: archetype,
             position: FVector3d::default(),
             rotation: FRotator::default(),
+            last_client_move_time: None,
             controls_player: None,
         }
     }
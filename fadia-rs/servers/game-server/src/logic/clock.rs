@@ -0,0 +1,274 @@
+use tracing::info;
+
+use crate::{config::ClockSettings, net::World};
+
+use super::{
+    layout::{PlayerState, TimerClock},
+    state::HTGameState,
+};
+
+const HOUR_MS: u64 = 3600 * 1000;
+const DAY_MS: u64 = 24 * HOUR_MS;
+const WEEK_MS: u64 = 7 * DAY_MS;
+/// The unix epoch was a Thursday, the first Monday after it is 1970-01-05.
+const FIRST_MONDAY_MS: u64 = 4 * DAY_MS;
+
+/// Scheduled events of the server clock, handled by `GameModeBase::on_clock_event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockEvent {
+    DailyReset,
+    WeeklyReset,
+}
+
+/// Upcoming events of the server clock. Reset times are unix times in milliseconds.
+#[derive(Debug)]
+pub struct ClockSchedule {
+    pub next_daily_reset: u64,
+    pub next_weekly_reset: u64,
+    /// World time of the next update of the clocks of clients.
+    next_sync_time: f64,
+}
+
+/// Server time and day progression at one moment, as sent to clients.
+#[derive(Debug)]
+pub struct ClockSnapshot {
+    pub server_time: u64,
+    pub utc_time: u64,
+    pub time_of_day: f32,
+    pub next_daily_reset: u64,
+    pub next_weekly_reset: u64,
+}
+
+impl ClockSchedule {
+    pub fn new(settings: &ClockSettings, now: u64) -> Self {
+        Self {
+            next_daily_reset: next_occurrence(daily_reset_origin(settings), DAY_MS, now),
+            next_weekly_reset: next_occurrence(weekly_reset_origin(settings), WEEK_MS, now),
+            next_sync_time: 0.0,
+        }
+    }
+
+    /// Time of the last weekly reset, weekly limits count from it.
+    pub fn last_weekly_reset(&self) -> u64 {
+        self.next_weekly_reset.saturating_sub(WEEK_MS)
+    }
+
+    /// Returns the events which are due at `now` and schedules their next occurrences.
+    /// An event which was missed several times, e.g. after the clock was moved forward, is returned once.
+    pub fn poll(&mut self, settings: &ClockSettings, now: u64) -> Vec<ClockEvent> {
+        let mut events = Vec::new();
+
+        if now >= self.next_daily_reset {
+            self.next_daily_reset = next_occurrence(daily_reset_origin(settings), DAY_MS, now);
+            events.push(ClockEvent::DailyReset);
+        }
+
+        if now >= self.next_weekly_reset {
+            self.next_weekly_reset = next_occurrence(weekly_reset_origin(settings), WEEK_MS, now);
+            events.push(ClockEvent::WeeklyReset);
+        }
+
+        events
+    }
+}
+
+/// Hour of the in-game day, from 0 to 24. A full day lasts `ClockSettings::day_length` seconds.
+pub fn time_of_day(settings: &ClockSettings, now: u64) -> f32 {
+    let day_length = settings.day_length.max(1) * 1000;
+    (now % day_length) as f32 / day_length as f32 * 24.0
+}
+
+pub fn snapshot(world: &World) -> ClockSnapshot {
+    let server_time = world.clock.unix_timestamp_ms();
+
+    ClockSnapshot {
+        server_time,
+        utc_time: world.clock.unix_utc_timestamp_ms(),
+        time_of_day: time_of_day(&world.globals.clock, server_time),
+        next_daily_reset: world.clock_schedule.next_daily_reset,
+        next_weekly_reset: world.clock_schedule.next_weekly_reset,
+    }
+}
+
+/// Dispatches the due clock events to the game mode, and sends the server clock
+/// to clients every `ClockSettings::sync_interval` seconds.
+pub fn tick_clock(world: &mut World) {
+    let settings = &world.globals.clock;
+    let now = world.clock.unix_timestamp_ms();
+
+    for event in world.clock_schedule.poll(settings, now) {
+        info!("clock event: {event:?}");
+        world.game_mode().on_clock_event(world, event);
+    }
+
+    let time = world.time_seconds();
+    if time < world.clock_schedule.next_sync_time {
+        return;
+    }
+
+    world.clock_schedule.next_sync_time = time + settings.sync_interval;
+    sync_clocks(world);
+}
+
+fn sync_clocks(world: &mut World) {
+    let snapshot = snapshot(world);
+    let world_time = world.time_seconds();
+
+    if let Some(mut game_state) = world.get_actor_object_mut::<HTGameState>(world.game_state()) {
+        sync_game_state(game_state.data_mut(), &snapshot, world_time);
+    }
+
+    let timer_clocks = world
+        .actors
+        .keys()
+        .filter_map(|&guid| world.get_actor_object::<PlayerState>(guid))
+        .map(|state| state.data().timer_clock)
+        .collect::<Vec<_>>();
+
+    for guid in timer_clocks {
+        if let Some(mut timer_clock) = world.get_object_mut::<TimerClock>(guid) {
+            timer_clock.data_mut().sync(&snapshot);
+        }
+    }
+}
+
+pub fn sync_game_state(game_state: &mut HTGameState, snapshot: &ClockSnapshot, world_time: f64) {
+    game_state
+        .hotta_server_date_time_now
+        .set_value(snapshot.server_time);
+    game_state
+        .hotta_server_date_time_utc_now
+        .set_value(snapshot.utc_time);
+    game_state
+        .replicated_world_time_seconds_double
+        .set_value(world_time);
+}
+
+fn daily_reset_origin(settings: &ClockSettings) -> u64 {
+    settings.reset_hour % 24 * HOUR_MS
+}
+
+fn weekly_reset_origin(settings: &ClockSettings) -> u64 {
+    (FIRST_MONDAY_MS + settings.weekly_reset_day % 7 * DAY_MS + daily_reset_origin(settings))
+        % WEEK_MS
+}
+
+/// First time after `now` which is `origin` plus a multiple of `period`, `origin` is below `period`.
+fn next_occurrence(origin: u64, period: u64, now: u64) -> u64 {
+    (now + period - origin) / period * period + origin
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01 00:00, a Monday.
+    const MONDAY: u64 = 1_704_067_200_000;
+
+    fn settings(reset_hour: u64, weekly_reset_day: u64) -> ClockSettings {
+        ClockSettings {
+            reset_hour,
+            weekly_reset_day,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn schedules_resets_after_now() {
+        let settings = settings(5, 0);
+
+        let schedule = ClockSchedule::new(&settings, MONDAY + 4 * HOUR_MS);
+        assert_eq!(schedule.next_daily_reset, MONDAY + 5 * HOUR_MS);
+        assert_eq!(schedule.next_weekly_reset, MONDAY + 5 * HOUR_MS);
+
+        // A reset which is due right now is already over.
+        let schedule = ClockSchedule::new(&settings, MONDAY + 5 * HOUR_MS);
+        assert_eq!(schedule.next_daily_reset, MONDAY + DAY_MS + 5 * HOUR_MS);
+        assert_eq!(schedule.next_weekly_reset, MONDAY + WEEK_MS + 5 * HOUR_MS);
+        assert_eq!(schedule.last_weekly_reset(), MONDAY + 5 * HOUR_MS);
+    }
+
+    #[test]
+    fn weekly_reset_happens_on_the_configured_day() {
+        let settings = settings(5, 2);
+
+        let schedule = ClockSchedule::new(&settings, MONDAY + 4 * HOUR_MS);
+        assert_eq!(schedule.next_daily_reset, MONDAY + 5 * HOUR_MS);
+        assert_eq!(
+            schedule.next_weekly_reset,
+            MONDAY + 2 * DAY_MS + 5 * HOUR_MS
+        );
+
+        // Sunday evening, the next Wednesday is in the next week.
+        let schedule = ClockSchedule::new(&settings, MONDAY + 6 * DAY_MS + 20 * HOUR_MS);
+        assert_eq!(
+            schedule.next_weekly_reset,
+            MONDAY + 9 * DAY_MS + 5 * HOUR_MS
+        );
+        assert_eq!(
+            schedule.last_weekly_reset(),
+            MONDAY + 2 * DAY_MS + 5 * HOUR_MS
+        );
+    }
+
+    #[test]
+    fn out_of_range_settings_wrap_around() {
+        let schedule = ClockSchedule::new(&settings(29, 7), MONDAY);
+
+        assert_eq!(schedule.next_daily_reset, MONDAY + 5 * HOUR_MS);
+        assert_eq!(schedule.next_weekly_reset, MONDAY + 5 * HOUR_MS);
+    }
+
+    #[test]
+    fn poll_returns_due_events_once() {
+        let settings = settings(5, 0);
+        let mut schedule = ClockSchedule::new(&settings, MONDAY + 4 * HOUR_MS);
+
+        assert!(
+            schedule
+                .poll(&settings, MONDAY + 5 * HOUR_MS - 1)
+                .is_empty()
+        );
+        assert_eq!(
+            schedule.poll(&settings, MONDAY + 5 * HOUR_MS),
+            [ClockEvent::DailyReset, ClockEvent::WeeklyReset]
+        );
+        assert!(schedule.poll(&settings, MONDAY + 5 * HOUR_MS).is_empty());
+
+        assert_eq!(
+            schedule.poll(&settings, MONDAY + DAY_MS + 5 * HOUR_MS),
+            [ClockEvent::DailyReset]
+        );
+        assert_eq!(schedule.next_weekly_reset, MONDAY + WEEK_MS + 5 * HOUR_MS);
+    }
+
+    #[test]
+    fn poll_returns_missed_resets_once() {
+        let settings = settings(5, 0);
+        let mut schedule = ClockSchedule::new(&settings, MONDAY + 6 * HOUR_MS);
+
+        let now = MONDAY + 3 * DAY_MS + 6 * HOUR_MS;
+        assert_eq!(schedule.poll(&settings, now), [ClockEvent::DailyReset]);
+        assert_eq!(schedule.next_daily_reset, MONDAY + 4 * DAY_MS + 5 * HOUR_MS);
+
+        let now = MONDAY + 3 * WEEK_MS;
+        assert_eq!(
+            schedule.poll(&settings, now),
+            [ClockEvent::DailyReset, ClockEvent::WeeklyReset]
+        );
+        assert_eq!(schedule.next_weekly_reset, now + 5 * HOUR_MS);
+    }
+
+    #[test]
+    fn time_of_day_cycles_with_the_day_length() {
+        let settings = ClockSettings {
+            day_length: 7200,
+            ..Default::default()
+        };
+
+        assert_eq!(time_of_day(&settings, MONDAY), 0.0);
+        assert_eq!(time_of_day(&settings, MONDAY + 1800 * 1000), 6.0);
+        assert_eq!(time_of_day(&settings, MONDAY + 3600 * 1000), 12.0);
+        assert_eq!(time_of_day(&settings, MONDAY + 7200 * 1000), 0.0);
+    }
+}
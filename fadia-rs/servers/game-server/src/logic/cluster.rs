@@ -1,7 +1,8 @@
 use std::{
     io::{self, Cursor},
-    sync::mpsc,
+    sync::mpsc::{self, RecvTimeoutError},
     thread,
+    time::{Duration, Instant},
 };
 
 use bitstream_io::{BitReader, LittleEndian};
@@ -10,11 +11,18 @@ use tracing::{error, warn};
 
 use crate::{
     assets::GameAssets,
-    config::GameplayGlobals,
-    net::{ConnectParams, NetworkEventListener, ReceiveParams},
+    config::{GameplayGlobals, MapSettings},
+    net::{ConnectParams, IncomingPacket, NetworkEventListener, ReceiveParams},
 };
 
-use super::scope::{LogicScope, LogicScopeManager};
+use super::{
+    ability, clock, dungeon, gameplay_debugger, npc,
+    scope::{LogicScope, LogicScopeManager},
+    stamina, weather,
+};
+
+/// Interval of the server-side simulation, e.g. gameplay effect timers.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
 
 enum ClusterInput {
     NewConnection(ConnectParams),
@@ -36,24 +44,40 @@ impl NetworkEventListener for ClusterHandle {
 fn cluster_logic_loop(
     rx: mpsc::Receiver<ClusterInput>,
     globals: &'static GameplayGlobals,
+    default_map: &'static MapSettings,
     assets: &'static GameAssets,
 ) {
     let mut scope_manager = LogicScopeManager::default();
+    let mut next_tick = Instant::now() + TICK_INTERVAL;
 
-    while let Ok(input) = rx.recv() {
-        match input {
-            ClusterInput::NewConnection(params) => {
-                scope_manager.create_scope(params, globals, assets);
+    loop {
+        match rx.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+            Ok(ClusterInput::NewConnection(params)) => {
+                scope_manager.create_scope(params, globals, default_map, assets);
             }
-            ClusterInput::ReceivePacket(params) => {
+            Ok(ClusterInput::ReceivePacket(params)) => {
                 if let Some(scope) = scope_manager.get_scope_for_session(params.session_id) {
                     if let Err(err) = receive_packet(scope, params) {
                         error!("receive_packet failed: {err}");
                     }
+
+                    scope_manager.drop_closed_connections();
                 } else {
                     warn!("no scope for session_id: {}", params.session_id);
                 }
             }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if Instant::now() >= next_tick {
+            scope_manager.scopes.values_mut().for_each(|scope| {
+                if let Err(err) = tick_scope(scope) {
+                    error!("tick_scope failed: {err}");
+                }
+            });
+
+            next_tick = Instant::now() + TICK_INTERVAL;
         }
     }
 }
@@ -67,28 +91,69 @@ enum BitReadError {
 }
 
 fn receive_packet(scope: &mut LogicScope, params: ReceiveParams) -> Result<(), BitReadError> {
-    let world = &mut scope.world;
-    let connection = scope.connections.get_mut(&params.session_id).unwrap();
-
-    let mut r = BitReader::endian(Cursor::new(params.data.as_ref()), LittleEndian);
-    r.seek_bits(io::SeekFrom::Start(params.data_offset_in_bits as u64))?;
-
-    if let Err(err) = connection.received_packet(world, &mut r, params.data_size_in_bits, true) {
-        error!("failed to receive packet: {err}");
+    let (world, connection) = scope.connection_world(params.session_id).unwrap();
+
+    match connection.packet_handler.incoming(IncomingPacket {
+        data: params.data,
+        offset_in_bits: params.data_offset_in_bits,
+        size_in_bits: params.data_size_in_bits,
+    }) {
+        Ok(packet) => {
+            let mut r = BitReader::endian(Cursor::new(packet.data.as_ref()), LittleEndian);
+            r.seek_bits(io::SeekFrom::Start(packet.offset_in_bits as u64))?;
+
+            if let Err(err) = connection.received_packet(world, &mut r, packet.size_in_bits, true) {
+                error!("failed to receive packet: {err}");
+            }
+        }
+        Err(err) => error!("packet handler dropped packet: {err}"),
     }
 
     world.tick(connection);
+    scope.process_travels();
+
+    let (_, connection) = scope.connection_world(params.session_id).unwrap();
     connection.flush_net()?;
 
     Ok(())
 }
 
+fn tick_scope(scope: &mut LogicScope) -> io::Result<()> {
+    for world in scope.worlds.values_mut() {
+        clock::tick_clock(world);
+        ability::tick_ability_systems(world);
+        ability::tick_satiety(world);
+        ability::tick_shields(world);
+        npc::tick_monsters(world);
+        stamina::tick_stamina_recovery(world);
+        weather::tick_weather(world);
+        dungeon::tick_dungeon(world);
+    }
+
+    for connection in scope.connections.values_mut() {
+        let world = scope.worlds.get_mut(&connection.world).unwrap();
+        gameplay_debugger::tick_gameplay_debugger(world, connection);
+        world.tick(connection);
+    }
+
+    scope.process_travels();
+
+    for connection in scope.connections.values_mut() {
+        if connection.has_awaiting_send_packets() {
+            connection.flush_net()?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn allocate_cluster(
     globals: &'static GameplayGlobals,
+    default_map: &'static MapSettings,
     assets: &'static GameAssets,
 ) -> ClusterHandle {
     let (tx, rx) = mpsc::channel();
-    thread::spawn(move || cluster_logic_loop(rx, globals, assets));
+    thread::spawn(move || cluster_logic_loop(rx, globals, default_map, assets));
 
     ClusterHandle(tx)
 }
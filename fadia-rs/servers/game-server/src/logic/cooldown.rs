@@ -0,0 +1,346 @@
+use fadia_config::dataset::gameplay_tag_matches;
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::{PropertyString, PropertyU8, PropertyU64},
+    util::FName,
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::net::World;
+
+use super::{
+    hotta::{
+        HottaReplicatedObjectProperty, HottaReplicatedObjectPropertyContainer,
+        HottaReplicatedProperty, player_state::ItemEffectCDData,
+    },
+    layout::{CDManager, CooldownItem, PlayerState},
+    progression::PlayerProgression,
+    rpc::call_rpcs,
+};
+
+/// What the key of a cooldown refers to.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Serialize, Deserialize,
+)]
+#[repr(u8)]
+pub enum ECooldownType {
+    /// Cooldown tag of an ability, e.g. `CD.Skill.Fadia`. Like an owned tag, it also puts
+    /// its parent tags on cooldown, e.g. `CD.Skill`.
+    AbilityTag = 0,
+    /// Name of an ability blueprint, e.g. `GA_Fadia_Skill`.
+    Skill,
+    /// ID of an item of the item data table.
+    Item,
+}
+
+/// A cooldown saved in the player profile.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedCooldown {
+    pub cd_type: ECooldownType,
+    pub key: String,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl CDManager {
+    /// Returns `true` if `key` has a cooldown of type `cd_type` which ends after `now`.
+    pub fn is_on_cooldown(&self, cd_type: ECooldownType, key: &str, now: u64) -> bool {
+        self.active(now).any(|item| {
+            item.cd_type.get() == u8::from(cd_type)
+                && match cd_type {
+                    ECooldownType::AbilityTag => gameplay_tag_matches(item.key.get(), key),
+                    ECooldownType::Skill | ECooldownType::Item => item.key.get() == key,
+                }
+        })
+    }
+
+    /// Starts a cooldown of `duration_ms` milliseconds, replacing the previous one of the key.
+    /// Expired cooldowns are removed.
+    pub fn start_cooldown(
+        &mut self,
+        cd_type: ECooldownType,
+        key: &str,
+        duration_ms: u64,
+        now: u64,
+    ) {
+        self.start_cooldown_at(cd_type, key, now, now + duration_ms, now);
+    }
+
+    /// Puts cooldowns saved in the player profile back, the expired ones are skipped.
+    pub fn restore(&mut self, saved: &[SavedCooldown], now: u64) {
+        for cooldown in saved.iter().filter(|cooldown| cooldown.end_time > now) {
+            self.start_cooldown_at(
+                cooldown.cd_type,
+                &cooldown.key,
+                cooldown.start_time,
+                cooldown.end_time,
+                now,
+            );
+        }
+    }
+
+    pub fn to_saved(&self, now: u64) -> Vec<SavedCooldown> {
+        self.active(now)
+            .filter_map(|item| {
+                Some(SavedCooldown {
+                    cd_type: ECooldownType::try_from(item.cd_type.get()).ok()?,
+                    key: item.key.get().to_string(),
+                    start_time: item.start_time.get(),
+                    end_time: item.end_time.get(),
+                })
+            })
+            .collect()
+    }
+
+    /// Item cooldowns in the form of `HottaPlayerState::item_effect_cd_data`.
+    pub fn item_effect_cd_data(&self, now: u64) -> Vec<ItemEffectCDData> {
+        self.active(now)
+            .filter(|item| item.cd_type.get() == u8::from(ECooldownType::Item))
+            .map(|item| ItemEffectCDData {
+                item_id: item.key.get().to_string(),
+                start_time: item.start_time.get(),
+                end_time: item.end_time.get(),
+            })
+            .collect()
+    }
+
+    fn active(&self, now: u64) -> impl Iterator<Item = &CooldownItem> {
+        self.cooldowns
+            .iter()
+            .map(|(_, item)| item)
+            .filter(move |item| item.end_time.get() > now)
+    }
+
+    fn start_cooldown_at(
+        &mut self,
+        cd_type: ECooldownType,
+        key: &str,
+        start_time: u64,
+        end_time: u64,
+        now: u64,
+    ) {
+        let expired = self
+            .cooldowns
+            .iter()
+            .filter(|(_, item)| {
+                item.end_time.get() <= now
+                    || (item.cd_type.get() == u8::from(cd_type) && item.key.get() == key)
+            })
+            .map(|(element_id, _)| element_id)
+            .collect::<Vec<_>>();
+
+        for element_id in expired {
+            self.cooldowns.remove(element_id);
+        }
+
+        self.cooldowns.push(CooldownItem {
+            cd_type: PropertyU8::new(cd_type.into()),
+            key: PropertyString::new(key.to_string()),
+            start_time: PropertyU64::new(start_time),
+            end_time: PropertyU64::new(end_time),
+        });
+    }
+}
+
+/// Looks up the player state of the player which controls a character.
+pub fn find_player_state(world: &World, character_guid: FNetworkGUID) -> Option<FNetworkGUID> {
+    world.actors.keys().copied().find(|guid| {
+        world
+            .get_actor_object::<PlayerState>(*guid)
+            .is_some_and(|state| {
+                state
+                    .data()
+                    .equipped_players
+                    .get(0)
+                    .is_some_and(|character| character.get() == character_guid)
+            })
+    })
+}
+
+/// Runs `f` on the cooldown manager of a player, with the current server time in milliseconds.
+pub fn with_cd_manager<R>(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    f: impl FnOnce(&mut CDManager, u64) -> R,
+) -> Option<R> {
+    let cd_manager_guid = world
+        .get_actor_object::<PlayerState>(player_state_guid)?
+        .data()
+        .cd_manager;
+
+    let now = world.clock.unix_timestamp_ms();
+    let mut cd_manager = world.get_object_mut::<CDManager>(cd_manager_guid)?;
+    Some(f(cd_manager.data_mut(), now))
+}
+
+/// Restores the cooldowns saved in the player profile.
+pub fn init_cooldowns(world: &mut World, player_state_guid: FNetworkGUID, saved: &[SavedCooldown]) {
+    with_cd_manager(world, player_state_guid, |cd_manager, now| {
+        cd_manager.restore(saved, now);
+    });
+}
+
+/// Starts cooldowns of a player and saves them to the player profile.
+/// Item cooldowns are also sent to the client as `ItemEffectCDData`.
+pub fn start_cooldowns(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    keys: &[(ECooldownType, &str)],
+    duration_ms: u64,
+) {
+    let Some(item_effect_cd_data) = with_cd_manager(world, player_state_guid, |cd_manager, now| {
+        for &(cd_type, key) in keys {
+            cd_manager.start_cooldown(cd_type, key, duration_ms, now);
+        }
+
+        cd_manager.item_effect_cd_data(now)
+    }) else {
+        return;
+    };
+
+    if keys
+        .iter()
+        .any(|&(cd_type, _)| cd_type == ECooldownType::Item)
+        && let Some(player_state) = world.get_actor_object_mut::<PlayerState>(player_state_guid)
+    {
+        let container = HottaReplicatedObjectPropertyContainer {
+            owner: player_state_guid,
+            properties: vec![HottaReplicatedObjectProperty {
+                name: FName::Custom(String::from("ItemEffectCDData")),
+                datas: item_effect_cd_data.replicate(),
+            }],
+        };
+
+        call_rpcs!(player_state.send_replicated_object_property_array_to_client(container));
+    }
+
+    save_cooldowns(world, player_state_guid);
+}
+
+fn save_cooldowns(world: &mut World, player_state_guid: FNetworkGUID) {
+    let Some(unique_id) = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .map(|state| state.data().unique_id.device.get().to_string())
+        .filter(|unique_id| !unique_id.is_empty())
+    else {
+        return;
+    };
+
+    let Some(cooldowns) = with_cd_manager(world, player_state_guid, |cd_manager, now| {
+        cd_manager.to_saved(now)
+    }) else {
+        return;
+    };
+
+    let mut progression = PlayerProgression::load_or_create(&unique_id);
+    progression.cooldowns = cooldowns;
+
+    if let Err(err) = progression.save(&unique_id) {
+        warn!("failed to save cooldowns of {unique_id}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(cd_manager: &CDManager) -> Vec<(String, u64)> {
+        cd_manager
+            .cooldowns
+            .iter()
+            .map(|(_, item)| (item.key.get().to_string(), item.end_time.get()))
+            .collect()
+    }
+
+    #[test]
+    fn restarting_a_cooldown_replaces_it() {
+        let mut cd_manager = CDManager::default();
+
+        cd_manager.start_cooldown(ECooldownType::Skill, "GA_Fadia_Skill", 5000, 1000);
+        cd_manager.start_cooldown(ECooldownType::Item, "GA_Fadia_Skill", 5000, 1000);
+        cd_manager.start_cooldown(ECooldownType::Skill, "GA_Fadia_Skill", 2000, 2000);
+
+        assert_eq!(
+            keys(&cd_manager),
+            vec![
+                (String::from("GA_Fadia_Skill"), 6000),
+                (String::from("GA_Fadia_Skill"), 4000)
+            ]
+        );
+    }
+
+    #[test]
+    fn starting_a_cooldown_removes_expired_ones() {
+        let mut cd_manager = CDManager::default();
+
+        cd_manager.start_cooldown(ECooldownType::Item, "1001", 1000, 0);
+        cd_manager.start_cooldown(ECooldownType::Item, "1002", 5000, 0);
+        cd_manager.start_cooldown(ECooldownType::Item, "1003", 1000, 1000);
+
+        assert_eq!(
+            keys(&cd_manager),
+            vec![(String::from("1002"), 5000), (String::from("1003"), 2000)]
+        );
+    }
+
+    #[test]
+    fn cooldowns_end_at_their_end_time() {
+        let mut cd_manager = CDManager::default();
+        cd_manager.start_cooldown(ECooldownType::Item, "1001", 1000, 0);
+
+        assert!(cd_manager.is_on_cooldown(ECooldownType::Item, "1001", 999));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::Item, "1001", 1000));
+        assert!(cd_manager.item_effect_cd_data(1000).is_empty());
+    }
+
+    #[test]
+    fn ability_tag_cooldowns_cover_parent_tags() {
+        let mut cd_manager = CDManager::default();
+        cd_manager.start_cooldown(ECooldownType::AbilityTag, "CD.Skill.Fadia", 1000, 0);
+
+        assert!(cd_manager.is_on_cooldown(ECooldownType::AbilityTag, "CD.Skill.Fadia", 0));
+        assert!(cd_manager.is_on_cooldown(ECooldownType::AbilityTag, "cd.skill", 0));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::AbilityTag, "CD.Skill.Fadia.Ex", 0));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::AbilityTag, "CD.Skill.Fa", 0));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::Skill, "CD.Skill.Fadia", 0));
+    }
+
+    #[test]
+    fn skill_and_item_cooldowns_match_exact_keys() {
+        let mut cd_manager = CDManager::default();
+        cd_manager.start_cooldown(ECooldownType::Skill, "GA_Fadia", 1000, 0);
+
+        assert!(cd_manager.is_on_cooldown(ECooldownType::Skill, "GA_Fadia", 0));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::Skill, "GA_Fadia.Skill", 0));
+        assert!(!cd_manager.is_on_cooldown(ECooldownType::Item, "GA_Fadia", 0));
+    }
+
+    #[test]
+    fn restore_skips_expired_cooldowns() {
+        let saved = |key: &str, end_time| SavedCooldown {
+            cd_type: ECooldownType::Item,
+            key: key.to_string(),
+            start_time: 0,
+            end_time,
+        };
+        let mut cd_manager = CDManager::default();
+
+        cd_manager.restore(
+            &[
+                saved("1001", 1000),
+                saved("1002", 3000),
+                saved("1003", 2000),
+            ],
+            2000,
+        );
+
+        assert_eq!(keys(&cd_manager), vec![(String::from("1002"), 3000)]);
+
+        let restored = cd_manager.to_saved(2000);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].start_time, 0);
+        assert_eq!(restored[0].cd_type, ECooldownType::Item);
+    }
+}
@@ -0,0 +1,103 @@
+use fadia_engine::{
+    replication::property::{PropertyArray, PropertyName},
+    util::FName,
+};
+use tracing::{debug, warn};
+
+use crate::net::World;
+
+use super::layout::WorldDataLayers;
+
+/// `EDataLayerRuntimeState`, active layers are loaded and visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EDataLayerRuntimeState {
+    Unloaded,
+    Loaded,
+    Activated,
+}
+
+impl WorldDataLayers {
+    pub fn runtime_state(&self, name: &str) -> EDataLayerRuntimeState {
+        if position(&self.rep_active_data_layer_names, name).is_some() {
+            EDataLayerRuntimeState::Activated
+        } else if position(&self.rep_loaded_data_layer_names, name).is_some() {
+            EDataLayerRuntimeState::Loaded
+        } else {
+            EDataLayerRuntimeState::Unloaded
+        }
+    }
+
+    /// Moves a layer to the replicated array of its new state. Layers don't have parents,
+    /// so the effective arrays are the same as the plain ones.
+    /// Returns `false` if the layer already was in the state.
+    pub fn set_runtime_state(&mut self, name: &str, state: EDataLayerRuntimeState) -> bool {
+        if self.runtime_state(name) == state {
+            return false;
+        }
+
+        for names in [
+            &mut self.rep_active_data_layer_names,
+            &mut self.rep_loaded_data_layer_names,
+            &mut self.rep_effective_active_data_layer_names,
+            &mut self.rep_effective_loaded_data_layer_names,
+        ] {
+            if let Some(index) = position(names, name) {
+                names.remove(index);
+            }
+        }
+
+        let (names, effective_names) = match state {
+            EDataLayerRuntimeState::Unloaded => return true,
+            EDataLayerRuntimeState::Loaded => (
+                &mut self.rep_loaded_data_layer_names,
+                &mut self.rep_effective_loaded_data_layer_names,
+            ),
+            EDataLayerRuntimeState::Activated => (
+                &mut self.rep_active_data_layer_names,
+                &mut self.rep_effective_active_data_layer_names,
+            ),
+        };
+
+        names.push(PropertyName::new(FName::Custom(name.to_string())));
+        effective_names.push(PropertyName::new(FName::Custom(name.to_string())));
+        true
+    }
+}
+
+/// Applies the data layers configured for the map of the world.
+pub fn init_data_layers(world: &mut World) {
+    let map_name = world.map.name();
+
+    let Some(layers) = world.globals.data_layers.get(map_name) else {
+        warn!("no data layers are configured for {map_name}");
+        return;
+    };
+
+    for name in layers.loaded.iter() {
+        set_data_layer_state(world, name, EDataLayerRuntimeState::Loaded);
+    }
+
+    for name in layers.active.iter() {
+        set_data_layer_state(world, name, EDataLayerRuntimeState::Activated);
+    }
+}
+
+/// Changes the state of a data layer of the world, e.g. for an event or a quest.
+/// The change replicates to every client of the world.
+pub fn set_data_layer_state(world: &mut World, name: &str, state: EDataLayerRuntimeState) {
+    let Some(mut data_layers) =
+        world.get_actor_object_mut::<WorldDataLayers>(world.world_data_layers())
+    else {
+        return;
+    };
+
+    if data_layers.data_mut().set_runtime_state(name, state) {
+        debug!("data layer {name} is now {state:?}");
+    }
+}
+
+fn position(names: &PropertyArray<PropertyName>, name: &str) -> Option<usize> {
+    names
+        .iter()
+        .position(|layer| matches!(layer.get(), FName::Custom(layer) if layer == name))
+}
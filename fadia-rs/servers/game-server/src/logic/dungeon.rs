@@ -0,0 +1,519 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use fadia_engine::{FNetworkGUID, util::FName};
+use tracing::{info, warn};
+
+use crate::{
+    config::{DungeonSettings, MapSettings},
+    net::{NetConnection, World},
+};
+
+use super::{
+    ability,
+    hotta::{
+        HottaReplicatedObjectProperty, HottaReplicatedObjectPropertyContainer,
+        HottaReplicatedProperty, player_state::HottaPlayerState,
+    },
+    inventory,
+    layout::PlayerState,
+    progression::PlayerProgression,
+    rpc::call_rpcs,
+    travel::TravelDestination,
+};
+
+/// Seconds players stay in a finished dungeon before they're sent back.
+const RETURN_DELAY: f64 = 10.0;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DungeonError {
+    #[error("dungeon {0} doesn't exist")]
+    UnknownDungeon(String),
+    #[error("dungeon {0} is locked until {1} is cleared")]
+    Locked(String, String),
+    #[error("player is already in a dungeon")]
+    AlreadyInDungeon,
+    #[error("player isn't in a dungeon")]
+    NotInDungeon,
+    #[error("player hasn't joined the world")]
+    NotJoined,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DungeonResult {
+    /// Every monster of the instance was killed.
+    Success,
+    /// Every player in the instance is dead.
+    Failure,
+    Timeout,
+}
+
+/// The dungeon of an instance world. Times are world times in seconds.
+pub struct DungeonInstance {
+    pub settings: &'static DungeonSettings,
+    /// Map players return to once the dungeon is over.
+    pub return_map: &'static MapSettings,
+    pub end_time: f64,
+    pub result: Option<DungeonResult>,
+    monsters_killed: usize,
+    return_time: f64,
+}
+
+/// Clone IDs of `HottaPlayerState`.
+#[derive(Debug, Default)]
+pub struct CloneIds {
+    /// Dungeons which rewards were received since the last weekly reset.
+    pub received: Vec<String>,
+    /// Dungeons the player can enter.
+    pub unlocked: Vec<String>,
+    /// Dungeons the player has cleared.
+    pub cleared: Vec<String>,
+}
+
+impl DungeonInstance {
+    /// Result of the instance once `killed` of its `monsters` are dead. `players_alive` is
+    /// `None` while nobody has joined the instance, only the timer runs until then.
+    fn check_result(
+        &self,
+        now: f64,
+        monsters: usize,
+        killed: usize,
+        players_alive: Option<bool>,
+    ) -> Option<DungeonResult> {
+        match players_alive {
+            Some(_) if killed == monsters => Some(DungeonResult::Success),
+            Some(false) => Some(DungeonResult::Failure),
+            _ => (now >= self.end_time).then_some(DungeonResult::Timeout),
+        }
+    }
+}
+
+impl CloneIds {
+    pub fn new(
+        dungeons: &[DungeonSettings],
+        progression: &PlayerProgression,
+        last_weekly_reset: u64,
+    ) -> Self {
+        Self {
+            received: progression
+                .dungeon_rewards
+                .keys()
+                .filter(|id| received_rewards(progression, id, last_weekly_reset))
+                .cloned()
+                .collect(),
+            unlocked: dungeons
+                .iter()
+                .filter(|dungeon| is_unlocked(dungeon, progression))
+                .map(|dungeon| dungeon.id.clone())
+                .collect(),
+            cleared: progression.cleared_dungeons.iter().cloned().collect(),
+        }
+    }
+
+    pub fn fill_hotta_player_state(self, state: &mut HottaPlayerState) {
+        state.receive_clone_system_contain_ids = self.received;
+        state.weekly_unlock_clone_ids = self.unlocked;
+        state.clone_system_challenge_success_ids = self.cleared;
+    }
+
+    fn replicate(&self, owner: FNetworkGUID) -> HottaReplicatedObjectPropertyContainer {
+        let property = |name: &str, ids: &Vec<String>| HottaReplicatedObjectProperty {
+            name: FName::Custom(name.to_string()),
+            datas: ids.replicate(),
+        };
+
+        HottaReplicatedObjectPropertyContainer {
+            owner,
+            properties: vec![
+                property("ReceiveCloneSystemContainIDs", &self.received),
+                property("WeeklyUnlockCloneIDs", &self.unlocked),
+                property("CloneSystemChallengeSuccessIDs", &self.cleared),
+            ],
+        }
+    }
+}
+
+/// Clone IDs of a player, from the player profile.
+pub fn clone_ids(world: &World, player_state_guid: FNetworkGUID) -> CloneIds {
+    unique_id(world, player_state_guid)
+        .map(|unique_id| {
+            CloneIds::new(
+                &world.globals.dungeons,
+                &PlayerProgression::load_or_create(&unique_id),
+                world.clock_schedule.last_weekly_reset(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Sends the clone IDs of a player to its client, e.g. after a dungeon or the weekly reset.
+pub fn send_clone_ids(world: &mut World, player_state_guid: FNetworkGUID) {
+    let container = clone_ids(world, player_state_guid).replicate(player_state_guid);
+
+    if let Some(player_state) = world.get_actor_object_mut::<PlayerState>(player_state_guid) {
+        call_rpcs!(player_state.send_replicated_object_property_array_to_client(container));
+    }
+}
+
+/// Weekly rewards can be received again, the clients are told so.
+pub fn weekly_reset(world: &mut World) {
+    for (player_state_guid, _) in players(world) {
+        send_clone_ids(world, player_state_guid);
+    }
+}
+
+/// Sends a player to a new private instance of a dungeon.
+/// There are no parties yet, so every player who enters gets an instance of their own.
+pub fn enter_dungeon(
+    world: &mut World,
+    connection: &mut NetConnection,
+    id: &str,
+) -> Result<(), DungeonError> {
+    let settings = world
+        .globals
+        .find_dungeon(id)
+        .ok_or_else(|| DungeonError::UnknownDungeon(id.to_string()))?;
+
+    if world.dungeon.is_some() {
+        return Err(DungeonError::AlreadyInDungeon);
+    }
+
+    let unique_id = connection
+        .player_controller
+        .and(connection.unique_id.get())
+        .ok_or(DungeonError::NotJoined)?;
+
+    if let Some(required) = settings.requires.as_ref()
+        && !is_unlocked(settings, &PlayerProgression::load_or_create(unique_id))
+    {
+        return Err(DungeonError::Locked(id.to_string(), required.clone()));
+    }
+
+    static NEXT_INSTANCE_ID: AtomicU32 = AtomicU32::new(1);
+    let instance_id = NEXT_INSTANCE_ID.fetch_add(1, Ordering::SeqCst);
+
+    info!("{unique_id} enters instance {instance_id} of dungeon {id}");
+
+    world.remove_player(connection);
+    connection.pending_travel = Some(TravelDestination::Dungeon(settings, instance_id));
+    Ok(())
+}
+
+/// Sends a player back from a dungeon before it's over, it's not rewarded.
+pub fn leave_dungeon(
+    world: &mut World,
+    connection: &mut NetConnection,
+) -> Result<(), DungeonError> {
+    let return_map = world
+        .dungeon
+        .as_ref()
+        .ok_or(DungeonError::NotInDungeon)?
+        .return_map;
+
+    world.remove_player(connection);
+    connection.pending_travel = Some(TravelDestination::Map(return_map));
+    Ok(())
+}
+
+/// Turns a world created from the map of a dungeon into an instance of it.
+pub fn start_dungeon(
+    world: &mut World,
+    settings: &'static DungeonSettings,
+    return_map: &'static MapSettings,
+) {
+    info!(
+        "started dungeon {} with {} monsters and a time limit of {}s",
+        settings.id,
+        world.monsters().len(),
+        settings.time_limit
+    );
+
+    world.dungeon = Some(DungeonInstance {
+        settings,
+        return_map,
+        end_time: world.time_seconds() + settings.time_limit as f64,
+        result: None,
+        monsters_killed: 0,
+        return_time: 0.0,
+    });
+}
+
+/// Ends the dungeon of an instance once every monster is killed,
+/// every player is dead or the time is up.
+pub fn tick_dungeon(world: &mut World) {
+    let now = world.time_seconds();
+    let Some(instance) = world
+        .dungeon
+        .as_ref()
+        .filter(|instance| instance.result.is_none())
+    else {
+        return;
+    };
+
+    let monsters_killed = instance.monsters_killed;
+
+    let killed = world
+        .monsters()
+        .iter()
+        .filter(|&&guid| !ability::is_alive(world, guid))
+        .count();
+
+    if killed != monsters_killed {
+        info!("killed {killed}/{} monsters", world.monsters().len());
+
+        if let Some(instance) = world.dungeon.as_mut() {
+            instance.monsters_killed = killed;
+        }
+    }
+
+    let characters = players(world)
+        .into_iter()
+        .filter_map(|(_, character)| character)
+        .collect::<Vec<_>>();
+
+    let players_alive = (!characters.is_empty()).then(|| {
+        characters
+            .iter()
+            .any(|&guid| ability::is_alive(world, guid))
+    });
+
+    let result = world.dungeon.as_ref().and_then(|instance| {
+        instance.check_result(now, world.monsters().len(), killed, players_alive)
+    });
+
+    if let Some(result) = result {
+        finish_dungeon(world, result);
+    }
+}
+
+/// Sends a player of a finished dungeon back to the map it came from.
+pub fn return_finished_player(world: &mut World, connection: &mut NetConnection) {
+    let now = world.time_seconds();
+    let Some(instance) = world
+        .dungeon
+        .as_ref()
+        .filter(|instance| instance.result.is_some() && now >= instance.return_time)
+    else {
+        return;
+    };
+
+    if connection.pending_travel.is_none() {
+        let return_map = instance.return_map;
+
+        world.remove_player(connection);
+        connection.pending_travel = Some(TravelDestination::Map(return_map));
+    }
+}
+
+/// Marks the dungeon as cleared for every player in the instance on success,
+/// and grants its rewards to the players who didn't receive them this week.
+fn finish_dungeon(world: &mut World, result: DungeonResult) {
+    let now = world.time_seconds();
+    let Some(instance) = world.dungeon.as_mut() else {
+        return;
+    };
+
+    instance.result = Some(result);
+    instance.return_time = now + RETURN_DELAY;
+
+    let settings = instance.settings;
+    info!("dungeon {} is over: {result:?}", settings.id);
+
+    if result != DungeonResult::Success {
+        return;
+    }
+
+    let time = world.clock.unix_timestamp_ms();
+    let last_weekly_reset = world.clock_schedule.last_weekly_reset();
+
+    for (player_state_guid, _) in players(world) {
+        let Some(unique_id) = unique_id(world, player_state_guid) else {
+            continue;
+        };
+
+        let mut progression = PlayerProgression::load_or_create(&unique_id);
+        progression.cleared_dungeons.insert(settings.id.clone());
+
+        let rewarded = received_rewards(&progression, &settings.id, last_weekly_reset);
+
+        if !rewarded {
+            progression
+                .dungeon_rewards
+                .insert(settings.id.clone(), time);
+        }
+
+        if let Err(err) = progression.save(&unique_id) {
+            warn!("failed to save dungeon progression of {unique_id}: {err}");
+        }
+
+        if !rewarded
+            && let Err(err) = inventory::give_items(world, player_state_guid, &settings.rewards)
+        {
+            warn!("failed to reward {unique_id}: {err}");
+        }
+
+        send_clone_ids(world, player_state_guid);
+    }
+}
+
+/// A dungeon is unlocked once the dungeon it requires is cleared.
+fn is_unlocked(settings: &DungeonSettings, progression: &PlayerProgression) -> bool {
+    settings
+        .requires
+        .as_ref()
+        .is_none_or(|required| progression.cleared_dungeons.contains(required))
+}
+
+/// Rewards of a dungeon are received once per week, counting from the last weekly reset.
+fn received_rewards(progression: &PlayerProgression, id: &str, last_weekly_reset: u64) -> bool {
+    progression
+        .dungeon_rewards
+        .get(id)
+        .is_some_and(|&time| time >= last_weekly_reset)
+}
+
+/// Player states in the world, with the current characters of the players.
+fn players(world: &World) -> Vec<(FNetworkGUID, Option<FNetworkGUID>)> {
+    world
+        .actors
+        .keys()
+        .filter_map(|&guid| {
+            let state = world.get_actor_object::<PlayerState>(guid)?;
+            let character = state.data().equipped_players.get(0).map(|pawn| pawn.get());
+
+            Some((guid, character))
+        })
+        .collect()
+}
+
+fn unique_id(world: &World, player_state_guid: FNetworkGUID) -> Option<String> {
+    world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .map(|state| state.data().unique_id.device.get().to_string())
+        .filter(|unique_id| !unique_id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn dungeon(id: &str, requires: Option<&str>) -> DungeonSettings {
+        DungeonSettings {
+            id: id.to_string(),
+            map: String::from("XL_map_bigworld_test"),
+            time_limit: 900,
+            rewards: BTreeMap::new(),
+            requires: requires.map(str::to_string),
+        }
+    }
+
+    fn instance(end_time: f64) -> DungeonInstance {
+        DungeonInstance {
+            settings: Box::leak(Box::new(dungeon("Dungeon_A", None))),
+            return_map: Box::leak(Box::new(MapSettings {
+                path: String::from("/Game/Maps/Map_bigworld/XL_map_bigworld_test"),
+                player_start: [0.0; 3],
+            })),
+            end_time,
+            result: None,
+            monsters_killed: 0,
+            return_time: 0.0,
+        }
+    }
+
+    #[test]
+    fn dungeons_unlock_once_their_requirement_is_cleared() {
+        let dungeons = [
+            dungeon("Dungeon_A", None),
+            dungeon("Dungeon_B", Some("Dungeon_A")),
+        ];
+        let mut progression = PlayerProgression::default();
+
+        assert!(is_unlocked(&dungeons[0], &progression));
+        assert!(!is_unlocked(&dungeons[1], &progression));
+        assert_eq!(
+            CloneIds::new(&dungeons, &progression, 0).unlocked,
+            ["Dungeon_A"]
+        );
+
+        progression
+            .cleared_dungeons
+            .insert(String::from("Dungeon_A"));
+
+        assert!(is_unlocked(&dungeons[1], &progression));
+        assert_eq!(
+            CloneIds::new(&dungeons, &progression, 0).unlocked,
+            ["Dungeon_A", "Dungeon_B"]
+        );
+    }
+
+    #[test]
+    fn rewards_are_received_once_per_week() {
+        let last_weekly_reset = 1_000;
+        let mut progression = PlayerProgression::default();
+        progression
+            .dungeon_rewards
+            .insert(String::from("Dungeon_A"), last_weekly_reset - 1);
+        progression
+            .dungeon_rewards
+            .insert(String::from("Dungeon_B"), last_weekly_reset);
+
+        assert!(!received_rewards(
+            &progression,
+            "Dungeon_A",
+            last_weekly_reset
+        ));
+        assert!(received_rewards(
+            &progression,
+            "Dungeon_B",
+            last_weekly_reset
+        ));
+        assert!(!received_rewards(
+            &progression,
+            "Dungeon_C",
+            last_weekly_reset
+        ));
+        assert_eq!(
+            CloneIds::new(&[], &progression, last_weekly_reset).received,
+            ["Dungeon_B"]
+        );
+    }
+
+    #[test]
+    fn instances_time_out_at_the_time_limit() {
+        let instance = instance(100.0);
+
+        assert_eq!(instance.check_result(99.0, 2, 1, Some(true)), None);
+        assert_eq!(
+            instance.check_result(100.0, 2, 1, Some(true)),
+            Some(DungeonResult::Timeout)
+        );
+
+        // Only the timer runs until a player joins.
+        assert_eq!(instance.check_result(99.0, 2, 2, None), None);
+        assert_eq!(
+            instance.check_result(100.0, 2, 2, None),
+            Some(DungeonResult::Timeout)
+        );
+    }
+
+    #[test]
+    fn instances_end_once_monsters_or_players_are_dead() {
+        let instance = instance(100.0);
+
+        assert_eq!(
+            instance.check_result(50.0, 2, 2, Some(true)),
+            Some(DungeonResult::Success)
+        );
+        assert_eq!(
+            instance.check_result(50.0, 2, 1, Some(false)),
+            Some(DungeonResult::Failure)
+        );
+        // Killing the last monster wins over the timer and the death of the players.
+        assert_eq!(
+            instance.check_result(100.0, 2, 2, Some(false)),
+            Some(DungeonResult::Success)
+        );
+    }
+}
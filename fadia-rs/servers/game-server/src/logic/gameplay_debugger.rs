@@ -0,0 +1,441 @@
+use bitstream_io::BitWrite;
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::ReplicatedProperty,
+    rotator::FRotator,
+    util::{FStringWriteExt, OutBitWriter, WritePrimitivesExt},
+    vector::FVector3d,
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use tracing::debug;
+
+use crate::net::{NetConnection, SpawnActorParams, World};
+
+use super::{
+    ability,
+    actor::NetRole,
+    layout::{
+        GameplayDebugger, HTPlayerCharacter, MonsterCharacter, PlayerControllerBase, PlayerState,
+        VehiclePawn,
+    },
+};
+
+const GAMEPLAY_DEBUGGER_CLASS: &[&str] = &[
+    "/Script/GameplayDebugger",
+    "GameplayDebuggerCategoryReplicator",
+];
+
+/// The replicator is opened after the weapon of the player.
+pub const GAMEPLAY_DEBUGGER_CHANNEL_INDEX: u32 = 12;
+/// Interval at which the categories are refilled, the debugger is read by a person.
+const UPDATE_INTERVAL: f64 = 0.5;
+/// Actors farther from the debug actor aren't listed by `NetRoles`.
+const NEARBY_RADIUS: f64 = 5000.0;
+const MAX_NEARBY_ACTORS: usize = 16;
+
+/// Categories of the in-game debugger filled by the server.
+/// IDs are the positions of the categories in the `GameplayDebugger` settings of the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(i32)]
+pub enum GameplayDebuggerCategory {
+    /// Net roles of the actors around the debug actor.
+    NetRoles = 0,
+    /// Attributes, active effects and abilities of the debug actor.
+    AbilitySystem,
+    /// Server AI of the debug actor, if it's a monster.
+    Ai,
+    /// Channels and net speed of the connection of the player.
+    Net,
+}
+
+impl GameplayDebuggerCategory {
+    const ALL: [Self; 4] = [Self::NetRoles, Self::AbilitySystem, Self::Ai, Self::Net];
+
+    fn bit(self) -> u32 {
+        1 << i32::from(self)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GameplayDebuggerError {
+    #[error("category {0} doesn't exist")]
+    UnknownCategory(i32),
+    #[error("replicator {0:?} doesn't exist")]
+    NoReplicator(FNetworkGUID),
+}
+
+/// `FGameplayDebuggerNetPack`, the text of the categories of a replicator.
+///
+/// The encoding follows `FGameplayDebuggerNetPack::NetDeltaSerialize`: the number of changed
+/// categories, then the ID of every changed category, a mask of its changed parts and the parts.
+/// Shapes and data packs aren't filled by the server.
+#[derive(Debug, Default)]
+pub struct GameplayDebuggerNetPack {
+    categories: Vec<CategoryData>,
+}
+
+#[derive(Debug, Default)]
+struct CategoryData {
+    text_lines: Vec<String>,
+    is_enabled: bool,
+    changed: u8,
+}
+
+impl CategoryData {
+    const TEXT_LINES_CHANGED: u8 = 1 << 0;
+    const ENABLED_CHANGED: u8 = 1 << 3;
+}
+
+impl GameplayDebuggerNetPack {
+    pub fn set_category(
+        &mut self,
+        category: GameplayDebuggerCategory,
+        is_enabled: bool,
+        text_lines: Vec<String>,
+    ) {
+        let index = i32::from(category) as usize;
+        if self.categories.len() <= index {
+            self.categories
+                .resize_with(index + 1, CategoryData::default);
+        }
+
+        let data = &mut self.categories[index];
+        if data.text_lines != text_lines {
+            data.text_lines = text_lines;
+            data.changed |= CategoryData::TEXT_LINES_CHANGED;
+        }
+
+        if data.is_enabled != is_enabled {
+            data.is_enabled = is_enabled;
+            data.changed |= CategoryData::ENABLED_CHANGED;
+        }
+    }
+}
+
+impl ReplicatedProperty for GameplayDebuggerNetPack {
+    fn is_changed(&self) -> bool {
+        self.categories.iter().any(|data| data.changed != 0)
+    }
+
+    fn acknowledge_changes(&mut self) {
+        for data in self.categories.iter_mut() {
+            data.changed = 0;
+        }
+    }
+
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        let changed = self
+            .categories
+            .iter()
+            .enumerate()
+            .filter(|(_, data)| data.changed != 0)
+            .collect::<Vec<_>>();
+
+        w.write_i32(changed.len() as i32)?;
+
+        for (id, data) in changed {
+            w.write_i32(id as i32)?;
+            w.write_u8(data.changed)?;
+
+            if data.changed & CategoryData::TEXT_LINES_CHANGED != 0 {
+                w.write_i32(data.text_lines.len() as i32)?;
+
+                for line in data.text_lines.iter() {
+                    w.write_string(line)?;
+                }
+            }
+
+            if data.changed & CategoryData::ENABLED_CHANGED != 0 {
+                w.write_bit(data.is_enabled)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Spawns the replicator of a player, if the debugger is enabled by `GameplayGlobals::gameplay_debugger`.
+pub fn spawn_gameplay_debugger(
+    world: &mut World,
+    connection: &mut NetConnection,
+    controller_guid: FNetworkGUID,
+) {
+    if !world.globals.gameplay_debugger {
+        return;
+    }
+
+    let archetype = world.register_hierarchy_for_static_objects(GAMEPLAY_DEBUGGER_CLASS);
+    let (guid, replicator) = GameplayDebugger::new(world, controller_guid);
+
+    let guid = world.spawn_actor(
+        SpawnActorParams::Dynamic {
+            guid,
+            pos: FVector3d::default(),
+            rot: FRotator::default(),
+            archetype,
+            archetype_rep: Box::new(replicator),
+        },
+        Vec::new(),
+    );
+
+    world.open_actor_channel_at(connection, GAMEPLAY_DEBUGGER_CHANNEL_INDEX, guid);
+}
+
+/// `ServerSetEnabled`, the debugger describes the character of the player until
+/// the client picks another actor.
+pub fn set_enabled(world: &mut World, replicator_guid: FNetworkGUID, enable: bool) {
+    let pawn_guid = world
+        .get_actor_object::<GameplayDebugger>(replicator_guid)
+        .and_then(|replicator| {
+            world.get_actor_object::<PlayerControllerBase>(replicator.data().owner_pc.get())
+        })
+        .and_then(|controller| {
+            world.get_actor_object::<PlayerState>(controller.data().player_state.get())
+        })
+        .and_then(|state| state.data().equipped_players.get(0).map(|pawn| pawn.get()));
+
+    if let Some(mut replicator) = world.get_actor_object_mut::<GameplayDebugger>(replicator_guid) {
+        let replicator = replicator.data_mut();
+        replicator.is_enabled.set_value(enable);
+
+        if enable
+            && !replicator.debug_actor.get().is_valid()
+            && let Some(pawn_guid) = pawn_guid
+        {
+            replicator.debug_actor.set_value(pawn_guid);
+        }
+
+        // The categories are refilled right away.
+        replicator.next_update_time = 0.0;
+    }
+
+    debug!("gameplay debugger {replicator_guid:?} enabled: {enable}");
+}
+
+/// `ServerSetCategoryEnabled`
+pub fn set_category_enabled(
+    world: &mut World,
+    replicator_guid: FNetworkGUID,
+    category_id: i32,
+    enable: bool,
+) -> Result<(), GameplayDebuggerError> {
+    let category = GameplayDebuggerCategory::try_from(category_id)
+        .map_err(|_| GameplayDebuggerError::UnknownCategory(category_id))?;
+
+    let mut replicator = world
+        .get_actor_object_mut::<GameplayDebugger>(replicator_guid)
+        .ok_or(GameplayDebuggerError::NoReplicator(replicator_guid))?;
+
+    let replicator = replicator.data_mut();
+    if enable {
+        replicator.enabled_categories |= category.bit();
+    } else {
+        replicator.enabled_categories &= !category.bit();
+    }
+
+    replicator.next_update_time = 0.0;
+    Ok(())
+}
+
+/// `ServerSetDebugActor`, actors which don't exist on the server are ignored.
+pub fn set_debug_actor(world: &mut World, replicator_guid: FNetworkGUID, actor_guid: FNetworkGUID) {
+    if !world.actors.contains_key(&actor_guid) {
+        return;
+    }
+
+    if let Some(mut replicator) = world.get_actor_object_mut::<GameplayDebugger>(replicator_guid) {
+        let replicator = replicator.data_mut();
+        replicator.debug_actor.set_value(actor_guid);
+        replicator.next_update_time = 0.0;
+    }
+}
+
+/// Refills the enabled categories of the replicator of a connection.
+pub fn tick_gameplay_debugger(world: &mut World, connection: &NetConnection) {
+    let Some(replicator_guid) = connection.get_channel_actor(GAMEPLAY_DEBUGGER_CHANNEL_INDEX)
+    else {
+        return;
+    };
+
+    let now = world.time_seconds();
+    let Some((debug_actor, enabled_categories)) = world
+        .get_actor_object::<GameplayDebugger>(replicator_guid)
+        .map(|replicator| replicator.data())
+        .filter(|replicator| replicator.is_enabled.get() && now >= replicator.next_update_time)
+        .map(|replicator| (replicator.debug_actor.get(), replicator.enabled_categories))
+    else {
+        return;
+    };
+
+    let categories = GameplayDebuggerCategory::ALL.map(|category| {
+        let is_enabled = enabled_categories & category.bit() != 0;
+        let text_lines = if is_enabled {
+            match category {
+                GameplayDebuggerCategory::NetRoles => net_roles_lines(world, debug_actor),
+                GameplayDebuggerCategory::AbilitySystem => ability_system_lines(world, debug_actor),
+                GameplayDebuggerCategory::Ai => ai_lines(world, debug_actor),
+                GameplayDebuggerCategory::Net => net_lines(connection),
+            }
+        } else {
+            Vec::new()
+        };
+
+        (category, is_enabled, text_lines)
+    });
+
+    if let Some(mut replicator) = world.get_actor_object_mut::<GameplayDebugger>(replicator_guid) {
+        let replicator = replicator.data_mut();
+        replicator.next_update_time = now + UPDATE_INTERVAL;
+
+        for (category, is_enabled, text_lines) in categories {
+            replicator
+                .replicated_data
+                .set_category(category, is_enabled, text_lines);
+        }
+    }
+}
+
+fn net_roles_lines(world: &World, debug_actor: FNetworkGUID) -> Vec<String> {
+    let Some(origin) = world.actors.get(&debug_actor).map(|actor| &actor.position) else {
+        return vec![String::from("no debug actor")];
+    };
+
+    let mut nearby = world
+        .actors
+        .values()
+        .filter(|actor| actor.self_guid != debug_actor)
+        .map(|actor| (actor.position.dist_2d(origin), actor))
+        .filter(|&(distance, _)| distance <= NEARBY_RADIUS)
+        .collect::<Vec<_>>();
+
+    nearby.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    nearby
+        .into_iter()
+        .take(MAX_NEARBY_ACTORS)
+        .map(|(distance, actor)| {
+            let class = world
+                .net_guid_cache
+                .get_path_name_by_guid(actor.archetype_guid);
+
+            let roles = net_roles(world, actor.self_guid)
+                .map(|(role, remote_role)| format!("{role:?}/{remote_role:?}"))
+                .unwrap_or_else(|| String::from("-"));
+
+            format!(
+                "{:?} {class} {:.0}m role: {roles} player: {:?}",
+                actor.self_guid,
+                distance / 100.0,
+                actor.controls_player
+            )
+        })
+        .collect()
+}
+
+/// Local and remote roles of the actors which move around the world.
+fn net_roles(world: &World, guid: FNetworkGUID) -> Option<(NetRole, NetRole)> {
+    if let Some(character) = world.get_actor_object::<HTPlayerCharacter>(guid) {
+        let character = character.data();
+        Some((character.role.get(), character.remote_role.get()))
+    } else if let Some(monster) = world.get_actor_object::<MonsterCharacter>(guid) {
+        let monster = monster.data();
+        Some((monster.role.get(), monster.remote_role.get()))
+    } else {
+        world
+            .get_actor_object::<VehiclePawn>(guid)
+            .map(|vehicle| (vehicle.data().role.get(), vehicle.data().remote_role.get()))
+    }
+}
+
+fn ability_system_lines(world: &mut World, debug_actor: FNetworkGUID) -> Vec<String> {
+    ability::with_ability_system(world, debug_actor, |asc, attributes, _, now| {
+        let mut lines = vec![
+            format!(
+                "HP {:.0}/{:.0}{}",
+                asc.hp_current.get(),
+                asc.max_hp.get(),
+                if asc.is_dead() { " (dead)" } else { "" }
+            ),
+            format!(
+                "ATK {:.0} DEF {:.0} CRIT {:.0} charge {:.0}/{:.0}",
+                attributes.atk_cur.get(),
+                attributes.def_cur.get(),
+                attributes.crit_cur.get(),
+                asc.charge_current.get(),
+                attributes.charge_max_cur.get()
+            ),
+        ];
+
+        lines.extend(asc.active_gameplay_effects.iter().map(|effect| {
+            let remaining = effect
+                .expiration_time()
+                .map(|time| format!("{:.1}s", time - now))
+                .unwrap_or_else(|| String::from("infinite"));
+
+            format!(
+                "effect {} x{} {remaining}",
+                effect.spec.def.path, effect.stack_count
+            )
+        }));
+
+        lines.extend(
+            asc.ability_activations
+                .active_abilities()
+                .map(|config| format!("active ability {}", config.ability_name())),
+        );
+
+        lines
+    })
+    .unwrap_or_else(|| vec![String::from("debug actor has no ability system")])
+}
+
+fn ai_lines(world: &World, debug_actor: FNetworkGUID) -> Vec<String> {
+    let Some(monster) = world.get_actor_object::<MonsterCharacter>(debug_actor) else {
+        return vec![String::from("debug actor isn't a monster")];
+    };
+
+    let ai = &monster.data().ai;
+    let distance_from_home = world
+        .actors
+        .get(&debug_actor)
+        .map(|actor| actor.position.dist_2d(&ai.home))
+        .unwrap_or_default();
+
+    vec![
+        format!("state {:?}", ai.state),
+        format!(
+            "{:.0}m from home, leash at {:.0}m",
+            distance_from_home / 100.0,
+            ai.settings.leash_radius / 100.0
+        ),
+        format!(
+            "aggro {:.0}m attack {:.0}m every {:.1}s",
+            ai.settings.aggro_radius / 100.0,
+            ai.settings.attack_radius / 100.0,
+            ai.settings.attack_interval
+        ),
+    ]
+}
+
+fn net_lines(connection: &NetConnection) -> Vec<String> {
+    let options = &connection.login_options;
+
+    vec![
+        format!(
+            "{:?} player {:?} in {:?}",
+            connection.session_id,
+            connection.net_player_index(),
+            connection.world
+        ),
+        format!(
+            "net speed {} open channels {} awaiting send: {}",
+            connection.current_net_speed,
+            connection.actor_channels.len(),
+            connection.has_awaiting_send_packets()
+        ),
+        format!(
+            "login: name {:?} character {:?} team {:?} spectator {} other {:?}",
+            options.name, options.character, options.team, options.spectator_only, options.other
+        ),
+    ]
+}
@@ -39,7 +39,7 @@ pub struct HottaPlayerState {
     #[property("LikeabilityChatGroupSaveDataArray")]
     pub likeability_chat_group_save_data_array: Vec<()>,
     #[property("ReceiveCloneSystemContainIDs")]
-    pub receive_clone_system_contain_ids: Vec<()>,
+    pub receive_clone_system_contain_ids: Vec<String>,
     #[property("WorldLevel")]
     pub world_level: u32,
     #[property("MaxWorldLevel")]
@@ -47,7 +47,7 @@ pub struct HottaPlayerState {
     #[property("LastAjustWorldLevelTime")]
     pub last_adjust_world_level_time: u64,
     #[property("ItemEffectCDData")]
-    pub item_effect_cd_data: Vec<()>,
+    pub item_effect_cd_data: Vec<ItemEffectCDData>,
     #[property("OwnedMusicList")]
     pub owned_music_list: Vec<String>,
     #[property("CurrentDisplayRealEstate")]
@@ -65,7 +65,7 @@ pub struct HottaPlayerState {
     #[property("BakStaminaAutoRecoveryInterval")]
     pub bak_stamina_auto_recovery_interval: u32,
     #[property("WeeklyUnlockCloneIDs")]
-    pub weekly_unlock_clone_ids: Vec<()>,
+    pub weekly_unlock_clone_ids: Vec<String>,
     #[property("WeathervaneID")]
     pub weathervane_id: String,
     #[property("CurDecorationPlans")]
@@ -95,7 +95,7 @@ pub struct HottaPlayerState {
     #[property("FurnitureActivateStates")]
     pub furniture_activate_states: Vec<()>,
     #[property("PlacementVehcialData")]
-    pub placement_vehicle_data: Vec<()>,
+    pub placement_vehicle_data: Vec<PlacementVehicleData>,
     #[property("m_BeforeSinBossTransform")]
     pub before_sin_boss_transform: Transform,
     #[property("SocialSetting")]
@@ -131,18 +131,33 @@ pub struct HottaPlayerState {
     #[property("GoldCloneSelectedFriendRoleID")]
     pub gold_clone_selected_friend_role_id: u64,
     #[property("CloneSystemChallengeSuccessIDs")]
-    pub clone_system_challenge_success_ids: Vec<()>,
+    pub clone_system_challenge_success_ids: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct RoomDecorationPlans(pub String, pub String);
 
+/// Cooldown of an item, timestamps are unix times in milliseconds.
+#[derive(Debug)]
+pub struct ItemEffectCDData {
+    pub item_id: String,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
 #[derive(Debug)]
 pub struct TakeOrdersSaveData {
     pub point_id: String,
     pub order_id: String,
 }
 
+/// A vehicle placed by the player, see `VehicleComponent::placed_vehicles`.
+#[derive(Debug)]
+pub struct PlacementVehicleData {
+    pub vehicle_id: String,
+    pub transform: Transform,
+}
+
 #[derive(Debug)]
 pub struct Transform {
     pub position: FVector3d,
@@ -167,6 +182,14 @@ impl HottaReplicatedProperty for RoomDecorationPlans {
     }
 }
 
+impl HottaReplicatedProperty for ItemEffectCDData {
+    fn replicate_impl(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_string(&self.item_id)?;
+        w.write_u64(self.start_time)?;
+        w.write_u64(self.end_time)
+    }
+}
+
 impl HottaReplicatedProperty for TakeOrdersSaveData {
     fn replicate_impl(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
         w.write_string(&self.point_id)?;
@@ -176,6 +199,13 @@ impl HottaReplicatedProperty for TakeOrdersSaveData {
     }
 }
 
+impl HottaReplicatedProperty for PlacementVehicleData {
+    fn replicate_impl(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_string(&self.vehicle_id)?;
+        self.transform.replicate_impl(w)
+    }
+}
+
 impl HottaReplicatedProperty for Transform {
     fn replicate_impl(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
         w.write_vector(&self.position)?;
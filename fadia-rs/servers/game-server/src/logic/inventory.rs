@@ -0,0 +1,675 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use fadia_config::dataset::ItemData;
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::{PropertyBool, PropertyString, PropertyU32},
+};
+use tracing::warn;
+
+use crate::{assets::GameAssets, net::World};
+
+use super::{
+    ability::{self, GameplayEffectSpec},
+    cooldown::{self, ECooldownType},
+    layout::{InventoryComponent, InventoryItem, PlayerState},
+    progression::{PlayerProgression, SavedItemStack},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum InventoryError {
+    #[error("player doesn't have an inventory")]
+    NoInventory,
+    #[error("item count can't be zero")]
+    ZeroCount,
+    #[error("item stack {0} doesn't exist")]
+    UnknownStack(u32),
+    #[error("item {0} doesn't exist")]
+    UnknownItem(String),
+    #[error("item {0} can't be used")]
+    NotUsable(String),
+    #[error("item {0} can't be discarded")]
+    NotDiscardable(String),
+    #[error("item stack {0} is locked")]
+    Locked(u32),
+    #[error("item stack {0} has less than {1} items")]
+    NotEnough(u32, u32),
+    #[error("item {0} is on cooldown")]
+    Cooldown(String),
+}
+
+impl InventoryComponent {
+    /// Adds items to the stacks of the same item, the rest is put into new stacks.
+    pub fn add_item(
+        &mut self,
+        assets: &GameAssets,
+        item_id: &str,
+        mut count: u32,
+    ) -> Result<(), InventoryError> {
+        let data = assets
+            .get_item_data(item_id)
+            .ok_or_else(|| InventoryError::UnknownItem(item_id.to_string()))?;
+        let max_stack_count = data.max_stack_count.max(1);
+
+        let partial_stacks = self
+            .items
+            .iter()
+            .filter(|(_, item)| item.item_id.get() == item_id && item.count.get() < max_stack_count)
+            .map(|(element_id, _)| element_id)
+            .collect::<Vec<_>>();
+
+        for element_id in partial_stacks {
+            if count == 0 {
+                break;
+            }
+
+            if let Some(item) = self.items.get_mut(element_id) {
+                let added = count.min(max_stack_count - item.count.get());
+                item.count.set_value(item.count.get() + added);
+                count -= added;
+            }
+        }
+
+        while count > 0 {
+            let added = count.min(max_stack_count);
+            self.push_stack(item_id, added, false);
+            count -= added;
+        }
+
+        Ok(())
+    }
+
+    /// Removes items from a stack, the stack is removed once it's empty.
+    pub fn take_items(&mut self, element_id: u32, count: u32) -> Result<(), InventoryError> {
+        if count == 0 {
+            return Err(InventoryError::ZeroCount);
+        }
+
+        let item = self
+            .items
+            .get(element_id)
+            .ok_or(InventoryError::UnknownStack(element_id))?;
+
+        let remaining = item
+            .count
+            .get()
+            .checked_sub(count)
+            .ok_or(InventoryError::NotEnough(element_id, count))?;
+
+        if remaining == 0 {
+            self.items.remove(element_id);
+        } else if let Some(item) = self.items.get_mut(element_id) {
+            item.count.set_value(remaining);
+        }
+
+        Ok(())
+    }
+
+    /// Looks up the ID and the data of the item of a stack which can be used.
+    pub fn usable_item<'assets>(
+        &self,
+        assets: &'assets GameAssets,
+        element_id: u32,
+    ) -> Result<(String, &'assets ItemData), InventoryError> {
+        let item_id = self
+            .items
+            .get(element_id)
+            .ok_or(InventoryError::UnknownStack(element_id))?
+            .item_id
+            .get()
+            .to_string();
+
+        let data = assets
+            .get_item_data(&item_id)
+            .ok_or_else(|| InventoryError::UnknownItem(item_id.clone()))?;
+
+        if !data.can_use {
+            return Err(InventoryError::NotUsable(item_id));
+        }
+
+        Ok((item_id, data))
+    }
+
+    /// Removes items of a stack which isn't locked and can be discarded.
+    pub fn discard_items(
+        &mut self,
+        assets: &GameAssets,
+        element_id: u32,
+        count: u32,
+    ) -> Result<(), InventoryError> {
+        let item = self
+            .items
+            .get(element_id)
+            .ok_or(InventoryError::UnknownStack(element_id))?;
+
+        if item.locked.get() {
+            return Err(InventoryError::Locked(element_id));
+        }
+
+        let item_id = item.item_id.get();
+        if !assets
+            .get_item_data(item_id)
+            .is_some_and(|data| data.can_discard)
+        {
+            return Err(InventoryError::NotDiscardable(item_id.to_string()));
+        }
+
+        self.take_items(element_id, count)
+    }
+
+    /// Merges stacks of the same item and orders them by item type, quality and ID.
+    pub fn sort(&mut self, assets: &GameAssets) {
+        let mut totals = BTreeMap::<(String, bool), u32>::new();
+        let element_ids = self
+            .items
+            .iter()
+            .map(|(element_id, _)| element_id)
+            .collect::<Vec<_>>();
+
+        for element_id in element_ids {
+            if let Some(item) = self.items.remove(element_id) {
+                *totals
+                    .entry((item.item_id.get().to_string(), item.locked.get()))
+                    .or_default() += item.count.get();
+            }
+        }
+
+        let mut totals = totals.into_iter().collect::<Vec<_>>();
+        totals.sort_by_key(|((item_id, _), _)| {
+            let data = assets.get_item_data(item_id);
+
+            (
+                data.map(|data| data.item_type),
+                Reverse(data.map(|data| data.quality)),
+                item_id.clone(),
+            )
+        });
+
+        for ((item_id, locked), mut count) in totals {
+            let max_stack_count = assets
+                .get_item_data(&item_id)
+                .map_or(count, |data| data.max_stack_count)
+                .max(1);
+
+            while count > 0 {
+                let added = count.min(max_stack_count);
+                self.push_stack(&item_id, added, locked);
+                count -= added;
+            }
+        }
+    }
+
+    pub fn set_locked(&mut self, element_id: u32, locked: bool) -> Result<(), InventoryError> {
+        let item = self
+            .items
+            .get_mut(element_id)
+            .ok_or(InventoryError::UnknownStack(element_id))?;
+
+        item.locked.set_value(locked);
+        Ok(())
+    }
+
+    /// Puts a stack saved in the player profile back into the inventory.
+    pub fn restore_stack(
+        &mut self,
+        assets: &GameAssets,
+        stack: &SavedItemStack,
+    ) -> Result<(), InventoryError> {
+        if assets.get_item_data(&stack.item_id).is_none() {
+            return Err(InventoryError::UnknownItem(stack.item_id.clone()));
+        }
+
+        if stack.count > 0 {
+            self.push_stack(&stack.item_id, stack.count, stack.locked);
+        }
+
+        Ok(())
+    }
+
+    pub fn to_saved(&self) -> Vec<SavedItemStack> {
+        let mut stacks = self.items.iter().map(|(_, item)| item).collect::<Vec<_>>();
+        stacks.sort_by_key(|item| item.slot.get());
+
+        stacks
+            .into_iter()
+            .map(|item| SavedItemStack {
+                item_id: item.item_id.get().to_string(),
+                count: item.count.get(),
+                locked: item.locked.get(),
+            })
+            .collect()
+    }
+
+    fn push_stack(&mut self, item_id: &str, count: u32, locked: bool) {
+        let slot = self
+            .items
+            .iter()
+            .map(|(_, item)| item.slot.get() + 1)
+            .max()
+            .unwrap_or_default();
+
+        self.items.push(InventoryItem {
+            item_id: PropertyString::new(item_id.to_string()),
+            count: PropertyU32::new(count),
+            slot: PropertyU32::new(slot),
+            locked: PropertyBool::new(locked),
+        });
+    }
+}
+
+/// Runs `f` on the inventory of a player.
+fn with_inventory<R>(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    f: impl FnOnce(&mut InventoryComponent, &'static GameAssets) -> R,
+) -> Result<R, InventoryError> {
+    let inventory_guid = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .ok_or(InventoryError::NoInventory)?
+        .data()
+        .inventory;
+
+    let assets = world.assets;
+    let mut inventory = world
+        .get_object_mut::<InventoryComponent>(inventory_guid)
+        .ok_or(InventoryError::NoInventory)?;
+
+    Ok(f(inventory.data_mut(), assets))
+}
+
+/// Fills the inventory of a player with the items saved in the player profile.
+/// New players get the configured starting items.
+pub fn init_inventory(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    saved_items: Option<Vec<SavedItemStack>>,
+) {
+    let starting_items = &world.globals.starting_items;
+    let is_new = saved_items.is_none();
+
+    let result = with_inventory(world, player_state_guid, |inventory, assets| {
+        let results = match saved_items {
+            Some(stacks) => stacks
+                .iter()
+                .map(|stack| inventory.restore_stack(assets, stack))
+                .collect::<Vec<_>>(),
+            None => starting_items
+                .iter()
+                .map(|(item_id, &count)| inventory.add_item(assets, item_id, count))
+                .collect(),
+        };
+
+        for err in results.into_iter().filter_map(Result::err) {
+            warn!("failed to add items to the inventory: {err}");
+        }
+    });
+
+    if let Err(err) = result {
+        warn!("failed to initialize inventory: {err}");
+    } else if is_new {
+        save_inventory(world, player_state_guid);
+    }
+}
+
+/// Uses items of a stack: applies the use effects of the item and restores satiety
+/// of the current character of the player once for every used item.
+/// Items with a cooldown can't be used again until it ends, and at least one item
+/// has to be used.
+pub fn use_item(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    element_id: u32,
+    count: u32,
+) -> Result<(), InventoryError> {
+    if count == 0 {
+        return Err(InventoryError::ZeroCount);
+    }
+
+    let (item_id, data) = with_inventory(world, player_state_guid, |inventory, assets| {
+        inventory.usable_item(assets, element_id)
+    })??;
+
+    if cooldown::with_cd_manager(world, player_state_guid, |cd_manager, now| {
+        cd_manager.is_on_cooldown(ECooldownType::Item, &item_id, now)
+    })
+    .unwrap_or_default()
+    {
+        return Err(InventoryError::Cooldown(item_id));
+    }
+
+    with_inventory(world, player_state_guid, |inventory, _| {
+        inventory.take_items(element_id, count)
+    })??;
+
+    if let Some(character_guid) = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .and_then(|state| Some(state.data().equipped_players.get(0)?.get()))
+    {
+        for _ in 0..count {
+            apply_item_use(world, character_guid, data);
+        }
+    }
+
+    if data.cooldown > 0.0 {
+        cooldown::start_cooldowns(
+            world,
+            player_state_guid,
+            &[(ECooldownType::Item, &item_id)],
+            (data.cooldown * 1000.0) as u64,
+        );
+    }
+
+    save_inventory(world, player_state_guid);
+    Ok(())
+}
+
+pub fn discard_item(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    element_id: u32,
+    count: u32,
+) -> Result<(), InventoryError> {
+    with_inventory(world, player_state_guid, |inventory, assets| {
+        inventory.discard_items(assets, element_id, count)
+    })??;
+
+    save_inventory(world, player_state_guid);
+    Ok(())
+}
+
+pub fn sort_items(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+) -> Result<(), InventoryError> {
+    with_inventory(world, player_state_guid, |inventory, assets| {
+        inventory.sort(assets);
+    })?;
+
+    save_inventory(world, player_state_guid);
+    Ok(())
+}
+
+pub fn lock_item(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    element_id: u32,
+    locked: bool,
+) -> Result<(), InventoryError> {
+    with_inventory(world, player_state_guid, |inventory, _| {
+        inventory.set_locked(element_id, locked)
+    })??;
+
+    save_inventory(world, player_state_guid);
+    Ok(())
+}
+
+/// Adds items to the inventory of a player, e.g. rewards, keyed by item IDs.
+pub fn give_items(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    items: &BTreeMap<String, u32>,
+) -> Result<(), InventoryError> {
+    let result = with_inventory(world, player_state_guid, |inventory, assets| {
+        items
+            .iter()
+            .try_for_each(|(item_id, &count)| inventory.add_item(assets, item_id, count))
+    })?;
+
+    save_inventory(world, player_state_guid);
+    result
+}
+
+fn apply_item_use(world: &mut World, character_guid: FNetworkGUID, data: &ItemData) {
+    for path in data.use_effects.iter() {
+        let Some(def) = world.assets.get_gameplay_effect_config(path) else {
+            warn!("item use effect {path} doesn't exist");
+            continue;
+        };
+
+        ability::apply_gameplay_effect(
+            world,
+            character_guid,
+            GameplayEffectSpec::new(def, 1.0, character_guid),
+        );
+    }
+
+    if data.satiety > 0.0 {
+        ability::with_ability_system(world, character_guid, |asc, attributes, _, now| {
+            asc.restore_satiety(attributes, data.satiety, now);
+        });
+    }
+}
+
+/// Writes the inventory of a player to the player profile.
+fn save_inventory(world: &mut World, player_state_guid: FNetworkGUID) {
+    let Some(unique_id) = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .map(|state| state.data().unique_id.device.get().to_string())
+        .filter(|unique_id| !unique_id.is_empty())
+    else {
+        return;
+    };
+
+    let Ok(items) = with_inventory(world, player_state_guid, |inventory, _| {
+        inventory.to_saved()
+    }) else {
+        return;
+    };
+
+    let mut progression = PlayerProgression::load_or_create(&unique_id);
+    progression.items = Some(items);
+
+    if let Err(err) = progression.save(&unique_id) {
+        warn!("failed to save inventory of {unique_id}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fadia_config::dataset::{DataTable, EItemType};
+
+    use super::*;
+
+    fn item(item_type: EItemType, quality: u32, can_use: bool, can_discard: bool) -> ItemData {
+        ItemData {
+            item_type,
+            quality,
+            max_stack_count: 10,
+            can_use,
+            can_discard,
+            use_effects: Vec::new(),
+            satiety: 0.0,
+            cooldown: 0.0,
+        }
+    }
+
+    fn assets() -> GameAssets {
+        let mut assets = GameAssets::empty();
+        assets.data_asset_set.item_data_table = Some(DataTable {
+            rows: HashMap::from([
+                (String::from("food"), item(EItemType::Food, 1, true, true)),
+                (
+                    String::from("rare_food"),
+                    item(EItemType::Food, 3, true, true),
+                ),
+                (
+                    String::from("ore"),
+                    item(EItemType::Material, 1, false, true),
+                ),
+                (
+                    String::from("letter"),
+                    item(EItemType::Quest, 1, false, false),
+                ),
+            ]),
+        });
+
+        assets
+    }
+
+    fn stacks(inventory: &InventoryComponent) -> Vec<(String, u32, bool)> {
+        inventory
+            .to_saved()
+            .into_iter()
+            .map(|stack| (stack.item_id, stack.count, stack.locked))
+            .collect()
+    }
+
+    fn first_stack(inventory: &InventoryComponent) -> u32 {
+        inventory.items.iter().next().unwrap().0
+    }
+
+    #[test]
+    fn adds_items_to_partial_stacks_first() {
+        let assets = assets();
+        let mut inventory = InventoryComponent::default();
+
+        inventory.add_item(&assets, "food", 7).unwrap();
+        inventory.add_item(&assets, "food", 7).unwrap();
+
+        assert_eq!(
+            stacks(&inventory),
+            vec![
+                (String::from("food"), 10, false),
+                (String::from("food"), 4, false)
+            ]
+        );
+        assert!(matches!(
+            inventory.add_item(&assets, "unknown", 1),
+            Err(InventoryError::UnknownItem(_))
+        ));
+    }
+
+    #[test]
+    fn uses_items_of_usable_stacks() {
+        let assets = assets();
+        let mut inventory = InventoryComponent::default();
+        inventory.add_item(&assets, "food", 2).unwrap();
+        let element_id = first_stack(&inventory);
+
+        let (item_id, data) = inventory.usable_item(&assets, element_id).unwrap();
+        assert_eq!(item_id, "food");
+        assert!(data.can_use);
+
+        inventory.take_items(element_id, 1).unwrap();
+        assert_eq!(stacks(&inventory), vec![(String::from("food"), 1, false)]);
+
+        inventory.take_items(element_id, 1).unwrap();
+        assert!(stacks(&inventory).is_empty());
+        assert!(matches!(
+            inventory.usable_item(&assets, element_id),
+            Err(InventoryError::UnknownStack(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unusable_items_and_bad_counts() {
+        let assets = assets();
+        let mut inventory = InventoryComponent::default();
+        inventory.add_item(&assets, "ore", 2).unwrap();
+        let element_id = first_stack(&inventory);
+
+        assert!(matches!(
+            inventory.usable_item(&assets, element_id),
+            Err(InventoryError::NotUsable(_))
+        ));
+        assert!(matches!(
+            inventory.take_items(element_id, 0),
+            Err(InventoryError::ZeroCount)
+        ));
+        assert!(matches!(
+            inventory.take_items(element_id, 3),
+            Err(InventoryError::NotEnough(_, 3))
+        ));
+        assert_eq!(stacks(&inventory), vec![(String::from("ore"), 2, false)]);
+    }
+
+    #[test]
+    fn discards_only_unlocked_discardable_stacks() {
+        let assets = assets();
+        let mut inventory = InventoryComponent::default();
+        inventory.add_item(&assets, "letter", 1).unwrap();
+        inventory.add_item(&assets, "ore", 5).unwrap();
+        let ids = inventory
+            .items
+            .iter()
+            .map(|(element_id, _)| element_id)
+            .collect::<Vec<_>>();
+        let (letter, ore) = (ids[0], ids[1]);
+
+        assert!(matches!(
+            inventory.discard_items(&assets, letter, 1),
+            Err(InventoryError::NotDiscardable(_))
+        ));
+
+        inventory.set_locked(ore, true).unwrap();
+        assert!(matches!(
+            inventory.discard_items(&assets, ore, 1),
+            Err(InventoryError::Locked(_))
+        ));
+
+        inventory.set_locked(ore, false).unwrap();
+        inventory.discard_items(&assets, ore, 2).unwrap();
+        assert_eq!(
+            stacks(&inventory),
+            vec![
+                (String::from("letter"), 1, false),
+                (String::from("ore"), 3, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn locking_an_unknown_stack_fails() {
+        let mut inventory = InventoryComponent::default();
+
+        assert!(matches!(
+            inventory.set_locked(1, true),
+            Err(InventoryError::UnknownStack(1))
+        ));
+    }
+
+    #[test]
+    fn sort_merges_stacks_and_orders_them() {
+        let assets = assets();
+        let mut inventory = InventoryComponent::default();
+        inventory.add_item(&assets, "ore", 3).unwrap();
+        inventory.add_item(&assets, "food", 6).unwrap();
+        inventory.add_item(&assets, "rare_food", 1).unwrap();
+        inventory.add_item(&assets, "ore", 2).unwrap();
+        inventory
+            .restore_stack(
+                &assets,
+                &SavedItemStack {
+                    item_id: String::from("food"),
+                    count: 6,
+                    locked: false,
+                },
+            )
+            .unwrap();
+        inventory
+            .restore_stack(
+                &assets,
+                &SavedItemStack {
+                    item_id: String::from("food"),
+                    count: 1,
+                    locked: true,
+                },
+            )
+            .unwrap();
+
+        inventory.sort(&assets);
+
+        assert_eq!(
+            stacks(&inventory),
+            vec![
+                (String::from("rare_food"), 1, false),
+                (String::from("food"), 10, false),
+                (String::from("food"), 2, false),
+                (String::from("food"), 1, true),
+                (String::from("ore"), 5, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use fadia_codegen::{RepLayout, dummy_rpc_handler};
+use fadia_engine::replication::{
+    FastArraySerializer,
+    property::{PropertyString, PropertyU8, PropertyU64},
+};
+
+use crate::logic::ObjectLayout;
+
+/// `UCDManager`, a sub-object of `PlayerState` which tracks cooldowns of the player.
+///
+/// Unverified: like the inventory, its layout is missing from the dumps, so cooldowns are
+/// guessed to be the first custom delta property and only replicated with
+/// `replicate_unverified_layouts`. Timestamps are unix times in milliseconds,
+/// so cooldowns outlive the world of the connection.
+#[derive(Debug, Default, RepLayout)]
+#[dummy_rpc_handler]
+pub struct CDManager {
+    #[rep(index = 0)]
+    pub cooldowns: FastArraySerializer<CooldownItem>,
+}
+
+#[derive(Debug, RepLayout)]
+pub struct CooldownItem {
+    /// `ECooldownType`
+    #[rep(handle = 1)]
+    pub cd_type: PropertyU8,
+    /// Cooldown tag, ability name or item ID, depending on the type.
+    #[rep(handle = 2)]
+    pub key: PropertyString,
+    #[rep(handle = 3)]
+    pub start_time: PropertyU64,
+    #[rep(handle = 4)]
+    pub end_time: PropertyU64,
+}
+
+impl ObjectLayout for CDManager {
+    fn is_unverified(&self) -> bool {
+        true
+    }
+}
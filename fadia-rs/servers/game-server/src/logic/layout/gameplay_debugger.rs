@@ -1,17 +1,94 @@
-use fadia_codegen::{RepLayout, dummy_rpc_handler};
-use fadia_engine::replication::property::PropertyObject;
+use fadia_codegen::{RepLayout, rpc_handlers};
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::{PropertyBool, PropertyObject},
+};
+use tracing::debug;
 
-use crate::logic::{actor::PropertyNetRole, ObjectLayout};
+use crate::{
+    logic::{
+        ObjectLayout,
+        actor::{NetRole, PropertyNetRole},
+        gameplay_debugger::{self, GameplayDebuggerNetPack},
+        rpc::RpcContext,
+    },
+    net::World,
+};
 
+/// `AGameplayDebuggerCategoryReplicator`, spawned for every player so the in-game
+/// debugger shows the server state of the categories the player enables.
+///
+/// The class is missing from the dumps. Handles follow the declaration order of the engine
+/// source after the ones of `AActor`, and the indices the alphabetical order of its net fields.
 #[derive(Debug, RepLayout)]
-#[dummy_rpc_handler]
+#[max_rep_index(22)]
 pub struct GameplayDebugger {
     #[rep(handle = 5)]
     pub remote_role: PropertyNetRole,
+    #[rep(handle = 13)]
+    pub owner: PropertyObject,
     #[rep(handle = 14)]
     pub role: PropertyNetRole,
     #[rep(handle = 17)]
-    pub player_state: PropertyObject,
+    pub owner_pc: PropertyObject,
+    #[rep(handle = 18)]
+    pub is_enabled: PropertyBool,
+    #[rep(index = 14)]
+    pub replicated_data: GameplayDebuggerNetPack,
+    /// `DebugActor.Actor`, the actor the categories describe.
+    #[rep(handle = 19)]
+    pub debug_actor: PropertyObject,
+    /// Categories enabled by the client, bits of their IDs.
+    #[rep(ignore)]
+    pub enabled_categories: u32,
+    #[rep(ignore)]
+    pub next_update_time: f64,
+}
+
+impl GameplayDebugger {
+    pub fn new(world: &mut World, owner_pc: FNetworkGUID) -> (FNetworkGUID, Self) {
+        (
+            world
+                .net_guid_cache
+                .assign_new_net_guid_for_dynamic_object(None),
+            GameplayDebugger {
+                remote_role: PropertyNetRole::new(NetRole::SimulatedProxy),
+                owner: PropertyObject::new(owner_pc),
+                role: PropertyNetRole::new(NetRole::Authority),
+                owner_pc: PropertyObject::new(owner_pc),
+                is_enabled: PropertyBool::default(),
+                replicated_data: GameplayDebuggerNetPack::default(),
+                debug_actor: PropertyObject::default(),
+                enabled_categories: 0,
+                next_update_time: 0.0,
+            },
+        )
+    }
+}
+
+#[rpc_handlers]
+impl GameplayDebugger {
+    #[rpc(18, server)]
+    fn server_set_category_enabled(context: RpcContext, category_id: i32, enable: bool) {
+        if let Err(err) = gameplay_debugger::set_category_enabled(
+            context.world,
+            context.actor_guid,
+            category_id,
+            enable,
+        ) {
+            debug!("failed to toggle gameplay debugger category: {err}");
+        }
+    }
+
+    #[rpc(19, server)]
+    fn server_set_debug_actor(context: RpcContext, actor: FNetworkGUID, _select_in_editor: bool) {
+        gameplay_debugger::set_debug_actor(context.world, context.actor_guid, actor);
+    }
+
+    #[rpc(20, server)]
+    fn server_set_enabled(context: RpcContext, enable: bool) {
+        gameplay_debugger::set_enabled(context.world, context.actor_guid, enable);
+    }
 }
 
 impl ObjectLayout for GameplayDebugger {}
@@ -0,0 +1,77 @@
+use fadia_codegen::{RepLayout, rpc_handlers};
+use fadia_engine::replication::{
+    FastArraySerializer,
+    property::{PropertyBool, PropertyString, PropertyU32},
+};
+use tracing::debug;
+
+use crate::logic::{ObjectLayout, inventory, rpc::RpcContext};
+
+/// `UInventoryComponent`, a sub-object of `PlayerState`.
+///
+/// Unverified: the layout of the component is missing from the dumps, so items are guessed
+/// to be the first custom delta property and the RPCs follow it. The items are only
+/// replicated with `replicate_unverified_layouts`.
+#[derive(Debug, Default, RepLayout)]
+#[max_rep_index(5)]
+pub struct InventoryComponent {
+    #[rep(index = 0)]
+    pub items: FastArraySerializer<InventoryItem>,
+}
+
+/// A stack of items, at most `ItemData::max_stack_count` of them.
+#[derive(Debug, RepLayout)]
+pub struct InventoryItem {
+    #[rep(handle = 1)]
+    pub item_id: PropertyString,
+    #[rep(handle = 2)]
+    pub count: PropertyU32,
+    /// Position of the stack in the inventory UI.
+    #[rep(handle = 3)]
+    pub slot: PropertyU32,
+    /// Locked stacks can't be discarded.
+    #[rep(handle = 4)]
+    pub locked: PropertyBool,
+}
+
+#[rpc_handlers]
+impl InventoryComponent {
+    #[rpc(1, server)]
+    fn server_use_item(context: RpcContext, element_id: u32, count: u32) {
+        if let Err(err) = inventory::use_item(context.world, context.actor_guid, element_id, count)
+        {
+            debug!("failed to use {count} items of stack {element_id}: {err}");
+        }
+    }
+
+    #[rpc(2, server)]
+    fn server_discard_item(context: RpcContext, element_id: u32, count: u32) {
+        if let Err(err) =
+            inventory::discard_item(context.world, context.actor_guid, element_id, count)
+        {
+            debug!("failed to discard {count} items of stack {element_id}: {err}");
+        }
+    }
+
+    #[rpc(3, server)]
+    fn server_sort_items(context: RpcContext) {
+        if let Err(err) = inventory::sort_items(context.world, context.actor_guid) {
+            debug!("failed to sort items: {err}");
+        }
+    }
+
+    #[rpc(4, server)]
+    fn server_lock_item(context: RpcContext, element_id: u32, locked: bool) {
+        if let Err(err) =
+            inventory::lock_item(context.world, context.actor_guid, element_id, locked)
+        {
+            debug!("failed to lock stack {element_id}: {err}");
+        }
+    }
+}
+
+impl ObjectLayout for InventoryComponent {
+    fn is_unverified(&self) -> bool {
+        true
+    }
+}
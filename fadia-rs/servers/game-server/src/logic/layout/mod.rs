@@ -1,21 +1,29 @@
-mod actor;
+mod cd_manager;
 mod gameplay_debugger;
+mod inventory;
+mod monster_character;
 mod player_character;
 mod player_controller;
 mod player_state;
+mod shield_component;
+mod timer_clock;
 mod ultra_dynamic_weather;
+mod vehicle;
+mod vehicle_component;
 mod weapon;
 mod world_data_layers;
 
-#[allow(unused_imports)]
-pub use actor::*;
-#[allow(unused_imports)]
+pub use cd_manager::*;
 pub use gameplay_debugger::*;
-#[allow(unused_imports)]
-pub use ultra_dynamic_weather::*;
-
+pub use inventory::*;
+pub use monster_character::*;
 pub use player_character::*;
 pub use player_controller::*;
 pub use player_state::*;
+pub use shield_component::*;
+pub use timer_clock::*;
+pub use ultra_dynamic_weather::*;
+pub use vehicle::*;
+pub use vehicle_component::*;
 pub use weapon::*;
 pub use world_data_layers::*;
@@ -0,0 +1,107 @@
+use fadia_codegen::{RepLayout, dummy_rpc_handler};
+
+use fadia_config::spawn::MonsterSpawnPoint;
+use fadia_engine::{
+    FNetworkGUID,
+    replication::{
+        FastArraySerializer,
+        property::{PropertyObject, PropertyRepMovement},
+    },
+    rotator::FRotator,
+    vector::FVector3d,
+};
+
+use crate::{
+    logic::{
+        ObjectLayout, SubObjectList,
+        ability::AbilityActivations,
+        actor::{NetRole, PropertyNetRole},
+        npc::MonsterAi,
+    },
+    net::World,
+};
+
+use super::{AbilitySystemComponent, HTAttributeSet};
+
+/// Enemy character placed by the monster spawner, controlled by the server AI.
+///
+/// Unverified: monster blueprints are missing from the dumps, so the property handles are
+/// guessed from `HTPlayerCharacter`. Monsters are only spawned from `assets/Spawns`, which
+/// has no tables until real spawn data is dumped.
+#[derive(Debug, RepLayout)]
+#[dummy_rpc_handler]
+pub struct MonsterCharacter {
+    #[rep(handle = 5)]
+    pub remote_role: PropertyNetRole,
+    #[rep(handle = 12)]
+    pub replicated_movement: PropertyRepMovement,
+    #[rep(handle = 13)]
+    pub owner: PropertyObject,
+    #[rep(handle = 14)]
+    pub role: PropertyNetRole,
+    #[rep(handle = 15)]
+    pub instigator: PropertyObject,
+    #[rep(ignore)]
+    pub ability_system_component: FNetworkGUID,
+    #[rep(ignore)]
+    pub ai: MonsterAi,
+}
+
+impl MonsterCharacter {
+    pub fn new(
+        world: &mut World,
+        spawn: &MonsterSpawnPoint,
+        remote_role: NetRole,
+        role: NetRole,
+    ) -> (FNetworkGUID, Self, SubObjectList) {
+        let character_guid = world
+            .net_guid_cache
+            .assign_new_net_guid_for_dynamic_object(None);
+        let mut sub_objects: SubObjectList = Vec::new();
+
+        let attribute_set_guid = world
+            .net_guid_cache
+            .assign_new_net_guid_for_dynamic_object(Some("AttributeSet"));
+        let ability_system_component_guid = world
+            .net_guid_cache
+            .assign_new_net_guid_for_dynamic_object(Some("AbilitySystemComponent"));
+
+        sub_objects.push((
+            attribute_set_guid,
+            Box::new(HTAttributeSet::new(&spawn.stats)),
+        ));
+
+        // Monsters don't have abilities, they deal basic attacks.
+        sub_objects.push((
+            ability_system_component_guid,
+            Box::new(AbilitySystemComponent::new(
+                character_guid,
+                attribute_set_guid,
+                &spawn.stats,
+                FastArraySerializer::default(),
+                AbilityActivations::default(),
+            )),
+        ));
+
+        let location = FVector3d::new(spawn.location.x, spawn.location.y, spawn.location.z);
+
+        (
+            character_guid,
+            MonsterCharacter {
+                remote_role: PropertyNetRole::new(remote_role),
+                replicated_movement: PropertyRepMovement::new(
+                    location.clone(),
+                    FRotator::new(0.0, spawn.yaw, 0.0),
+                ),
+                owner: PropertyObject::default(),
+                role: PropertyNetRole::new(role),
+                instigator: PropertyObject::new(character_guid),
+                ability_system_component: ability_system_component_guid,
+                ai: MonsterAi::new(spawn.ai, location),
+            },
+            sub_objects,
+        )
+    }
+}
+
+impl ObjectLayout for MonsterCharacter {}
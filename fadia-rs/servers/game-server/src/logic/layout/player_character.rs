@@ -1,21 +1,29 @@
-use fadia_codegen::{RepLayout, ReplicatedProperty, dummy_rpc_handler};
+use fadia_codegen::{RepLayout, ReplicatedProperty, dummy_rpc_handler, rpc_handlers};
 
-use fadia_config::blueprint::PlayerCharacterConfig;
+use fadia_config::{blueprint::PlayerCharacterConfig, spawn::CharacterStats};
 use fadia_engine::{
     FNetworkGUID,
     replication::{
         FastArraySerializer, NullLayout,
         property::{
-            GameplayTagContainer, PropertyArray, PropertyBool, PropertyF32, PropertyObject,
-            PropertyU32,
+            GameplayTagContainer, PropertyArray, PropertyBool, PropertyF32, PropertyI32,
+            PropertyObject, PropertyU32,
         },
     },
 };
 
+use tracing::debug;
+
 use crate::{
     logic::{
-        ObjectLayout,
+        ObjectLayout, SubObjectList,
+        ability::{
+            self, AbilityActivations, ActiveGameplayEffectHandle, ActiveGameplayEffectsContainer,
+            EAbilityGenericReplicatedEvent, GameplayAbilityActivationInfo, PredictionKey,
+            SatietyGauge, Shield, TenacityGauge,
+        },
         actor::{NetRole, PropertyNetRole},
+        rpc::{RpcContext, call_rpcs},
     },
     net::World,
 };
@@ -45,6 +53,8 @@ pub struct HTPlayerCharacter {
     pub server_ready_flag: PropertyBool,
     #[rep(handle = 75)]
     pub saved_player_state: PropertyObject,
+    #[rep(ignore)]
+    pub ability_system_component: FNetworkGUID,
 }
 
 #[derive(Debug, RepLayout)]
@@ -102,7 +112,6 @@ pub struct HTAttributeSet {
 
 #[derive(Debug, RepLayout)]
 #[max_rep_index(98)]
-#[dummy_rpc_handler]
 pub struct AbilitySystemComponent {
     #[rep(handle = 6)]
     pub owner_actor: PropertyObject,
@@ -122,8 +131,24 @@ pub struct AbilitySystemComponent {
     pub charge_current: PropertyF32,
     #[rep(handle = 28)]
     pub unbal_speed: PropertyF32,
+    #[rep(index = 4)]
+    pub active_gameplay_effects: ActiveGameplayEffectsContainer,
     #[rep(index = 7)]
     pub activatable_abilities: FastArraySerializer<GameplayAbilitySpec>,
+    #[rep(ignore)]
+    pub attribute_set: FNetworkGUID,
+    #[rep(ignore)]
+    pub ability_activations: AbilityActivations,
+    /// Awakening buffs granted by the character progression.
+    #[rep(ignore)]
+    pub upgrade_effects: Vec<ActiveGameplayEffectHandle>,
+    #[rep(ignore)]
+    pub tenacity: TenacityGauge,
+    #[rep(ignore)]
+    pub satiety: Option<SatietyGauge>,
+    /// Shields granted by effects, oldest first.
+    #[rep(ignore)]
+    pub shields: Vec<Shield>,
 }
 
 #[derive(Debug, ReplicatedProperty)]
@@ -147,23 +172,48 @@ pub struct GameplayAbilitySpec {
     pub replicated_instances: PropertyArray<PropertyObject>,
 }
 
+/// Replicated part of `FActiveGameplayEffect`, handles follow the flattened `FGameplayEffectSpec`.
+#[derive(Debug, RepLayout)]
+pub struct ActiveGameplayEffect {
+    #[rep(handle = 1)]
+    pub def: PropertyObject,
+    #[rep(handle = 3)]
+    pub duration: PropertyF32,
+    #[rep(handle = 4)]
+    pub period: PropertyF32,
+    #[rep(handle = 6)]
+    pub dynamic_granted_tags: GameplayTagContainer,
+    #[rep(handle = 7)]
+    pub dynamic_asset_tags: GameplayTagContainer,
+    #[rep(handle = 9)]
+    pub stack_count: PropertyI32,
+    #[rep(handle = 12)]
+    pub level: PropertyF32,
+    #[rep(handle = 14)]
+    pub start_server_world_time: PropertyF32,
+}
+
 impl HTPlayerCharacter {
     pub const MESH_COMPONENT_NAME: &str = "CharacterMesh0";
 
+    const STATS: CharacterStats = CharacterStats {
+        hp_max: 1000.0,
+        atk: 75.0,
+        def: 60.0,
+        crit: 0.05,
+        crit_damage: 0.5,
+    };
+
     pub fn new(
         world: &mut World,
         config: &'static PlayerCharacterConfig,
         remote_role: NetRole,
         role: NetRole,
-    ) -> (
-        FNetworkGUID,
-        Self,
-        Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
-    ) {
+    ) -> (FNetworkGUID, Self, SubObjectList) {
         let character_guid = world
             .net_guid_cache
             .assign_new_net_guid_for_dynamic_object(None);
-        let mut sub_objects: Vec<(FNetworkGUID, Box<dyn ObjectLayout>)> = Vec::new();
+        let mut sub_objects: SubObjectList = Vec::new();
 
         let attribute_set_guid = world
             .net_guid_cache
@@ -182,15 +232,15 @@ impl HTPlayerCharacter {
             .assign_new_net_guid_for_dynamic_object(Some("HTCharacterAttributeSet"));
 
         let mut activatable_abilities = FastArraySerializer::default();
+        let mut ability_activations = AbilityActivations::default();
         let mut ability_guids = Vec::new();
 
-        for (i, ability) in config
+        for ability in config
             .properties
             .granted_abilities
             .iter()
             .chain(config.properties.passive_abilities.iter())
             .filter(|config| !config.ability_class.is_empty())
-            .enumerate()
         {
             let ability_config = world
                 .assets
@@ -202,13 +252,15 @@ impl HTPlayerCharacter {
             let ability_guid =
                 world.register_hierarchy_for_static_objects(&[outer, &ability_config.name]);
 
+            let handle = ability_activations.grant(ability_config);
+
             activatable_abilities.push(GameplayAbilitySpec {
-                handle: GameplayAbilityHandle(PropertyU32::new(10000 + i as u32)),
+                handle: GameplayAbilityHandle(PropertyU32::new(handle)),
                 ability: PropertyObject::new(ability_guid),
                 level: PropertyU32::new(1),
                 input_id: PropertyU32::new(ability.input_id.into()),
                 source_object: PropertyObject::new(character_guid),
-                gameplay_tags: GameplayTagContainer,
+                gameplay_tags: GameplayTagContainer::new(world.assets.gameplay_tag_net_settings()),
                 replicated_instances: PropertyArray::default(),
             });
 
@@ -217,48 +269,18 @@ impl HTPlayerCharacter {
 
         sub_objects.push((
             attribute_set_guid,
-            Box::new(HTAttributeSet {
-                hp_max_base: PropertyF32::new(1000.0),
-                hp_max_cur: PropertyF32::new(1000.0),
-                atk_base: PropertyF32::new(75.0),
-                atk_cur: PropertyF32::new(75.0),
-                crit_base: PropertyF32::new(0.05),
-                crit_cur: PropertyF32::new(0.05),
-                crit_damage_base: PropertyF32::new(0.5),
-                crit_damage_cur: PropertyF32::new(0.5),
-                charge_get_efficiency_base: PropertyF32::new(1.0),
-                charge_get_efficiency_cur: PropertyF32::new(1.0),
-                charge_max_base: PropertyF32::new(30.0),
-                charge_max_cur: PropertyF32::new(30.0),
-                def_base: PropertyF32::new(60.0),
-                def_cur: PropertyF32::new(60.0),
-                unbal_accrue_efficiency_base: PropertyF32::new(1.0),
-                unbal_accrue_efficiency_cur: PropertyF32::new(1.0),
-                unbal_reduce_natur_base: PropertyF32::new(100.0),
-                unbal_reduce_natur_cur: PropertyF32::new(100.0),
-                tenacity_recover_speed_base: PropertyF32::new(10.0),
-                tenacity_recover_speed_cur: PropertyF32::new(10.0),
-                tenacity_reset_time_base: PropertyF32::new(3.0),
-                tenacity_reset_time_cur: PropertyF32::new(3.0),
-                satiety_max_base: PropertyF32::new(100.0),
-                satiety_max_cur: PropertyF32::new(100.0),
-            }),
+            Box::new(HTAttributeSet::new(&Self::STATS)),
         ));
 
         sub_objects.push((
             ability_system_component_guid,
-            Box::new(AbilitySystemComponent {
-                owner_actor: PropertyObject::new(character_guid),
-                avatar_actor: PropertyObject::new(character_guid),
-                hp_current: PropertyF32::new(1337.0),
-                max_hp: PropertyF32::new(1337.0),
-                max_hp_temp: PropertyF32::new(1337.0),
-                satiety_change_time: PropertyF32::new(4912.057),
-                atk: PropertyF32::new(75.0),
-                charge_current: PropertyF32::new(100.0),
-                unbal_speed: PropertyF32::new(200.0),
+            Box::new(AbilitySystemComponent::new(
+                character_guid,
+                attribute_set_guid,
+                &Self::STATS,
                 activatable_abilities,
-            }),
+                ability_activations,
+            )),
         ));
 
         sub_objects.push((motion_warping_guid, Box::new(NullLayout)));
@@ -279,19 +301,236 @@ impl HTPlayerCharacter {
                 current_weapon: PropertyObject::default(),
                 server_ready_flag: PropertyBool::default(),
                 saved_player_state: PropertyObject::default(),
+                ability_system_component: ability_system_component_guid,
             },
             sub_objects,
         )
     }
 }
 
+impl HTAttributeSet {
+    pub fn new(stats: &CharacterStats) -> Self {
+        Self {
+            hp_max_base: PropertyF32::new(stats.hp_max),
+            hp_max_cur: PropertyF32::new(stats.hp_max),
+            atk_base: PropertyF32::new(stats.atk),
+            atk_cur: PropertyF32::new(stats.atk),
+            crit_base: PropertyF32::new(stats.crit),
+            crit_cur: PropertyF32::new(stats.crit),
+            crit_damage_base: PropertyF32::new(stats.crit_damage),
+            crit_damage_cur: PropertyF32::new(stats.crit_damage),
+            charge_get_efficiency_base: PropertyF32::new(1.0),
+            charge_get_efficiency_cur: PropertyF32::new(1.0),
+            charge_max_base: PropertyF32::new(30.0),
+            charge_max_cur: PropertyF32::new(30.0),
+            def_base: PropertyF32::new(stats.def),
+            def_cur: PropertyF32::new(stats.def),
+            unbal_accrue_efficiency_base: PropertyF32::new(1.0),
+            unbal_accrue_efficiency_cur: PropertyF32::new(1.0),
+            unbal_reduce_natur_base: PropertyF32::new(100.0),
+            unbal_reduce_natur_cur: PropertyF32::new(100.0),
+            tenacity_recover_speed_base: PropertyF32::new(10.0),
+            tenacity_recover_speed_cur: PropertyF32::new(10.0),
+            tenacity_reset_time_base: PropertyF32::new(3.0),
+            tenacity_reset_time_cur: PropertyF32::new(3.0),
+            satiety_max_base: PropertyF32::new(100.0),
+            satiety_max_cur: PropertyF32::new(100.0),
+        }
+    }
+}
+
 impl ObjectLayout for HTPlayerCharacter {}
 impl ObjectLayout for HTAttributeSet {}
 
+impl AbilitySystemComponent {
+    /// Creates the ability system of a character at full HP.
+    pub fn new(
+        owner_guid: FNetworkGUID,
+        attribute_set_guid: FNetworkGUID,
+        stats: &CharacterStats,
+        activatable_abilities: FastArraySerializer<GameplayAbilitySpec>,
+        ability_activations: AbilityActivations,
+    ) -> Self {
+        Self {
+            owner_actor: PropertyObject::new(owner_guid),
+            avatar_actor: PropertyObject::new(owner_guid),
+            hp_current: PropertyF32::new(stats.hp_max),
+            max_hp: PropertyF32::new(stats.hp_max),
+            max_hp_temp: PropertyF32::new(stats.hp_max),
+            satiety_change_time: PropertyF32::default(),
+            atk: PropertyF32::new(stats.atk),
+            charge_current: PropertyF32::new(100.0),
+            unbal_speed: PropertyF32::new(200.0),
+            activatable_abilities,
+            active_gameplay_effects: ActiveGameplayEffectsContainer::default(),
+            attribute_set: attribute_set_guid,
+            ability_activations,
+            upgrade_effects: Vec::new(),
+            tenacity: TenacityGauge::default(),
+            satiety: None,
+            shields: Vec::new(),
+        }
+    }
+
+    fn export_gameplay_effect_defs(&self, channel: &mut crate::net::ActorChannel, world: &World) {
+        self.active_gameplay_effects
+            .iter_defs()
+            .filter(|guid| !channel.exported_guids.contains(guid))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|guid| channel.export_net_guid(world.export_guid(guid)));
+    }
+}
+
+#[rpc_handlers]
+impl AbilitySystemComponent {
+    #[rpc(16, client)]
+    pub fn client_activate_ability_failed(&self, handle: u32, prediction_key: i16) {}
+
+    #[rpc(17, client)]
+    pub fn client_activate_ability_succeed(&self, handle: u32, prediction_key: PredictionKey) {}
+
+    #[rpc(35, server)]
+    fn server_cancel_ability(
+        context: RpcContext,
+        handle: u32,
+        activation_info: GameplayAbilityActivationInfo,
+    ) {
+        let mut asc = context
+            .world
+            .get_object_mut::<AbilitySystemComponent>(context.self_guid)
+            .unwrap();
+
+        if let Some(activation) = asc.data_mut().end_ability(handle, &activation_info) {
+            debug!(
+                "ability {handle} cancelled, replicated events: {:?}",
+                activation.replicated_events
+            );
+        }
+    }
+
+    #[rpc(39, server)]
+    fn server_end_ability(
+        context: RpcContext,
+        handle: u32,
+        activation_info: GameplayAbilityActivationInfo,
+        _prediction_key: PredictionKey,
+    ) {
+        let mut asc = context
+            .world
+            .get_object_mut::<AbilitySystemComponent>(context.self_guid)
+            .unwrap();
+
+        if let Some(activation) = asc.data_mut().end_ability(handle, &activation_info) {
+            debug!(
+                "ability {handle} ended, replicated events: {:?}",
+                activation.replicated_events
+            );
+        }
+    }
+
+    #[rpc(44, server)]
+    fn server_set_replicated_event(
+        context: RpcContext,
+        event: EAbilityGenericReplicatedEvent,
+        handle: u32,
+        original_prediction_key: PredictionKey,
+        _current_prediction_key: PredictionKey,
+    ) {
+        let mut asc = context
+            .world
+            .get_object_mut::<AbilitySystemComponent>(context.self_guid)
+            .unwrap();
+
+        if !asc
+            .data_mut()
+            .set_replicated_event(handle, original_prediction_key, event)
+        {
+            debug!("replicated event {event:?} for inactive ability {handle}");
+        }
+    }
+
+    #[rpc(48, server)]
+    fn server_try_activate_ability(
+        context: RpcContext,
+        handle: u32,
+        _input_pressed: bool,
+        prediction_key: PredictionKey,
+    ) {
+        let result = ability::try_activate_ability(
+            context.world,
+            context.actor_guid,
+            handle,
+            prediction_key,
+        );
+
+        let asc = context
+            .world
+            .get_object_mut::<AbilitySystemComponent>(context.self_guid)
+            .unwrap();
+
+        match result {
+            Ok(()) => {
+                debug!("ability {handle} activated, prediction key: {prediction_key:?}");
+                call_rpcs!(asc.client_activate_ability_succeed(handle, prediction_key));
+            }
+            Err(err) => {
+                debug!("failed to activate ability {handle}: {err}");
+                call_rpcs!(asc.client_activate_ability_failed(handle, prediction_key.current));
+            }
+        }
+    }
+
+    // Hit reports are specific to `UHTAbilitySystemComponent`,
+    // their indices are placed after the ones of the engine component.
+    #[rpc(80, server)]
+    fn server_report_hit(
+        context: RpcContext,
+        handle: u32,
+        target: FNetworkGUID,
+        damage_range: String,
+    ) {
+        let result = ability::report_hit(
+            context.world,
+            context.actor_guid,
+            handle,
+            target,
+            &damage_range,
+        );
+
+        match result {
+            Ok(result) => debug!("ability {handle} hit {target:?}: {result:?}"),
+            Err(err) => debug!("rejected hit of ability {handle} on {target:?}: {err}"),
+        }
+    }
+
+    #[rpc(81, client)]
+    pub fn client_receive_hit(
+        &self,
+        source: FNetworkGUID,
+        damage: f32,
+        is_critical: bool,
+        reaction_tag: String,
+    ) {
+    }
+}
+
+impl ActiveGameplayEffect {
+    /// `FGameplayEffectConstants::INFINITE_DURATION`
+    pub const INFINITE_DURATION: f32 = -1.0;
+}
+
 impl ObjectLayout for AbilitySystemComponent {
     fn on_channel_open(&self, channel: &mut crate::net::ActorChannel, world: &World) {
         self.activatable_abilities.iter().for_each(|(_, ability)| {
             channel.export_net_guid(world.export_guid(ability.ability.get()));
         });
+
+        self.export_gameplay_effect_defs(channel, world);
+    }
+
+    fn on_channel_tick(&self, channel: &mut crate::net::ActorChannel, world: &World) {
+        // Effects applied after the channel was opened may reference new definitions.
+        self.export_gameplay_effect_defs(channel, world);
     }
 }
@@ -1,13 +1,16 @@
 use fadia_codegen::{RepLayout, rpc_handlers};
-use fadia_engine::FNetworkGUID;
 use fadia_engine::replication::property::{PropertyObject, PropertyVector};
+use fadia_engine::{FGuid, FNetworkGUID};
+use tracing::debug;
 
 use crate::logic::actor::NetRole;
 use crate::logic::hotta::HottaReplicatedObject;
 use crate::logic::hotta::player_state::HottaPlayerState;
 use crate::logic::rpc::call_rpcs;
+use crate::logic::travel::{self, ETravelType};
+use crate::logic::{cooldown, dungeon, vehicle, weather};
 
-use crate::logic::ObjectLayout;
+use crate::logic::{ObjectLayout, SubObjectList};
 use crate::logic::{actor::PropertyNetRole, rpc::RpcContext};
 use crate::net::World;
 
@@ -33,11 +36,7 @@ impl PlayerControllerBase {
         world: &mut World,
         remote_role: NetRole,
         role: NetRole,
-    ) -> (
-        FNetworkGUID,
-        Self,
-        Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
-    ) {
+    ) -> (FNetworkGUID, Self, SubObjectList) {
         (
             world
                 .net_guid_cache
@@ -75,7 +74,7 @@ impl PlayerControllerBase {
 
         let player_state_guid = context
             .world
-            .get_actor_archetype_new::<PlayerControllerBase>(player_controller_guid)
+            .get_actor_object::<PlayerControllerBase>(player_controller_guid)
             .unwrap()
             .data()
             .player_state
@@ -83,7 +82,7 @@ impl PlayerControllerBase {
 
         let pawn_guid = context
             .world
-            .get_actor_archetype_new::<PlayerState>(player_state_guid)
+            .get_actor_object::<PlayerState>(player_state_guid)
             .unwrap()
             .data()
             .equipped_players
@@ -93,20 +92,33 @@ impl PlayerControllerBase {
 
         let player_controller_base = context
             .world
-            .get_actor_archetype_mut_new::<PlayerControllerBase>(player_controller_guid)
+            .get_actor_object_mut::<PlayerControllerBase>(player_controller_guid)
             .unwrap();
 
         call_rpcs!(player_controller_base.client_retry_client_restart(pawn_guid));
 
+        let item_effect_cd_data =
+            cooldown::with_cd_manager(context.world, player_state_guid, |cd_manager, now| {
+                cd_manager.item_effect_cd_data(now)
+            })
+            .unwrap_or_default();
+
+        let placement_vehicle_data =
+            vehicle::placement_vehicle_data(context.world, player_state_guid);
+
+        let clone_ids = dungeon::clone_ids(context.world, player_state_guid);
+
         let player_state = context
             .world
-            .get_actor_archetype_mut_new::<PlayerState>(player_state_guid)
+            .get_actor_object_mut::<PlayerState>(player_state_guid)
             .unwrap();
 
-        let state_data = HottaPlayerState {
+        let mut state_data = HottaPlayerState {
             has_named: true,
             world_level: 5,
             max_world_level: 5,
+            item_effect_cd_data,
+            placement_vehicle_data,
             unlock_avatar_ids: assets
                 .data_asset_set
                 .avatar_data_table
@@ -124,6 +136,13 @@ impl PlayerControllerBase {
             ..Default::default()
         };
 
+        player_state
+            .data()
+            .stamina_recovery
+            .fill_hotta_player_state(&mut state_data);
+
+        clone_ids.fill_hotta_player_state(&mut state_data);
+
         let replicated_state_container = state_data.replicate(player_state_guid);
 
         call_rpcs! {
@@ -143,7 +162,7 @@ impl PlayerControllerBase {
 
         let player_state_guid = context
             .world
-            .get_actor_archetype_new::<PlayerControllerBase>(player_controller_guid)
+            .get_actor_object::<PlayerControllerBase>(player_controller_guid)
             .unwrap()
             .data()
             .player_state
@@ -151,16 +170,66 @@ impl PlayerControllerBase {
 
         context
             .world
-            .get_actor_archetype_mut_new::<PlayerState>(player_state_guid)
+            .get_actor_object_mut::<PlayerState>(player_state_guid)
             .unwrap()
             .data_mut()
             .server_ready_flag
             .set_value(true);
     }
 
+    /// `ServerExec`, console commands of the client. `servertravel <map>` travels seamlessly,
+    /// `travel <map>` makes the client reconnect to the map, `enterdungeon <clone id>` and
+    /// `leavedungeon` move the player to and from the instance of a dungeon, `weather <preset>`
+    /// forces a weather preset in every world and `weather` alone clears it.
+    #[rpc(75, server)]
+    fn server_exec(context: RpcContext, msg: String) {
+        let mut args = msg.split_whitespace();
+        let command = args.next().unwrap_or_default().to_ascii_lowercase();
+
+        let result: Result<(), Box<dyn std::error::Error>> = match (command.as_str(), args.next()) {
+            ("servertravel", Some(map)) => {
+                travel::server_travel(context.world, context.connection, map, true)
+                    .map_err(Into::into)
+            }
+            ("travel", Some(map)) => {
+                travel::server_travel(context.world, context.connection, map, false)
+                    .map_err(Into::into)
+            }
+            ("enterdungeon", Some(id)) => {
+                dungeon::enter_dungeon(context.world, context.connection, id).map_err(Into::into)
+            }
+            ("leavedungeon", None) => {
+                dungeon::leave_dungeon(context.world, context.connection).map_err(Into::into)
+            }
+            ("weather", preset) => {
+                weather::force_preset(&context.world.globals.weather, preset).map_err(Into::into)
+            }
+            _ => {
+                debug!("unsupported command: {msg}");
+                return;
+            }
+        };
+
+        if let Err(err) = result {
+            debug!("{msg} failed: {err}");
+        }
+    }
+
     #[rpc(46, client)]
     pub fn client_retry_client_restart(&self, new_pawn: FNetworkGUID) {}
 
     #[rpc(53, client)]
     pub fn client_set_hud(&self, hud_guid: FNetworkGUID) {}
+
+    /// `ClientTravelInternal`. Its index, like the one of `ServerExec`, is missing from the dumps
+    /// and follows the alphabetical order of the engine RPCs from `ClientSetHUD`.
+    #[rpc(62, client)]
+    pub fn client_travel_internal(
+        &self,
+        url: String,
+        travel_type: ETravelType,
+        seamless: bool,
+        map_package_guid: FGuid,
+    ) {
+    }
 }
@@ -14,11 +14,16 @@ use tracing::info;
 
 use crate::{
     logic::{
-        ObjectLayout,
+        ObjectLayout, SubObjectList,
         actor::{NetRole, PropertyNetRole},
+        clock,
         hotta::HottaReplicatedObjectPropertyContainer,
-        layout::PlayerControllerBase,
+        layout::{
+            CDManager, InventoryComponent, PlayerControllerBase, ShieldComponent, TimerClock,
+            VehicleComponent,
+        },
         rpc::RpcContext,
+        stamina::{MAX_STAMINA, MAX_STRENGTH, StaminaRecovery},
     },
     net::World,
 };
@@ -78,6 +83,18 @@ pub struct PlayerState {
     pub curr_character_net_id_serial: PropertyU32,
     #[rep(handle = 99)]
     pub avatar_id: PropertyName,
+    #[rep(ignore)]
+    pub stamina_recovery: StaminaRecovery,
+    #[rep(ignore)]
+    pub inventory: FNetworkGUID,
+    #[rep(ignore)]
+    pub timer_clock: FNetworkGUID,
+    #[rep(ignore)]
+    pub cd_manager: FNetworkGUID,
+    #[rep(ignore)]
+    pub shield_component: FNetworkGUID,
+    #[rep(ignore)]
+    pub vehicle_component: FNetworkGUID,
 }
 
 #[derive(Debug, Default)]
@@ -86,12 +103,17 @@ pub struct PlayerUniqueID {
     pub device: PropertyString,
 }
 
+const INVENTORY_COMPONENT: &str = "InventoryComponent";
+const TIMER_CLOCK: &str = "TimerClock";
+const CD_MANAGER: &str = "CDManager";
+const SHIELD_COMPONENT: &str = "ShieldComponent";
+const VEHICLE_COMPONENT: &str = "VehicleComponent";
 const SUB_CLASSES: &[&str] = &[
-    "InventoryComponent",
-    "TimerClock",
-    "CDManager",
-    "ShieldComponent",
-    "VehicleComponent",
+    INVENTORY_COMPONENT,
+    TIMER_CLOCK,
+    CD_MANAGER,
+    SHIELD_COMPONENT,
+    VEHICLE_COMPONENT,
     "SystematicPlayerComponent",
 ];
 
@@ -100,31 +122,51 @@ impl PlayerState {
         world: &mut World,
         remote_role: NetRole,
         role: NetRole,
-    ) -> (
-        FNetworkGUID,
-        Self,
-        Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
-    ) {
+    ) -> (FNetworkGUID, Self, SubObjectList) {
         let own_guid = world
             .net_guid_cache
             .assign_new_net_guid_for_dynamic_object(None);
 
+        let mut state = PlayerState::internal_new(remote_role, role);
         let sub_objects = SUB_CLASSES
             .iter()
-            .map(|name| {
+            .map(|&name| {
                 let sub_guid = world
                     .net_guid_cache
                     .assign_new_net_guid_for_dynamic_object(Some(name));
 
-                (sub_guid, Box::new(NullLayout) as _)
+                let layout: Box<dyn ObjectLayout> = match name {
+                    INVENTORY_COMPONENT => {
+                        state.inventory = sub_guid;
+                        Box::new(InventoryComponent::default())
+                    }
+                    TIMER_CLOCK => {
+                        state.timer_clock = sub_guid;
+
+                        let mut timer_clock = TimerClock::default();
+                        timer_clock.sync(&clock::snapshot(world));
+                        Box::new(timer_clock)
+                    }
+                    CD_MANAGER => {
+                        state.cd_manager = sub_guid;
+                        Box::new(CDManager::default())
+                    }
+                    SHIELD_COMPONENT => {
+                        state.shield_component = sub_guid;
+                        Box::new(ShieldComponent::default())
+                    }
+                    VEHICLE_COMPONENT => {
+                        state.vehicle_component = sub_guid;
+                        Box::new(VehicleComponent::default())
+                    }
+                    _ => Box::new(NullLayout),
+                };
+
+                (sub_guid, layout)
             })
             .collect();
 
-        (
-            own_guid,
-            PlayerState::internal_new(remote_role, role),
-            sub_objects,
-        )
+        (own_guid, state, sub_objects)
     }
 
     fn internal_new(remote_role: NetRole, role: NetRole) -> Self {
@@ -145,16 +187,22 @@ impl PlayerState {
             birthday_day: Default::default(),
             play_time_seconds: Default::default(),
             player_world_time_seconds_delta: Default::default(),
-            current_stamina: PropertyU32::new(200),
+            current_stamina: PropertyU32::new(MAX_STAMINA),
             rand_bean: Default::default(),
             cur_bean_count: Default::default(),
-            strength_current: PropertyF32::new(100.0),
+            strength_current: PropertyF32::new(MAX_STRENGTH),
             role_level: PropertyU32::new(1),
             role_exp: Default::default(),
             equipped_players: Default::default(),
             curr_character_net_id_solt: Default::default(),
             curr_character_net_id_serial: Default::default(),
             avatar_id: PropertyName::new(FName::Custom(String::from("1"))),
+            stamina_recovery: StaminaRecovery::default(),
+            inventory: FNetworkGUID::default(),
+            timer_clock: FNetworkGUID::default(),
+            cd_manager: FNetworkGUID::default(),
+            shield_component: FNetworkGUID::default(),
+            vehicle_component: FNetworkGUID::default(),
         }
     }
 }
@@ -184,7 +232,7 @@ impl PlayerState {
 
         let player_state_guid = context
             .world
-            .get_actor_archetype_new::<PlayerControllerBase>(player_controller_guid)
+            .get_actor_object::<PlayerControllerBase>(player_controller_guid)
             .unwrap()
             .data()
             .player_state
@@ -192,7 +240,7 @@ impl PlayerState {
 
         context
             .world
-            .get_actor_archetype_mut_new::<PlayerState>(player_state_guid)
+            .get_actor_object_mut::<PlayerState>(player_state_guid)
             .unwrap()
             .data_mut()
             .str_role_name
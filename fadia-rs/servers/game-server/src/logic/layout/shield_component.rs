@@ -0,0 +1,25 @@
+use fadia_codegen::{RepLayout, dummy_rpc_handler};
+use fadia_engine::replication::property::PropertyF32;
+
+use crate::logic::ObjectLayout;
+
+/// `UShieldComponent`, a sub-object of `PlayerState` which shows the shields
+/// of the current character of the player, see `AbilitySystemComponent::shields`.
+///
+/// Unverified: the layout of the component is missing from the dumps, so the handles of
+/// the total value of the shields and its maximum are guessed. They're only replicated
+/// with `replicate_unverified_layouts`.
+#[derive(Debug, Default, RepLayout)]
+#[dummy_rpc_handler]
+pub struct ShieldComponent {
+    #[rep(handle = 1)]
+    pub shield_value: PropertyF32,
+    #[rep(handle = 2)]
+    pub shield_max: PropertyF32,
+}
+
+impl ObjectLayout for ShieldComponent {
+    fn is_unverified(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,37 @@
+use fadia_codegen::{RepLayout, dummy_rpc_handler};
+use fadia_engine::replication::property::{PropertyF32, PropertyU64};
+
+use crate::logic::{ObjectLayout, clock::ClockSnapshot};
+
+/// `UTimerClock`, a sub-object of `PlayerState` which keeps the client in sync with the server clock.
+///
+/// Unverified: the layout of the component is missing from the dumps, so the property handles
+/// are guessed and only replicated with `replicate_unverified_layouts`. Timestamps are unix
+/// times in milliseconds, the time of day is the hour of the in-game day.
+#[derive(Debug, Default, RepLayout)]
+#[dummy_rpc_handler]
+pub struct TimerClock {
+    #[rep(handle = 1)]
+    pub server_time: PropertyU64,
+    #[rep(handle = 2)]
+    pub time_of_day: PropertyF32,
+    #[rep(handle = 3)]
+    pub next_daily_reset: PropertyU64,
+    #[rep(handle = 4)]
+    pub next_weekly_reset: PropertyU64,
+}
+
+impl TimerClock {
+    pub fn sync(&mut self, snapshot: &ClockSnapshot) {
+        self.server_time.set_value(snapshot.server_time);
+        self.time_of_day.set_value(snapshot.time_of_day);
+        self.next_daily_reset.set_value(snapshot.next_daily_reset);
+        self.next_weekly_reset.set_value(snapshot.next_weekly_reset);
+    }
+}
+
+impl ObjectLayout for TimerClock {
+    fn is_unverified(&self) -> bool {
+        true
+    }
+}
@@ -1,7 +1,16 @@
 use fadia_codegen::{RepLayout, dummy_rpc_handler};
-use fadia_engine::replication::property::{PropertyF64, PropertyU32};
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::{PropertyF64, PropertyU32},
+};
 
-use crate::logic::{actor::PropertyNetRole, ObjectLayout};
+use crate::{
+    logic::{
+        ObjectLayout,
+        actor::{NetRole, PropertyNetRole},
+    },
+    net::World,
+};
 
 #[derive(Debug, RepLayout)]
 #[dummy_rpc_handler]
@@ -36,4 +45,30 @@ pub struct UltraDynamicWeather {
     pub season: PropertyF64,
 }
 
+impl UltraDynamicWeather {
+    pub fn new(world: &mut World, remote_role: NetRole, role: NetRole) -> (FNetworkGUID, Self) {
+        (
+            world
+                .net_guid_cache
+                .assign_new_net_guid_for_dynamic_object(None),
+            UltraDynamicWeather {
+                remote_role: PropertyNetRole::new(remote_role),
+                role: PropertyNetRole::new(role),
+                weater_index_replicate: PropertyU32::default(),
+                cloud_coverage: PropertyF64::default(),
+                fog: PropertyF64::default(),
+                wind_intensity: PropertyF64::default(),
+                rain: PropertyF64::default(),
+                snow: PropertyF64::default(),
+                thunder_lightning: PropertyF64::default(),
+                material_snow_coverage: PropertyF64::default(),
+                transition_state: PropertyU32::default(),
+                weather_speed: PropertyF64::new(1.0),
+                replicated_material_snow: PropertyF64::default(),
+                season: PropertyF64::default(),
+            },
+        )
+    }
+}
+
 impl ObjectLayout for UltraDynamicWeather {}
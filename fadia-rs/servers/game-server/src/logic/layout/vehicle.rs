@@ -0,0 +1,104 @@
+use fadia_codegen::{RepLayout, rpc_handlers};
+use fadia_engine::{
+    FNetworkGUID,
+    replication::property::{PropertyObject, PropertyRepMovement},
+    rotator::FRotator,
+    vector::FVector3d,
+};
+use tracing::debug;
+
+use crate::{
+    logic::{
+        ObjectLayout,
+        actor::{NetRole, PropertyNetRole},
+        rpc::RpcContext,
+        vehicle,
+    },
+    net::World,
+};
+
+/// Vehicle placed by a player, a pawn which the player possesses while driving it.
+///
+/// Unverified: vehicle blueprints are missing from the dumps, so the property handles are
+/// guessed from the ones `HTPlayerCharacter` and `MonsterCharacter` share, and the movement
+/// RPC follows them. Vehicles are only spawned from the vehicle table, which isn't dumped yet.
+#[derive(Debug, RepLayout)]
+#[max_rep_index(21)]
+pub struct VehiclePawn {
+    #[rep(handle = 5)]
+    pub remote_role: PropertyNetRole,
+    #[rep(handle = 12)]
+    pub replicated_movement: PropertyRepMovement,
+    #[rep(handle = 13)]
+    pub owner: PropertyObject,
+    #[rep(handle = 14)]
+    pub role: PropertyNetRole,
+    #[rep(handle = 15)]
+    pub instigator: PropertyObject,
+    #[rep(handle = 18)]
+    pub player_state: PropertyObject,
+    #[rep(handle = 19)]
+    pub controller: PropertyObject,
+    #[rep(ignore)]
+    pub max_speed: f64,
+    /// World time of the last accepted movement update, set while the vehicle is driven.
+    #[rep(ignore)]
+    pub last_move_time: Option<f64>,
+}
+
+impl VehiclePawn {
+    pub fn new(
+        world: &mut World,
+        max_speed: f64,
+        location: FVector3d,
+        yaw: f64,
+    ) -> (FNetworkGUID, Self) {
+        let guid = world
+            .net_guid_cache
+            .assign_new_net_guid_for_dynamic_object(None);
+
+        (
+            guid,
+            VehiclePawn {
+                remote_role: PropertyNetRole::new(NetRole::SimulatedProxy),
+                replicated_movement: PropertyRepMovement::new(
+                    location,
+                    FRotator::new(0.0, yaw, 0.0),
+                ),
+                owner: PropertyObject::default(),
+                role: PropertyNetRole::new(NetRole::Authority),
+                instigator: PropertyObject::new(guid),
+                player_state: PropertyObject::default(),
+                controller: PropertyObject::default(),
+                max_speed,
+                last_move_time: None,
+            },
+        )
+    }
+}
+
+#[rpc_handlers]
+impl VehiclePawn {
+    #[rpc(20, server)]
+    fn server_move_vehicle(
+        context: RpcContext,
+        location: FVector3d,
+        yaw: f32,
+        velocity: FVector3d,
+    ) {
+        if let Err(err) = vehicle::move_vehicle(
+            context.world,
+            context.actor_guid,
+            location,
+            f64::from(yaw),
+            velocity,
+        ) {
+            debug!(
+                "rejected movement of vehicle {:?}: {err}",
+                context.actor_guid
+            );
+        }
+    }
+}
+
+impl ObjectLayout for VehiclePawn {}
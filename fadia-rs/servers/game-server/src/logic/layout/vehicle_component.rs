@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use fadia_codegen::{RepLayout, rpc_handlers};
+use fadia_engine::{FNetworkGUID, replication::property::PropertyObject, vector::FVector3d};
+use tracing::debug;
+
+use crate::logic::{ObjectLayout, rpc::RpcContext, vehicle};
+
+/// `UVehicleComponent`, a sub-object of `PlayerState` which tracks the vehicles of the player.
+///
+/// Unverified: the layout of the component is missing from the dumps, so the handle of the
+/// driven vehicle and the RPCs following it are guessed. The vehicle is only replicated
+/// with `replicate_unverified_layouts`.
+#[derive(Debug, Default, RepLayout)]
+#[max_rep_index(4)]
+pub struct VehicleComponent {
+    /// `VehiclePawn` driven by the player, null while the player is on foot.
+    #[rep(handle = 1)]
+    pub current_vehicle: PropertyObject,
+    /// Vehicles placed by the player keyed by their IDs, there's one of every kind.
+    #[rep(ignore)]
+    pub placed_vehicles: BTreeMap<String, FNetworkGUID>,
+}
+
+#[rpc_handlers]
+impl VehicleComponent {
+    #[rpc(1, server)]
+    fn server_place_vehicle(
+        context: RpcContext,
+        vehicle_id: String,
+        location: FVector3d,
+        yaw: f32,
+    ) {
+        if let Err(err) = vehicle::place_vehicle(
+            context.world,
+            context.connection,
+            context.actor_guid,
+            &vehicle_id,
+            location,
+            f64::from(yaw),
+        ) {
+            debug!("failed to place vehicle {vehicle_id}: {err}");
+        }
+    }
+
+    #[rpc(2, server)]
+    fn server_mount_vehicle(context: RpcContext, vehicle: FNetworkGUID) {
+        if let Err(err) = vehicle::mount_vehicle(context.world, context.actor_guid, vehicle) {
+            debug!("failed to mount vehicle {vehicle:?}: {err}");
+        }
+    }
+
+    #[rpc(3, server)]
+    fn server_dismount_vehicle(context: RpcContext) {
+        if let Err(err) = vehicle::dismount_vehicle(context.world, context.actor_guid) {
+            debug!("failed to dismount vehicle: {err}");
+        }
+    }
+}
+
+impl ObjectLayout for VehicleComponent {
+    fn is_unverified(&self) -> bool {
+        true
+    }
+}
@@ -7,7 +7,7 @@ use fadia_engine::{
 
 use crate::{
     logic::{
-        ObjectLayout,
+        ObjectLayout, SubObjectList,
         actor::{NetRole, PropertyNetRole},
     },
     net::World,
@@ -37,11 +37,7 @@ impl WeaponBase {
         remote_role: NetRole,
         role: NetRole,
         world: &mut World,
-    ) -> (
-        FNetworkGUID,
-        Self,
-        Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
-    ) {
+    ) -> (FNetworkGUID, Self, SubObjectList) {
         let guid = world
             .net_guid_cache
             .assign_new_net_guid_for_dynamic_object(None);
@@ -1,12 +1,25 @@
+pub mod ability;
 pub mod actor;
+pub mod clock;
 pub mod cluster;
+pub mod cooldown;
+pub mod data_layers;
+pub mod dungeon;
+pub mod gameplay_debugger;
 pub mod hotta;
+pub mod inventory;
 pub mod layout;
 pub mod mode;
+pub mod npc;
 mod object;
+pub mod progression;
 pub mod replication;
 pub mod rpc;
 pub mod scope;
+pub mod stamina;
 pub mod state;
+pub mod travel;
+pub mod vehicle;
+pub mod weather;
 
-pub use object::{MutObjectWrap, Object, ObjectLayout, RefObjectWrap};
+pub use object::{MutObjectWrap, Object, ObjectLayout, RefObjectWrap, SubObjectList};
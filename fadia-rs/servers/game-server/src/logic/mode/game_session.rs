@@ -11,6 +11,8 @@ use crate::{
     net::World,
 };
 
+use super::SessionLoginOptions;
+
 pub struct GameSession {
     pub max_spectators: usize,
     pub max_players: usize,
@@ -19,11 +21,6 @@ pub struct GameSession {
     cur_spectator_count: Cell<usize>,
 }
 
-pub struct SessionLoginOptions {
-    pub spectator_only: bool,
-    pub splitscreen_count: u8,
-}
-
 impl GameSession {
     pub fn get_next_player_id(&self) -> u32 {
         // Start at 256, because 255 is special (means all team for some UT Emote stuff)
@@ -33,7 +30,7 @@ impl GameSession {
         NEXT_PLAYER_ID.fetch_add(1, Ordering::SeqCst)
     }
 
-    pub fn approve_login(&self, options: SessionLoginOptions) -> Result<(), &'static str> {
+    pub fn approve_login(&self, options: &SessionLoginOptions) -> Result<(), &'static str> {
         if self.at_capacity(options.spectator_only) {
             return Err("Server full.");
         }
@@ -55,18 +52,25 @@ impl GameSession {
         world: &mut World,
         unique_id: String,
     ) {
-        if let Some(pc) =
-            world.get_actor_archetype_new::<PlayerControllerBase>(new_player_controller)
+        if let Some(pc) = world.get_actor_object::<PlayerControllerBase>(new_player_controller)
+            && let Some(mut state) =
+                world.get_actor_object_mut::<PlayerState>(pc.data().player_state.get())
         {
-            if let Some(mut state) =
-                world.get_actor_archetype_mut_new::<PlayerState>(pc.data().player_state.get())
-            {
-                let state = state.data_mut();
-                state.player_id.set_value(self.get_next_player_id());
-                state.unique_id.device.set_value(unique_id);
+            let state = state.data_mut();
+            state.player_id.set_value(self.get_next_player_id());
+            state.unique_id.device.set_value(unique_id);
 
-                self.cur_player_count.update(|count| count + 1);
-            }
+            self.cur_player_count.update(|count| count + 1);
+        }
+    }
+
+    pub fn unregister_player(&self, exiting_player_controller: FNetworkGUID, world: &World) {
+        if world
+            .get_actor_object::<PlayerControllerBase>(exiting_player_controller)
+            .is_some()
+        {
+            self.cur_player_count
+                .update(|count| count.saturating_sub(1));
         }
     }
 
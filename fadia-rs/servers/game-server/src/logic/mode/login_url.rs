@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use crate::config::GameplayGlobals;
+
+/// Request URL of `NMT_Login`, `FURL`: the map the client asks for followed by `?Key=Value`
+/// options, e.g. `/Game/Maps/Map_bigworld/XL_map_bigworld_test?Name=Player?SpectatorOnly=1`.
+#[derive(Debug, Default, Clone)]
+pub struct LoginUrl {
+    /// Empty if the client doesn't ask for a map.
+    pub map: String,
+    pub options: SessionLoginOptions,
+}
+
+/// Options of the login URL. Option names are case-insensitive, like `UGameplayStatics::ParseOption`.
+#[derive(Debug, Default, Clone)]
+pub struct SessionLoginOptions {
+    /// `?Name=`, the name of the player.
+    pub name: Option<String>,
+    /// `?SpectatorOnly=1`
+    pub spectator_only: bool,
+    /// `?SplitscreenCount=`
+    pub splitscreen_count: u8,
+    /// `?Character=`, the player character to spawn instead of `GameplayGlobals::player_character`.
+    pub character: Option<String>,
+    /// `?Team=`
+    pub team: Option<u8>,
+    /// Options the server doesn't know, e.g. Hotta-specific ones, keyed by option names.
+    /// Options without a value are kept with an empty one.
+    pub other: BTreeMap<String, String>,
+}
+
+impl LoginUrl {
+    pub fn parse(url: &str) -> Self {
+        // `#portal` ends the options.
+        let url = url.split('#').next().unwrap_or_default();
+        let mut parts = url.split('?');
+
+        let map = parts.next().unwrap_or_default().to_string();
+        let mut options = SessionLoginOptions::default();
+
+        for option in parts.filter(|option| !option.is_empty()) {
+            let (key, value) = option.split_once('=').unwrap_or((option, ""));
+            options.set(key, value);
+        }
+
+        Self { map, options }
+    }
+}
+
+impl SessionLoginOptions {
+    /// Player character asked for by the client, or the configured one.
+    pub fn character<'a>(&'a self, globals: &'a GameplayGlobals) -> &'a str {
+        self.character
+            .as_deref()
+            .unwrap_or(&globals.player_character)
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key.to_ascii_lowercase().as_str() {
+            "name" => self.name = Some(value.to_string()).filter(|name| !name.is_empty()),
+            // `AGameModeBase::PreLogin` checks `ParseOption(Options, "SpectatorOnly") == "1"`.
+            "spectatoronly" => self.spectator_only = value == "1",
+            "splitscreencount" => self.splitscreen_count = value.parse().unwrap_or_default(),
+            "character" => {
+                self.character = Some(value.to_string()).filter(|character| !character.is_empty())
+            }
+            "team" => self.team = value.parse().ok(),
+            // Session ticket options of the travel URL, the token was checked in `NMT_Hello`
+            // and the key is a secret which mustn't end up in logs.
+            "encryptiontoken" | "encryptionkey" => {}
+            _ => {
+                self.other.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_map_and_options() {
+        let url = LoginUrl::parse(
+            "/Game/Maps/Map_bigworld/XL_map_bigworld_test?Name=Player?Team=2?SplitscreenCount=1",
+        );
+
+        assert_eq!(url.map, "/Game/Maps/Map_bigworld/XL_map_bigworld_test");
+        assert_eq!(url.options.name.as_deref(), Some("Player"));
+        assert_eq!(url.options.team, Some(2));
+        assert_eq!(url.options.splitscreen_count, 1);
+    }
+
+    #[test]
+    fn option_names_are_case_insensitive() {
+        let url = LoginUrl::parse("Map?NAME=Player?character=Player_039_Fadia?spectatoronly=1");
+
+        assert_eq!(url.options.name.as_deref(), Some("Player"));
+        assert_eq!(url.options.character.as_deref(), Some("Player_039_Fadia"));
+        assert!(url.options.spectator_only);
+    }
+
+    #[test]
+    fn spectator_only_must_be_one() {
+        assert!(!LoginUrl::parse("Map?SpectatorOnly").options.spectator_only);
+        assert!(!LoginUrl::parse("Map?SpectatorOnly=").options.spectator_only);
+        assert!(
+            !LoginUrl::parse("Map?SpectatorOnly=0")
+                .options
+                .spectator_only
+        );
+        assert!(
+            !LoginUrl::parse("Map?SpectatorOnly=true")
+                .options
+                .spectator_only
+        );
+        assert!(
+            LoginUrl::parse("Map?SpectatorOnly=1")
+                .options
+                .spectator_only
+        );
+    }
+
+    #[test]
+    fn keeps_bare_and_unknown_options() {
+        let url = LoginUrl::parse("Map?Listen?HybridMode=1?Name=");
+
+        assert_eq!(
+            url.options.other.get("Listen").map(String::as_str),
+            Some("")
+        );
+        assert_eq!(
+            url.options.other.get("HybridMode").map(String::as_str),
+            Some("1")
+        );
+        assert_eq!(url.options.name, None);
+    }
+
+    #[test]
+    fn drops_session_ticket_options() {
+        let url = LoginUrl::parse("Map?EncryptionToken=0A1B?EncryptionKey=2C3D?Name=Player");
+
+        assert_eq!(url.options.name.as_deref(), Some("Player"));
+        assert!(url.options.other.is_empty());
+    }
+
+    #[test]
+    fn skips_empty_options_and_portal() {
+        let url = LoginUrl::parse("Map??Name=Player???Team=1#Portal?Name=Other");
+
+        assert_eq!(url.map, "Map");
+        assert_eq!(url.options.name.as_deref(), Some("Player"));
+        assert_eq!(url.options.team, Some(1));
+        assert!(url.options.other.is_empty());
+    }
+
+    #[test]
+    fn parses_url_without_options() {
+        let url = LoginUrl::parse("");
+
+        assert!(url.map.is_empty());
+        assert!(url.options.name.is_none());
+        assert!(url.options.other.is_empty());
+    }
+}
@@ -10,14 +10,21 @@ use crate::{
 };
 
 use super::{
+    ability,
     actor::NetRole,
+    clock::ClockEvent,
+    cooldown, dungeon, gameplay_debugger, inventory,
     layout::{HTPlayerCharacter, PlayerControllerBase, PlayerState, WeaponBase},
+    progression::PlayerProgression,
+    stamina,
     state::GameStateBase,
+    vehicle,
 };
 
 mod game_session;
+mod login_url;
 
-pub use game_session::SessionLoginOptions;
+pub use login_url::{LoginUrl, SessionLoginOptions};
 
 #[derive(thiserror::Error, Debug)]
 #[error("PreLogin failed: {0}")]
@@ -30,13 +37,13 @@ pub struct LoginError(pub &'static str);
 pub trait GameModeBase {
     fn init_game_state(&self, state: &mut dyn GameStateBase);
     fn game_state_class(&self) -> FNetworkGUID;
-    fn pre_login(&self, options: SessionLoginOptions) -> Result<(), PreLoginError>;
+    fn pre_login(&self, options: &SessionLoginOptions) -> Result<(), PreLoginError>;
     fn login(
         &self,
         connection: &mut NetConnection,
         remote_role: NetRole,
         world: &mut World,
-        options: SessionLoginOptions,
+        options: &SessionLoginOptions,
         unique_id: String,
     ) -> Result<FNetworkGUID, LoginError>;
     fn post_login(
@@ -45,6 +52,15 @@ pub trait GameModeBase {
         player_controller_guid: FNetworkGUID,
         world: &mut World,
     );
+    /// Called when a player leaves the world, before its actors are destroyed.
+    fn logout(
+        &self,
+        connection: &mut NetConnection,
+        player_controller_guid: FNetworkGUID,
+        world: &mut World,
+    );
+    /// Called by the server clock when a scheduled event is due, e.g. the daily reset.
+    fn on_clock_event(&self, world: &mut World, event: ClockEvent);
 }
 
 pub trait NewGameMode: GameModeBase {
@@ -82,6 +98,9 @@ const GAME_MODE_CLASS: &[&str] = &["/Game/Blueprints/GameMode/BP_HTGameMode", "B
 
 const SPECTATOR_CLASS: &[&str] = &["/Script/Engine", "SpectatorPawn"];
 
+/// Name of players who log in without `?Name=`.
+const DEFAULT_PLAYER_NAME: &str = "Hotta";
+
 impl NewGameMode for HTGameMode {
     fn new(world: &mut World) -> Self {
         let self_guid = world.register_hierarchy_for_static_objects(GAME_MODE_CLASS);
@@ -116,7 +135,7 @@ impl GameModeBase for HTGameMode {
         self.game_state_class
     }
 
-    fn pre_login(&self, options: SessionLoginOptions) -> Result<(), PreLoginError> {
+    fn pre_login(&self, options: &SessionLoginOptions) -> Result<(), PreLoginError> {
         self.game_session
             .approve_login(options)
             .map_err(PreLoginError)
@@ -127,21 +146,30 @@ impl GameModeBase for HTGameMode {
         _connection: &mut NetConnection,
         remote_role: NetRole,
         world: &mut World,
-        options: SessionLoginOptions,
+        options: &SessionLoginOptions,
         unique_id: String,
     ) -> Result<FNetworkGUID, LoginError> {
         self.game_session
             .approve_login(options)
             .map_err(LoginError)?;
 
+        if world
+            .assets
+            .get_player_character_config(options.character(world.globals))
+            .is_none()
+        {
+            return Err(LoginError("Unknown character."));
+        }
+
+        let [x, y, z] = world.map.player_start;
         let player_controller_guid = self.spawn_player_controller_common(
             remote_role,
-            FVector3d::new(-79551.5, 158422.4, 4939.1),
+            FVector3d::new(x, y, z),
             FRotator::default(),
             world,
         );
 
-        self.init_new_player(player_controller_guid, world, unique_id);
+        self.init_new_player(player_controller_guid, world, options, unique_id);
 
         Ok(player_controller_guid)
     }
@@ -154,6 +182,31 @@ impl GameModeBase for HTGameMode {
     ) {
         self.generic_player_initialization(player_controller_guid, world);
         self.handle_starting_new_player(connection, player_controller_guid, world);
+        gameplay_debugger::spawn_gameplay_debugger(world, connection, player_controller_guid);
+    }
+
+    fn logout(
+        &self,
+        _connection: &mut NetConnection,
+        player_controller_guid: FNetworkGUID,
+        world: &mut World,
+    ) {
+        if let Some(player_state_guid) = world
+            .get_actor_object::<PlayerControllerBase>(player_controller_guid)
+            .map(|player_controller| player_controller.data().player_state.get())
+        {
+            ability::save_satiety(world, player_state_guid);
+        }
+
+        self.game_session
+            .unregister_player(player_controller_guid, world);
+    }
+
+    fn on_clock_event(&self, world: &mut World, event: ClockEvent) {
+        match event {
+            ClockEvent::DailyReset => stamina::refill_stamina(world),
+            ClockEvent::WeeklyReset => dungeon::weekly_reset(world),
+        }
     }
 }
 
@@ -204,14 +257,32 @@ impl HTGameMode {
         controller_guid
     }
 
-    fn init_new_player(&self, controller_guid: FNetworkGUID, world: &mut World, unique_id: String) {
+    fn init_new_player(
+        &self,
+        controller_guid: FNetworkGUID,
+        world: &mut World,
+        options: &SessionLoginOptions,
+        unique_id: String,
+    ) {
         self.game_session
             .register_player(controller_guid, world, unique_id);
+
+        let name = options.name.as_deref().unwrap_or(DEFAULT_PLAYER_NAME);
+        if let Some(state_guid) = world
+            .get_actor_object::<PlayerControllerBase>(controller_guid)
+            .map(|controller| controller.data().player_state.get())
+            && let Some(mut player_state) = world.get_actor_object_mut::<PlayerState>(state_guid)
+        {
+            player_state
+                .data_mut()
+                .player_name_private
+                .set_value(name.to_string());
+        }
     }
 
     fn generic_player_initialization(&self, controller_guid: FNetworkGUID, world: &mut World) {
         if let Some(mut player_controller) =
-            world.get_actor_archetype_mut_new::<PlayerControllerBase>(controller_guid)
+            world.get_actor_object_mut::<PlayerControllerBase>(controller_guid)
         {
             call_rpcs!(player_controller.client_set_hud(self.hud_class));
 
@@ -234,16 +305,54 @@ impl HTGameMode {
         controller_guid: FNetworkGUID,
         world: &mut World,
     ) {
-        let pawn_guid = self.spawn_default_pawn_for(controller_guid, world);
+        let character = connection
+            .login_options
+            .character(world.globals)
+            .to_string();
+
+        let pawn_guid = self.spawn_default_pawn_for(controller_guid, &character, world);
+        ability::init_ability_system(world, pawn_guid);
+
+        let progression = connection
+            .unique_id
+            .get()
+            .map(|unique_id| PlayerProgression::load_or_create(unique_id));
+
+        if let Some(progression) = progression.as_ref() {
+            let character_config = world
+                .assets
+                .get_player_character_config(&character)
+                .unwrap();
+
+            ability::apply_character_progression(
+                world,
+                pawn_guid,
+                character_config,
+                &progression.character(&character),
+            );
+            ability::init_satiety(world, pawn_guid, progression.satiety);
+        }
 
         let player_controller = world
-            .get_actor_archetype_new::<PlayerControllerBase>(controller_guid)
+            .get_actor_object::<PlayerControllerBase>(controller_guid)
             .unwrap();
 
         let state_guid = player_controller.data().player_state.get();
+        let (saved_items, saved_cooldowns, saved_vehicles) = progression
+            .map(|progression| {
+                (
+                    progression.items,
+                    progression.cooldowns,
+                    progression.vehicles,
+                )
+            })
+            .unwrap_or_default();
+
+        inventory::init_inventory(world, state_guid, saved_items);
+        cooldown::init_cooldowns(world, state_guid, &saved_cooldowns);
 
         let mut player_state = world
-            .get_actor_archetype_mut_new::<PlayerState>(state_guid)
+            .get_actor_object_mut::<PlayerState>(state_guid)
             .unwrap();
 
         let player_state = player_state.data_mut();
@@ -254,9 +363,6 @@ impl HTGameMode {
         // Fill data. Maybe make it configurable later.
         player_state.role_id.set_value(1337);
         player_state.role_level.set_value(60);
-        player_state
-            .player_name_private
-            .set_value(String::from("Hotta"));
         player_state
             .str_role_name
             .set_value(String::from("fadia-rs"));
@@ -274,7 +380,7 @@ impl HTGameMode {
         // Open a channel for Weapon, if needed
 
         let character = world
-            .get_actor_archetype_new::<HTPlayerCharacter>(pawn_guid)
+            .get_actor_object::<HTPlayerCharacter>(pawn_guid)
             .unwrap();
 
         let weapon_guid = character.data().current_weapon.get();
@@ -282,15 +388,18 @@ impl HTGameMode {
         if weapon_guid.is_valid() {
             world.open_actor_channel_at(connection, 11, weapon_guid);
         }
+
+        vehicle::init_vehicles(world, connection, state_guid, &saved_vehicles);
     }
 
     fn spawn_default_pawn_for(
         &self,
         controller_guid: FNetworkGUID,
+        character_name: &str,
         world: &mut World,
     ) -> FNetworkGUID {
         let player_controller = world
-            .get_actor_archetype_new::<PlayerControllerBase>(controller_guid)
+            .get_actor_object::<PlayerControllerBase>(controller_guid)
             .unwrap();
 
         let spawn_location = player_controller.data().spawn_location.get().clone();
@@ -298,7 +407,7 @@ impl HTGameMode {
 
         let character_config = world
             .assets
-            .get_player_character_config(&world.globals.player_character)
+            .get_player_character_config(character_name)
             .unwrap();
 
         let archetype_class = world.register_hierarchy_for_static_objects(&[
@@ -323,10 +432,7 @@ impl HTGameMode {
         character.saved_player_state.set_value(player_state_guid);
         character.server_ready_flag.set_value(true);
 
-        if let Some(weapon) = world
-            .assets
-            .get_weapon_for_character(&world.globals.player_character)
-        {
+        if let Some(weapon) = world.assets.get_weapon_for_character(character_name) {
             let mesh_guid = world
                 .net_guid_cache
                 .assign_new_net_guid_for_dynamic_object(Some(
@@ -0,0 +1,278 @@
+use fadia_config::spawn::MonsterAiSettings;
+use fadia_engine::{FNetworkGUID, rotator::FRotator, vector::FVector3d};
+use tracing::{debug, info};
+
+use crate::net::{SpawnActorParams, World};
+
+use super::{
+    ability::{self, HitSpec},
+    actor::NetRole,
+    layout::{HTPlayerCharacter, MonsterCharacter},
+};
+
+/// Channels of monsters are opened after the ones of the player and the level actors.
+pub const FIRST_MONSTER_CHANNEL_INDEX: u32 = 32;
+/// Longest simulated step, a late tick doesn't teleport monsters across the map.
+const MAX_TICK_DELTA: f64 = 1.0;
+
+/// State of the server AI of a monster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonsterAiState {
+    /// Standing at the spawn point until a player comes within the aggro radius.
+    Idle,
+    /// Moving towards the target until it's within the attack radius.
+    Chase(FNetworkGUID),
+    /// Attacking the target on an interval.
+    Attack(FNetworkGUID),
+    /// Returning to the spawn point, players are ignored on the way.
+    Leash,
+}
+
+#[derive(Debug)]
+pub struct MonsterAi {
+    pub settings: MonsterAiSettings,
+    /// Spawn point, the monster returns here once it's leashed.
+    pub home: FVector3d,
+    pub state: MonsterAiState,
+    next_attack_time: f64,
+    last_tick_time: Option<f64>,
+}
+
+impl MonsterAi {
+    pub fn new(settings: MonsterAiSettings, home: FVector3d) -> Self {
+        Self {
+            settings,
+            home,
+            state: MonsterAiState::Idle,
+            next_attack_time: 0.0,
+            last_tick_time: None,
+        }
+    }
+}
+
+/// Spawns the monsters placed on the map of the world by its spawn table.
+pub fn spawn_monsters(world: &mut World) -> Vec<FNetworkGUID> {
+    let (map, assets) = (world.map, world.assets);
+    let mut monsters = Vec::new();
+
+    for spawn in assets.get_monster_spawns(&map.path) {
+        let (package, _) = spawn.class.package_and_index().unwrap();
+        let archetype =
+            world.register_hierarchy_for_static_objects(&[package, &spawn.default_object_name]);
+
+        let (guid, monster, sub_objects) =
+            MonsterCharacter::new(world, spawn, NetRole::SimulatedProxy, NetRole::Authority);
+
+        let guid = world.spawn_actor(
+            SpawnActorParams::Dynamic {
+                guid,
+                pos: monster.ai.home.clone(),
+                rot: FRotator::new(0.0, spawn.yaw, 0.0),
+                archetype,
+                archetype_rep: Box::new(monster),
+            },
+            sub_objects,
+        );
+
+        ability::with_ability_system(world, guid, |asc, attributes, _, _| {
+            asc.recalculate_attributes(attributes);
+        });
+
+        monsters.push(guid);
+    }
+
+    info!("spawned {} monsters on {}", monsters.len(), map.name());
+
+    monsters
+}
+
+/// Runs the AI of every monster of the world.
+///
+/// Monsters only aggro on players whose client has sent their movement, see
+/// `Actor::last_client_move_time`. Movement RPCs of player characters aren't
+/// handled yet, so until they are, monsters stay at their spawn points.
+pub fn tick_monsters(world: &mut World) {
+    let now = world.time_seconds();
+
+    for guid in world.monsters().to_vec() {
+        tick_monster(world, guid, now);
+    }
+}
+
+fn tick_monster(world: &mut World, guid: FNetworkGUID, now: f64) {
+    // Dead monsters stay where they were killed.
+    if !ability::is_alive(world, guid) {
+        return;
+    }
+
+    let Some(position) = world.actors.get(&guid).map(|actor| actor.position.clone()) else {
+        return;
+    };
+
+    let Some(mut monster) = world.get_actor_object_mut::<MonsterCharacter>(guid) else {
+        return;
+    };
+
+    let ai = &mut monster.data_mut().ai;
+    let delta = now - ai.last_tick_time.replace(now).unwrap_or(now);
+    let settings = ai.settings;
+    let home = ai.home.clone();
+    let state = ai.state;
+    let next_attack_time = ai.next_attack_time;
+
+    let next_state = match state {
+        MonsterAiState::Idle => find_nearest_player(world, &position, settings.aggro_radius)
+            .map_or(MonsterAiState::Idle, MonsterAiState::Chase),
+        MonsterAiState::Chase(target) | MonsterAiState::Attack(target) => {
+            match target_position(world, target) {
+                Some(_) if position.dist_2d(&home) > settings.leash_radius => MonsterAiState::Leash,
+                Some(target_position)
+                    if position.dist_2d(&target_position) <= settings.attack_radius =>
+                {
+                    MonsterAiState::Attack(target)
+                }
+                Some(_) => MonsterAiState::Chase(target),
+                None => MonsterAiState::Leash,
+            }
+        }
+        MonsterAiState::Leash if position.dist_2d(&home) < 1.0 => {
+            restore_health(world, guid);
+            MonsterAiState::Idle
+        }
+        MonsterAiState::Leash => MonsterAiState::Leash,
+    };
+
+    if next_state != state {
+        debug!("monster {guid:?}: {state:?} -> {next_state:?}");
+    }
+
+    let step = settings.move_speed * delta.clamp(0.0, MAX_TICK_DELTA);
+
+    match next_state {
+        MonsterAiState::Idle => move_towards(world, guid, &position, 0.0, 0.0, delta),
+        MonsterAiState::Chase(target) => {
+            let destination = target_position(world, target).unwrap();
+            move_towards(
+                world,
+                guid,
+                &destination,
+                settings.attack_radius,
+                step,
+                delta,
+            );
+        }
+        MonsterAiState::Attack(target) => {
+            let destination = target_position(world, target).unwrap();
+            move_towards(world, guid, &destination, 0.0, 0.0, delta);
+
+            if now >= next_attack_time {
+                attack(world, guid, target);
+                set_next_attack_time(world, guid, now + settings.attack_interval);
+            }
+        }
+        MonsterAiState::Leash => move_towards(world, guid, &home, 0.0, step, delta),
+    }
+
+    if let Some(mut monster) = world.get_actor_object_mut::<MonsterCharacter>(guid) {
+        monster.data_mut().ai.state = next_state;
+    }
+}
+
+/// Returns the position of a living target, if its client keeps it up to date.
+fn target_position(world: &World, target: FNetworkGUID) -> Option<FVector3d> {
+    ability::is_alive(world, target)
+        .then(|| world.actors.get(&target))
+        .flatten()
+        .filter(|actor| actor.last_client_move_time.is_some())
+        .map(|actor| actor.position.clone())
+}
+
+fn find_nearest_player(world: &World, position: &FVector3d, radius: f64) -> Option<FNetworkGUID> {
+    world
+        .actors
+        .iter()
+        .filter(|(guid, actor)| {
+            world
+                .objects
+                .get(guid)
+                .is_some_and(|object| object.is::<HTPlayerCharacter>())
+                && actor.last_client_move_time.is_some()
+                && ability::is_alive(world, **guid)
+        })
+        .map(|(&guid, actor)| (guid, actor.position.dist_2d(position)))
+        .filter(|&(_, distance)| distance <= radius)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(guid, _)| guid)
+}
+
+/// Turns the monster towards `destination` and moves it by up to `step`,
+/// stopping `stop_distance` away from it.
+fn move_towards(
+    world: &mut World,
+    guid: FNetworkGUID,
+    destination: &FVector3d,
+    stop_distance: f64,
+    step: f64,
+    delta: f64,
+) {
+    let Some(actor) = world.actors.get_mut(&guid) else {
+        return;
+    };
+
+    let distance = actor.position.dist_2d(destination);
+    let travel = (distance - stop_distance).clamp(0.0, step);
+    let mut velocity = FVector3d::default();
+
+    if distance > f64::EPSILON {
+        let (dir_x, dir_y) = (
+            (destination.x - actor.position.x) / distance,
+            (destination.y - actor.position.y) / distance,
+        );
+        let dz = (destination.z - actor.position.z) * travel / distance;
+
+        actor.position.x += dir_x * travel;
+        actor.position.y += dir_y * travel;
+        actor.position.z += dz;
+        actor.rotation = FRotator::new(0.0, dir_y.atan2(dir_x).to_degrees(), 0.0);
+
+        if travel > 0.0 && delta > 0.0 {
+            velocity = FVector3d::new(dir_x * travel / delta, dir_y * travel / delta, dz / delta);
+        }
+    }
+
+    let (position, rotation) = (actor.position.clone(), actor.rotation.clone());
+
+    if let Some(mut monster) = world.get_actor_object_mut::<MonsterCharacter>(guid) {
+        monster
+            .data_mut()
+            .replicated_movement
+            .set_value(position, rotation, velocity);
+    }
+}
+
+fn attack(world: &mut World, guid: FNetworkGUID, target: FNetworkGUID) {
+    let Some(hit) = ability::with_ability_system(world, guid, |_, attributes, _, _| {
+        HitSpec::basic_attack(guid, attributes)
+    }) else {
+        return;
+    };
+
+    match ability::deal_hit(world, target, &hit) {
+        Ok(result) => debug!("monster {guid:?} hit {target:?}: {result:?}"),
+        Err(err) => debug!("monster {guid:?} failed to hit {target:?}: {err}"),
+    }
+}
+
+fn set_next_attack_time(world: &mut World, guid: FNetworkGUID, time: f64) {
+    if let Some(mut monster) = world.get_actor_object_mut::<MonsterCharacter>(guid) {
+        monster.data_mut().ai.next_attack_time = time;
+    }
+}
+
+/// Leashed monsters regenerate, so players can't wear them down by pulling them back and forth.
+fn restore_health(world: &mut World, guid: FNetworkGUID) {
+    ability::with_ability_system(world, guid, |asc, _, _, _| {
+        let max_hp = asc.max_hp.get();
+        asc.hp_current.set_value(max_hp);
+    });
+}
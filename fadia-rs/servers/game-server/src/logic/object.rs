@@ -13,8 +13,21 @@ pub trait ObjectLayout: RepLayout + RpcHandler {
     fn on_channel_open(&self, _channel: &mut ActorChannel, _world: &World) {
         // on_channel_open.
     }
+
+    fn on_channel_tick(&self, _channel: &mut ActorChannel, _world: &World) {
+        // on_channel_tick.
+    }
+
+    /// Layouts which handles are guessed because the class is missing from the dumps.
+    /// Their properties are only sent with `GameplayGlobals::replicate_unverified_layouts`.
+    fn is_unverified(&self) -> bool {
+        false
+    }
 }
 
+/// Sub-objects created along with an actor, spawned on the actor channel.
+pub type SubObjectList = Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>;
+
 pub struct Object {
     pub rep_layout: Box<dyn ObjectLayout>,
     pub sub_objects: HashSet<FNetworkGUID>,
@@ -26,6 +39,10 @@ impl Object {
         self.queued_rpcs.push((index, data));
     }
 
+    pub fn is<T: RepLayout>(&self) -> bool {
+        (self.rep_layout.as_ref() as &dyn Any).is::<T>()
+    }
+
     pub fn layout<T: RepLayout>(&self) -> &T {
         let rep_layout = self.rep_layout.as_ref();
         (rep_layout as &dyn Any)
@@ -69,6 +86,18 @@ impl Object {
 
         self.rep_layout.on_channel_open(channel, world);
     }
+
+    pub fn on_channel_tick(&self, channel: &mut ActorChannel, world: &World) {
+        self.sub_objects.iter().for_each(|guid| {
+            world
+                .objects
+                .get(guid)
+                .unwrap()
+                .on_channel_tick(channel, world);
+        });
+
+        self.rep_layout.on_channel_tick(channel, world);
+    }
 }
 
 pub struct RefObjectWrap<'obj, T> {
@@ -0,0 +1,114 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{cooldown::SavedCooldown, vehicle::SavedVehicle};
+
+const PLAYER_DATA_DIR: &str = "player_data";
+
+/// Progression of a single character.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CharacterProgression {
+    /// Awakening level, every level unlocks the `Upgrade/LevelN` buff of the character.
+    pub upgrade_level: u32,
+    /// Levels of abilities keyed by their blueprint names, e.g. `GA_Fadia_Skill`.
+    pub skill_levels: BTreeMap<String, u32>,
+}
+
+/// Progression of all characters of a player, persisted in `player_data/<unique_id>.toml`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PlayerProgression {
+    pub characters: BTreeMap<String, CharacterProgression>,
+    /// Satiety of the player, new players are full.
+    pub satiety: Option<f32>,
+    /// Inventory of the player, `None` for new players who get the starting items.
+    pub items: Option<Vec<SavedItemStack>>,
+    /// Cooldowns which were active when the player was last saved.
+    pub cooldowns: Vec<SavedCooldown>,
+    /// Vehicles placed by the player, where they were last parked.
+    pub vehicles: Vec<SavedVehicle>,
+    /// Clone IDs of the dungeons the player has cleared.
+    pub cleared_dungeons: BTreeSet<String>,
+    /// Unix times in milliseconds at which dungeon rewards were last received, keyed by clone IDs.
+    pub dungeon_rewards: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedItemStack {
+    pub item_id: String,
+    pub count: u32,
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl CharacterProgression {
+    pub const MAX_UPGRADE_LEVEL: u32 = 6;
+
+    /// Abilities without a saved level are at level 1.
+    pub fn skill_level(&self, ability_name: &str) -> u32 {
+        self.skill_levels.get(ability_name).copied().unwrap_or(1)
+    }
+}
+
+impl PlayerProgression {
+    /// Loads the progression of a player, or creates an empty one if the player is new.
+    pub fn load_or_create(unique_id: &str) -> Self {
+        let path = Self::file_path(unique_id);
+
+        match std::fs::read_to_string(&path) {
+            Ok(data) => toml::from_str(&data).unwrap_or_else(|err| {
+                warn!("failed to parse {}: {err}", path.display());
+                Self::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let progression = Self::default();
+                if let Err(err) = progression.save(unique_id) {
+                    warn!("failed to save {}: {err}", path.display());
+                }
+
+                progression
+            }
+            Err(err) => {
+                warn!("failed to read {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, unique_id: &str) -> io::Result<()> {
+        let data = toml::to_string_pretty(self).map_err(io::Error::other)?;
+
+        std::fs::create_dir_all(PLAYER_DATA_DIR)?;
+        std::fs::write(Self::file_path(unique_id), data)
+    }
+
+    pub fn character(&self, character_name: &str) -> CharacterProgression {
+        self.characters
+            .get(character_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn file_path(unique_id: &str) -> PathBuf {
+        // Unique IDs come from clients, keep only characters which are safe in file names.
+        let file_name = unique_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        PathBuf::from(PLAYER_DATA_DIR).join(format!("{file_name}.toml"))
+    }
+}
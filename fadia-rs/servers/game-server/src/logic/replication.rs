@@ -16,15 +16,18 @@ pub struct InRPC {
     pub data: Box<[u8]>,
 }
 
+/// Writes the properties and queued RPCs of an object, only the RPCs if `properties` is false.
 pub fn serialize_object(
     writer: &mut OutBitWriter,
     guid: FNetworkGUID,
     object: &Object,
     is_actor: bool,
     full: bool,
+    properties: bool,
 ) -> std::io::Result<()> {
-    let has_rep_layout =
-        !object.rep_layout.is_empty() && (full || object.rep_layout.rep_layout_changed());
+    let has_rep_layout = properties
+        && !object.rep_layout.is_empty()
+        && (full || object.rep_layout.rep_layout_changed());
 
     writer.write_bit(has_rep_layout)?;
     writer.write_bit(is_actor)?;
@@ -44,13 +47,13 @@ pub fn serialize_object(
             .serialize_layout_properties(&mut out, full)?;
     }
 
-    for (rep_index, data) in object
-        .rep_layout
-        .serialize_custom_properties(full)
-        .unwrap()
-        .iter()
-        .chain(object.queued_rpcs.iter())
-    {
+    let custom_properties = if properties {
+        object.rep_layout.serialize_custom_properties(full)?
+    } else {
+        Vec::new()
+    };
+
+    for (rep_index, data) in custom_properties.iter().chain(object.queued_rpcs.iter()) {
         let max_rep_index = object.rep_layout.max_rep_index();
         let size_in_bits = util::get_bits_from_terminated_stream(data).unwrap();
         out.write_compressed_int(*rep_index, max_rep_index + 1)?;
@@ -99,10 +102,7 @@ pub fn receive_client_data(
         let size_in_bits = r.read_packed_int()? as usize;
         let data = r.read_bits(size_in_bits)?.into_boxed_slice();
 
-        output.push(InRPC {
-            rep_index,
-            data,
-        });
+        output.push(InRPC { rep_index, data });
     }
 
     Ok(output)
@@ -12,7 +12,6 @@ use super::replication::InRPC;
 
 mod primitives;
 
-#[expect(dead_code)]
 pub struct RpcContext<'world, 'connection> {
     pub actor_guid: FNetworkGUID,
     pub self_guid: FNetworkGUID,
@@ -1,6 +1,12 @@
+use bitstream_io::{BitRead, BitWrite};
 use fadia_engine::{
-    FNetworkGUID,
-    util::{FStringReadExt, FStringWriteExt, PackedBitReadExt, PackedBitWriteExt},
+    FGuid, FNetworkGUID,
+    util::{
+        FStringReadExt, FStringWriteExt, PackedBitReadExt, PackedBitWriteExt, ReadPrimitivesExt,
+        WritePrimitivesExt,
+        quantized::{QuantizedReadExt, QuantizedWriteExt},
+    },
+    vector::FVector3d,
 };
 
 use super::RpcArgument;
@@ -25,3 +31,74 @@ impl RpcArgument for String {
         r.read_string()
     }
 }
+
+impl RpcArgument for u32 {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_u32(*self)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        r.read_u32()
+    }
+}
+
+impl RpcArgument for i32 {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_i32(*self)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        Ok(r.read_u32()? as i32)
+    }
+}
+
+impl RpcArgument for i16 {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_i16(*self)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        Ok(r.read_u16()? as i16)
+    }
+}
+
+impl RpcArgument for f32 {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_f32(*self)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        r.read_f32()
+    }
+}
+
+/// Serialized as `FVector_NetQuantize`, rounded to whole units.
+impl RpcArgument for FVector3d {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_packed_vector(self, 1)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        r.read_packed_vector(1)
+    }
+}
+
+impl RpcArgument for bool {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        w.write_bit(*self)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        r.read_bit()
+    }
+}
+
+impl RpcArgument for FGuid {
+    fn serialize(&self, w: &mut fadia_engine::util::OutBitWriter) -> std::io::Result<()> {
+        self.net_serialize(w)
+    }
+
+    fn deserialize(r: &mut fadia_engine::util::InBitReader) -> std::io::Result<Self> {
+        Self::net_deserialize(r)
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
+use tracing::info;
+
 use crate::{
     assets::GameAssets,
-    config::GameplayGlobals,
-    net::{ConnectParams, NetConnection, SessionID, World},
+    config::{GameplayGlobals, MapSettings},
+    net::{ConnectParams, NetConnection, PacketHandler, SessionID, World},
 };
 
-use super::{actor::NetPlayerIndex, mode::HTGameMode};
+use super::{actor::NetPlayerIndex, dungeon, mode::HTGameMode, travel::TravelDestination};
 
 #[derive(Default)]
 pub struct LogicScopeManager {
@@ -18,8 +20,17 @@ pub struct LogicScopeManager {
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub struct ScopeID(u64);
 
+/// A world of a scope, by the name of its map or the clone ID and instance ID of its dungeon.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum WorldID {
+    Map(&'static str),
+    Dungeon(&'static str, u32),
+}
+
 pub struct LogicScope {
-    pub world: World,
+    /// Worlds are created once a player enters them,
+    /// dungeon instances are dropped once every player has left.
+    pub worlds: HashMap<WorldID, World>,
     pub connections: HashMap<SessionID, NetConnection>,
 }
 
@@ -28,25 +39,29 @@ impl LogicScopeManager {
         &mut self,
         params: ConnectParams,
         globals: &'static GameplayGlobals,
+        default_map: &'static MapSettings,
         assets: &'static GameAssets,
     ) -> ScopeID {
         self.scope_counter += 1;
         let scope_id = ScopeID(self.scope_counter);
 
-        let world = World::new::<HTGameMode>(assets, globals);
+        let world = World::new::<HTGameMode>(assets, globals, default_map);
 
         let connection = NetConnection::new(
             params.session_id,
             NetPlayerIndex(0),
             (params.server_seq, params.client_seq),
             params.output,
+            WorldID::Map(default_map.name()),
+            PacketHandler::new(globals.packet_encryption),
+            params.version,
         );
 
         self.session_scopes.insert(params.session_id, scope_id);
         self.scopes.insert(
             scope_id,
             LogicScope {
-                world,
+                worlds: HashMap::from([(WorldID::Map(default_map.name()), world)]),
                 connections: HashMap::from([(params.session_id, connection)]),
             },
         );
@@ -54,9 +69,93 @@ impl LogicScopeManager {
         scope_id
     }
 
+    /// Drops the connections closed while handling their packets, along with their empty scopes.
+    pub fn drop_closed_connections(&mut self) {
+        for scope in self.scopes.values_mut() {
+            scope.connections.retain(|session_id, connection| {
+                if !connection.closed {
+                    return true;
+                }
+
+                info!("dropping closed connection {session_id:?}");
+                let world = scope.worlds.get_mut(&connection.world).unwrap();
+                world.remove_player(connection);
+                self.session_scopes.remove(session_id);
+                false
+            });
+        }
+
+        self.scopes.retain(|_, scope| !scope.connections.is_empty());
+    }
+
     pub fn get_scope_for_session(&mut self, session_id: SessionID) -> Option<&mut LogicScope> {
         self.session_scopes
             .get(&session_id)
             .and_then(|scope_id| self.scopes.get_mut(scope_id))
     }
 }
+
+impl LogicScope {
+    /// Looks up a connection along with the world it's in.
+    pub fn connection_world(
+        &mut self,
+        session_id: SessionID,
+    ) -> Option<(&mut World, &mut NetConnection)> {
+        let connection = self.connections.get_mut(&session_id)?;
+        let world = self.worlds.get_mut(&connection.world)?;
+
+        Some((world, connection))
+    }
+
+    /// Moves connections with a pending travel into the world of their destination
+    /// and welcomes them there, the client loads the map and joins the world then.
+    /// Players of finished dungeons are sent back first, and empty instances are dropped.
+    pub fn process_travels(&mut self) {
+        for connection in self.connections.values_mut() {
+            let world = self.worlds.get_mut(&connection.world).unwrap();
+            dungeon::return_finished_player(world, connection);
+        }
+
+        for connection in self.connections.values_mut() {
+            let Some(destination) = connection.pending_travel.take() else {
+                continue;
+            };
+
+            let previous_world = &self.worlds[&connection.world];
+            let (assets, globals, previous_map) = (
+                previous_world.assets,
+                previous_world.globals,
+                previous_world.map,
+            );
+
+            let world_id = destination.world_id();
+            info!(
+                "moving {:?} from {:?} to {world_id:?}",
+                connection.session_id, connection.world
+            );
+
+            connection.world = world_id;
+
+            self.worlds
+                .entry(world_id)
+                .or_insert_with(|| match destination {
+                    TravelDestination::Map(map) => World::new::<HTGameMode>(assets, globals, map),
+                    TravelDestination::Dungeon(settings, _) => {
+                        let map = globals.find_map(&settings.map).unwrap();
+                        let mut world = World::new::<HTGameMode>(assets, globals, map);
+                        dungeon::start_dungeon(&mut world, settings, previous_map);
+                        world
+                    }
+                })
+                .welcome_player(connection);
+        }
+
+        let connections = &self.connections;
+        self.worlds.retain(|world_id, _| {
+            matches!(world_id, WorldID::Map(_))
+                || connections
+                    .values()
+                    .any(|connection| connection.world == *world_id)
+        });
+    }
+}
@@ -0,0 +1,155 @@
+use common::time_util;
+use fadia_engine::{FNetworkGUID, util::FName};
+
+use crate::net::World;
+
+use super::{
+    hotta::{
+        HottaReplicatedObjectProperty, HottaReplicatedObjectPropertyContainer,
+        HottaReplicatedProperty, player_state::HottaPlayerState,
+    },
+    layout::PlayerState,
+    rpc::call_rpcs,
+};
+
+/// Cap of `PlayerState::current_stamina`.
+pub const MAX_STAMINA: u32 = 200;
+/// Cap of `PlayerState::strength_current`.
+pub const MAX_STRENGTH: f32 = 100.0;
+/// Recovered stamina which doesn't fit under `MAX_STAMINA` is kept as backup stamina, up to this value.
+pub const MAX_BAK_STAMINA: u32 = 1000;
+
+/// Periodic recovery of stamina and strength of a player, configured by
+/// `BakStaminaAutoRecoveryInterval` and `BakStaminaAutoRecoveryValue` of `HottaPlayerState`.
+#[derive(Debug)]
+pub struct StaminaRecovery {
+    pub current_bak_stamina: u32,
+    /// Unix time of the last recovery, in seconds.
+    pub last_recovery_time: u64,
+    /// Seconds between two recoveries.
+    pub interval: u32,
+    /// Stamina and strength restored by every recovery.
+    pub value: u32,
+}
+
+impl Default for StaminaRecovery {
+    fn default() -> Self {
+        Self {
+            current_bak_stamina: 0,
+            last_recovery_time: time_util::unix_time().as_secs(),
+            interval: 60,
+            value: 10,
+        }
+    }
+}
+
+impl StaminaRecovery {
+    /// Copies the recovery state to the player state data sent to the client.
+    pub fn fill_hotta_player_state(&self, state: &mut HottaPlayerState) {
+        state.current_bak_stamina = self.current_bak_stamina;
+        state.last_recovery_bak_stamina_time = self.last_recovery_time;
+        state.bak_stamina_auto_recovery_value = self.value;
+        state.bak_stamina_auto_recovery_interval = self.interval;
+    }
+
+    /// Only the changed part of `HottaPlayerState`, sent after every recovery.
+    fn replicate_bak_stamina(&self, owner: FNetworkGUID) -> HottaReplicatedObjectPropertyContainer {
+        let property = |name: &str, datas| HottaReplicatedObjectProperty {
+            name: FName::Custom(String::from(name)),
+            datas,
+        };
+
+        HottaReplicatedObjectPropertyContainer {
+            owner,
+            properties: vec![
+                property("CurrentBakStamina", self.current_bak_stamina.replicate()),
+                property(
+                    "LastRecoveryBakStaminaTime",
+                    self.last_recovery_time.replicate(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Recovers stamina and strength of every player whose recovery interval has passed.
+pub fn tick_stamina_recovery(world: &mut World) {
+    let now = world.clock.unix_time().as_secs();
+
+    for guid in player_states(world) {
+        let Some(mut player_state) = world.get_actor_object_mut::<PlayerState>(guid) else {
+            continue;
+        };
+
+        let Some(container) = recover(player_state.data_mut(), guid, now) else {
+            continue;
+        };
+
+        if let Some(player_state) = world.get_actor_object_mut::<PlayerState>(guid) {
+            call_rpcs!(player_state.send_replicated_object_property_array_to_client(container));
+        }
+    }
+}
+
+/// Fills stamina and strength of every player, done by the daily reset.
+pub fn refill_stamina(world: &mut World) {
+    for guid in player_states(world) {
+        if let Some(mut player_state) = world.get_actor_object_mut::<PlayerState>(guid) {
+            let state = player_state.data_mut();
+            state.current_stamina.set_value(MAX_STAMINA);
+            state.strength_current.set_value(MAX_STRENGTH);
+        }
+    }
+}
+
+fn player_states(world: &World) -> Vec<FNetworkGUID> {
+    world
+        .actors
+        .keys()
+        .copied()
+        .filter(|guid| {
+            world
+                .objects
+                .get(guid)
+                .is_some_and(|object| object.is::<PlayerState>())
+        })
+        .collect()
+}
+
+/// Applies the recoveries which are due. Returns the backup stamina update
+/// for the client if the backup stamina changed.
+fn recover(
+    state: &mut PlayerState,
+    guid: FNetworkGUID,
+    now: u64,
+) -> Option<HottaReplicatedObjectPropertyContainer> {
+    let recovery = &mut state.stamina_recovery;
+    let interval = u64::from(recovery.interval.max(1));
+    let recoveries = now.saturating_sub(recovery.last_recovery_time) / interval;
+
+    if recoveries == 0 {
+        return None;
+    }
+
+    recovery.last_recovery_time += recoveries * interval;
+    let amount = u32::try_from(recoveries)
+        .unwrap_or(u32::MAX)
+        .saturating_mul(recovery.value);
+
+    let stamina = state.current_stamina.get();
+    let recovered_stamina = amount.min(MAX_STAMINA.saturating_sub(stamina));
+    state.current_stamina.set_value(stamina + recovered_stamina);
+
+    let bak_stamina = recovery.current_bak_stamina;
+    recovery.current_bak_stamina = bak_stamina
+        .saturating_add(amount - recovered_stamina)
+        .min(MAX_BAK_STAMINA);
+    let bak_stamina_changed = recovery.current_bak_stamina != bak_stamina;
+
+    let strength = state.strength_current.get();
+    state
+        .strength_current
+        .set_value((strength + amount as f32).min(MAX_STRENGTH));
+
+    bak_stamina_changed.then(|| state.stamina_recovery.replicate_bak_stamina(guid))
+}
@@ -16,7 +16,7 @@ use crate::{
     net::{ClassHierarchy, World},
 };
 
-use super::{ObjectLayout, actor::NetRole};
+use super::{ObjectLayout, SubObjectList, actor::NetRole};
 
 pub trait GameStateBase {
     fn received_game_mode_class(&mut self, guid: FNetworkGUID);
@@ -59,11 +59,7 @@ impl HTGameState {
         world: &mut World,
         remote_role: NetRole,
         role: NetRole,
-    ) -> (
-        FNetworkGUID,
-        Self,
-        Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
-    ) {
+    ) -> (FNetworkGUID, Self, SubObjectList) {
         let own_guid = world
             .net_guid_cache
             .assign_new_net_guid_for_dynamic_object(None);
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use bitstream_io::{BitRead, BitWrite};
+use fadia_engine::{
+    FGuid,
+    util::{InBitReader, OutBitWriter},
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use tracing::{error, info};
+
+use crate::{
+    config::{DungeonSettings, GameplayGlobals, MapSettings},
+    net::{NetConnection, World},
+};
+
+use super::{
+    layout::PlayerControllerBase,
+    mode::LoginUrl,
+    rpc::{RpcArgument, call_rpcs},
+    scope::WorldID,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TravelError {
+    #[error("map {0} doesn't exist")]
+    UnknownMap(String),
+    #[error("player is already on {0}")]
+    SameMap(&'static str),
+    #[error("player hasn't joined the world")]
+    NotJoined,
+}
+
+/// Where a connection moves to, see `LogicScope::process_travels`.
+#[derive(Clone, Copy)]
+pub enum TravelDestination {
+    Map(&'static MapSettings),
+    /// An instance of a dungeon by its instance ID, it's created if nobody is in it yet.
+    Dungeon(&'static DungeonSettings, u32),
+}
+
+impl TravelDestination {
+    pub fn world_id(&self) -> WorldID {
+        match self {
+            Self::Map(map) => WorldID::Map(map.name()),
+            Self::Dungeon(dungeon, instance_id) => WorldID::Dungeon(&dungeon.id, *instance_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum ETravelType {
+    Absolute = 0,
+    Partial,
+    Relative,
+}
+
+impl ETravelType {
+    /// `FMath::CeilLogTwo(ETravelType::TRAVEL_MAX)`
+    const NET_SERIALIZE_BITS: u32 = 2;
+}
+
+impl RpcArgument for ETravelType {
+    fn serialize(&self, w: &mut OutBitWriter) -> std::io::Result<()> {
+        w.write(Self::NET_SERIALIZE_BITS, u8::from(*self))
+    }
+
+    fn deserialize(r: &mut InBitReader) -> std::io::Result<Self> {
+        let value = r.read::<u8>(Self::NET_SERIALIZE_BITS)?;
+
+        Self::try_from(value).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid travel type: {value}"),
+            )
+        })
+    }
+}
+
+/// Looks up the map asked for by the URL of `NMT_Login`, see `LoginUrl::map`.
+/// `None` if the URL has no map or the map isn't registered.
+pub fn requested_map(
+    globals: &'static GameplayGlobals,
+    login_url: &LoginUrl,
+) -> Option<&'static MapSettings> {
+    let path = login_url.map.as_str();
+    (!path.is_empty()).then(|| globals.find_map(path)).flatten()
+}
+
+/// Sends a player to another map.
+///
+/// Seamless travel keeps the connection: the player leaves the world and is welcomed
+/// by the world of the new map. Otherwise the client is told to `ClientTravel` there,
+/// it reconnects and its login URL leads it to the new world. Its session ticket was consumed
+/// at login, so it gets a new one whose token the travel URL carries as `EncryptionToken`.
+/// Either way the player is restored from the player profile once it joins.
+pub fn server_travel(
+    world: &mut World,
+    connection: &mut NetConnection,
+    map_name: &str,
+    seamless: bool,
+) -> Result<(), TravelError> {
+    let globals = world.globals;
+    let map = globals
+        .find_map(map_name)
+        .ok_or_else(|| TravelError::UnknownMap(map_name.to_string()))?;
+
+    if map.name() == world.map.name() {
+        return Err(TravelError::SameMap(map.name()));
+    }
+
+    let controller_guid = connection.player_controller.ok_or(TravelError::NotJoined)?;
+
+    info!(
+        "{:?} travels from {} to {}, seamless: {seamless}",
+        connection.session_id,
+        world.map.name(),
+        map.name()
+    );
+
+    if seamless {
+        world.remove_player(connection);
+        connection.pending_travel = Some(TravelDestination::Map(map));
+    } else if let Some(player_controller) =
+        world.get_actor_object_mut::<PlayerControllerBase>(controller_guid)
+    {
+        let mut url = map.path.clone();
+        if let Some(ticket) = &connection.session_ticket {
+            let lifetime = Duration::from_secs(globals.session_ticket_lifetime);
+            match ticket.renew(&globals.session_ticket_dir, lifetime) {
+                Ok(ticket) => url.push_str(&format!("?EncryptionToken={}", ticket.token)),
+                Err(err) => error!("failed to renew session ticket for the travel: {err}"),
+            }
+        }
+
+        call_rpcs!(player_controller.client_travel_internal(
+            url,
+            ETravelType::Relative,
+            false,
+            FGuid::default()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,463 @@
+use fadia_engine::{FNetworkGUID, rotator::FRotator, vector::FVector3d};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::net::{NetConnection, SpawnActorParams, World};
+
+use super::{
+    actor::NetRole,
+    hotta::player_state::{PlacementVehicleData, Transform},
+    layout::{HTPlayerCharacter, PlayerControllerBase, PlayerState, VehicleComponent, VehiclePawn},
+    npc::FIRST_MONSTER_CHANNEL_INDEX,
+    progression::PlayerProgression,
+    rpc::call_rpcs,
+};
+
+/// Channels of vehicles are opened after the ones of the player, before the monsters.
+pub const FIRST_VEHICLE_CHANNEL_INDEX: u32 = 16;
+/// Actors can't be despawned, so a player can place at most as many vehicles as there are channels left.
+const MAX_PLACED_VEHICLES: usize =
+    (FIRST_MONSTER_CHANNEL_INDEX - FIRST_VEHICLE_CHANNEL_INDEX) as usize;
+/// Drivers may move slightly faster than the max speed of the vehicle, to tolerate network jitter.
+const SPEED_TOLERANCE: f64 = 1.2;
+
+#[derive(thiserror::Error, Debug)]
+pub enum VehicleError {
+    #[error("player doesn't have a vehicle component")]
+    NoVehicleComponent,
+    #[error("vehicle {0} doesn't exist")]
+    UnknownVehicle(String),
+    #[error("player can't place more than {MAX_PLACED_VEHICLES} vehicles")]
+    TooManyVehicles,
+    #[error("vehicle {0:?} isn't placed by the player")]
+    NotPlaced(FNetworkGUID),
+    #[error("player is already driving a vehicle")]
+    AlreadyMounted,
+    #[error("player isn't driving a vehicle")]
+    NotMounted,
+    #[error("vehicle moved at {0:.0} units per second, faster than its max speed")]
+    TooFast(f64),
+}
+
+/// A vehicle saved in the player profile.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedVehicle {
+    pub vehicle_id: String,
+    /// Name of the map the vehicle is placed on. Vehicles saved without one
+    /// are placed on the first map the player enters.
+    #[serde(default)]
+    pub map: String,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    #[serde(default)]
+    pub yaw: f64,
+}
+
+/// Runs `f` on the vehicle component of a player.
+fn with_vehicle_component<R>(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    f: impl FnOnce(&mut VehicleComponent) -> R,
+) -> Result<R, VehicleError> {
+    let vehicle_component_guid = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .ok_or(VehicleError::NoVehicleComponent)?
+        .data()
+        .vehicle_component;
+
+    let mut vehicle_component = world
+        .get_object_mut::<VehicleComponent>(vehicle_component_guid)
+        .ok_or(VehicleError::NoVehicleComponent)?;
+
+    Ok(f(vehicle_component.data_mut()))
+}
+
+/// Places the vehicles saved in the player profile on the map of the world back into it.
+pub fn init_vehicles(
+    world: &mut World,
+    connection: &mut NetConnection,
+    player_state_guid: FNetworkGUID,
+    saved: &[SavedVehicle],
+) {
+    let map_name = world.map.name();
+
+    for vehicle in saved
+        .iter()
+        .filter(|vehicle| vehicle.map.is_empty() || vehicle.map == map_name)
+    {
+        let location = FVector3d::new(vehicle.x, vehicle.y, vehicle.z);
+
+        if let Err(err) = spawn_vehicle(
+            world,
+            connection,
+            player_state_guid,
+            &vehicle.vehicle_id,
+            location,
+            vehicle.yaw,
+        ) {
+            warn!("failed to restore vehicle {}: {err}", vehicle.vehicle_id);
+        }
+    }
+}
+
+/// Places a vehicle at `location`. A vehicle which is already placed is moved there instead,
+/// unless the player is driving it.
+pub fn place_vehicle(
+    world: &mut World,
+    connection: &mut NetConnection,
+    player_state_guid: FNetworkGUID,
+    vehicle_id: &str,
+    location: FVector3d,
+    yaw: f64,
+) -> Result<FNetworkGUID, VehicleError> {
+    let (placed, current_vehicle) =
+        with_vehicle_component(world, player_state_guid, |vehicle_component| {
+            (
+                vehicle_component.placed_vehicles.get(vehicle_id).copied(),
+                vehicle_component.current_vehicle.get(),
+            )
+        })?;
+
+    let guid = match placed {
+        Some(guid) if guid == current_vehicle => return Err(VehicleError::AlreadyMounted),
+        Some(guid) => {
+            set_vehicle_movement(world, guid, location, yaw, FVector3d::default());
+            guid
+        }
+        None => spawn_vehicle(
+            world,
+            connection,
+            player_state_guid,
+            vehicle_id,
+            location,
+            yaw,
+        )?,
+    };
+
+    save_vehicles(world, player_state_guid);
+    Ok(guid)
+}
+
+/// Possesses a placed vehicle with the controller of the player, the character is left behind.
+pub fn mount_vehicle(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+    vehicle_guid: FNetworkGUID,
+) -> Result<(), VehicleError> {
+    let (placed, current_vehicle) =
+        with_vehicle_component(world, player_state_guid, |vehicle_component| {
+            (
+                vehicle_component
+                    .placed_vehicles
+                    .values()
+                    .any(|&guid| guid == vehicle_guid),
+                vehicle_component.current_vehicle.get(),
+            )
+        })?;
+
+    if !placed {
+        return Err(VehicleError::NotPlaced(vehicle_guid));
+    }
+
+    if current_vehicle.is_valid() {
+        return Err(VehicleError::AlreadyMounted);
+    }
+
+    let (controller_guid, character_guid) = player_pawns(world, player_state_guid)?;
+    let now = world.time_seconds();
+
+    if let Some(mut vehicle) = world.get_actor_object_mut::<VehiclePawn>(vehicle_guid) {
+        let vehicle = vehicle.data_mut();
+        vehicle.remote_role.set_value(NetRole::AutonomousProxy);
+        vehicle.owner.set_value(controller_guid);
+        vehicle.controller.set_value(controller_guid);
+        vehicle.player_state.set_value(player_state_guid);
+        // Movement is measured from the moment the driver got in.
+        vehicle.last_move_time = Some(now);
+    }
+
+    if let Some(mut character) = world.get_actor_object_mut::<HTPlayerCharacter>(character_guid) {
+        character
+            .data_mut()
+            .controller
+            .set_value(FNetworkGUID::default());
+    }
+
+    with_vehicle_component(world, player_state_guid, |vehicle_component| {
+        vehicle_component.current_vehicle.set_value(vehicle_guid);
+    })?;
+
+    restart_client(world, controller_guid, vehicle_guid);
+    Ok(())
+}
+
+/// Gives the controller of the player back to the character, the vehicle stays where it was driven.
+pub fn dismount_vehicle(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+) -> Result<(), VehicleError> {
+    let vehicle_guid = with_vehicle_component(world, player_state_guid, |vehicle_component| {
+        vehicle_component.current_vehicle.get()
+    })?;
+
+    if !vehicle_guid.is_valid() {
+        return Err(VehicleError::NotMounted);
+    }
+
+    let (controller_guid, character_guid) = player_pawns(world, player_state_guid)?;
+
+    if let Some(mut vehicle) = world.get_actor_object_mut::<VehiclePawn>(vehicle_guid) {
+        let vehicle = vehicle.data_mut();
+        vehicle.remote_role.set_value(NetRole::SimulatedProxy);
+        vehicle.controller.set_value(FNetworkGUID::default());
+        vehicle.player_state.set_value(FNetworkGUID::default());
+        vehicle.last_move_time = None;
+
+        let location = vehicle.replicated_movement.location().clone();
+        let rotation = vehicle.replicated_movement.rotation().clone();
+        vehicle
+            .replicated_movement
+            .set_value(location, rotation, FVector3d::default());
+    }
+
+    if let Some(mut character) = world.get_actor_object_mut::<HTPlayerCharacter>(character_guid) {
+        character.data_mut().controller.set_value(controller_guid);
+    }
+
+    with_vehicle_component(world, player_state_guid, |vehicle_component| {
+        vehicle_component
+            .current_vehicle
+            .set_value(FNetworkGUID::default());
+    })?;
+
+    restart_client(world, controller_guid, character_guid);
+    save_vehicles(world, player_state_guid);
+    Ok(())
+}
+
+/// Applies a movement update sent by the driver of a vehicle.
+/// Updates which would move the vehicle faster than its max speed are rejected.
+pub fn move_vehicle(
+    world: &mut World,
+    vehicle_guid: FNetworkGUID,
+    location: FVector3d,
+    yaw: f64,
+    velocity: FVector3d,
+) -> Result<(), VehicleError> {
+    let now = world.time_seconds();
+    let position = world
+        .actors
+        .get(&vehicle_guid)
+        .map(|actor| actor.position.clone())
+        .ok_or(VehicleError::NotPlaced(vehicle_guid))?;
+
+    let mut vehicle = world
+        .get_actor_object_mut::<VehiclePawn>(vehicle_guid)
+        .ok_or(VehicleError::NotPlaced(vehicle_guid))?;
+
+    let vehicle = vehicle.data_mut();
+    let last_move_time = vehicle.last_move_time.ok_or(VehicleError::NotMounted)?;
+
+    let elapsed = now - last_move_time;
+    let distance = position.dist_2d(&location).hypot(location.z - position.z);
+    let max_distance = vehicle.max_speed * SPEED_TOLERANCE * elapsed;
+
+    if distance > max_distance {
+        return Err(VehicleError::TooFast(distance / elapsed.max(f64::EPSILON)));
+    }
+
+    vehicle.last_move_time = Some(now);
+    set_vehicle_movement(world, vehicle_guid, location, yaw, velocity);
+    Ok(())
+}
+
+/// Vehicles placed by a player in the form of `HottaPlayerState::placement_vehicle_data`.
+pub fn placement_vehicle_data(
+    world: &mut World,
+    player_state_guid: FNetworkGUID,
+) -> Vec<PlacementVehicleData> {
+    let Ok(placed_vehicles) =
+        with_vehicle_component(world, player_state_guid, |vehicle_component| {
+            vehicle_component.placed_vehicles.clone()
+        })
+    else {
+        return Vec::new();
+    };
+
+    placed_vehicles
+        .into_iter()
+        .filter_map(|(vehicle_id, guid)| {
+            let actor = world.actors.get(&guid)?;
+
+            Some(PlacementVehicleData {
+                vehicle_id,
+                transform: Transform {
+                    position: actor.position.clone(),
+                    rotation: FVector3d::new(0.0, actor.rotation.yaw(), 0.0),
+                    ..Default::default()
+                },
+            })
+        })
+        .collect()
+}
+
+/// Spawns a vehicle actor owned by the player and opens its channel.
+fn spawn_vehicle(
+    world: &mut World,
+    connection: &mut NetConnection,
+    player_state_guid: FNetworkGUID,
+    vehicle_id: &str,
+    location: FVector3d,
+    yaw: f64,
+) -> Result<FNetworkGUID, VehicleError> {
+    let placed_count = with_vehicle_component(world, player_state_guid, |vehicle_component| {
+        vehicle_component.placed_vehicles.len()
+    })?;
+
+    if placed_count >= MAX_PLACED_VEHICLES {
+        return Err(VehicleError::TooManyVehicles);
+    }
+
+    let unknown_vehicle = || VehicleError::UnknownVehicle(vehicle_id.to_string());
+    let data = world
+        .assets
+        .get_vehicle_data(vehicle_id)
+        .ok_or_else(unknown_vehicle)?;
+    let (package, _) = data
+        .vehicle_class
+        .package_and_index()
+        .ok_or_else(unknown_vehicle)?;
+
+    let archetype =
+        world.register_hierarchy_for_static_objects(&[package, &data.default_object_name]);
+
+    let (guid, vehicle) = VehiclePawn::new(world, data.max_speed, location.clone(), yaw);
+    let guid = world.spawn_actor(
+        SpawnActorParams::Dynamic {
+            guid,
+            pos: location,
+            rot: FRotator::new(0.0, yaw, 0.0),
+            archetype,
+            archetype_rep: Box::new(vehicle),
+        },
+        Vec::new(),
+    );
+
+    with_vehicle_component(world, player_state_guid, |vehicle_component| {
+        vehicle_component
+            .placed_vehicles
+            .insert(vehicle_id.to_string(), guid);
+    })?;
+
+    world.open_actor_channel_at(
+        connection,
+        FIRST_VEHICLE_CHANNEL_INDEX + placed_count as u32,
+        guid,
+    );
+
+    Ok(guid)
+}
+
+fn set_vehicle_movement(
+    world: &mut World,
+    vehicle_guid: FNetworkGUID,
+    location: FVector3d,
+    yaw: f64,
+    velocity: FVector3d,
+) {
+    let rotation = FRotator::new(0.0, yaw, 0.0);
+    let now = world.time_seconds();
+
+    if let Some(actor) = world.actors.get_mut(&vehicle_guid) {
+        actor.position = location.clone();
+        actor.rotation = rotation.clone();
+        actor.last_client_move_time = Some(now);
+    }
+
+    if let Some(mut vehicle) = world.get_actor_object_mut::<VehiclePawn>(vehicle_guid) {
+        vehicle
+            .data_mut()
+            .replicated_movement
+            .set_value(location, rotation, velocity);
+    }
+}
+
+/// Returns the controller and the current character of a player.
+fn player_pawns(
+    world: &World,
+    player_state_guid: FNetworkGUID,
+) -> Result<(FNetworkGUID, FNetworkGUID), VehicleError> {
+    let state = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .ok_or(VehicleError::NoVehicleComponent)?;
+
+    let controller_guid = state.data().owner.get();
+    let character_guid = state
+        .data()
+        .equipped_players
+        .get(0)
+        .map(|character| character.get())
+        .unwrap_or_default();
+
+    Ok((controller_guid, character_guid))
+}
+
+/// Makes the client of the player take control of `pawn_guid`.
+fn restart_client(world: &mut World, controller_guid: FNetworkGUID, pawn_guid: FNetworkGUID) {
+    if let Some(player_controller) =
+        world.get_actor_object_mut::<PlayerControllerBase>(controller_guid)
+    {
+        call_rpcs!(player_controller.client_retry_client_restart(pawn_guid));
+    }
+}
+
+/// Writes the vehicles placed by a player to the player profile.
+/// Vehicles left on other maps are kept, unless they were placed here since.
+fn save_vehicles(world: &mut World, player_state_guid: FNetworkGUID) {
+    let Some(unique_id) = world
+        .get_actor_object::<PlayerState>(player_state_guid)
+        .map(|state| state.data().unique_id.device.get().to_string())
+        .filter(|unique_id| !unique_id.is_empty())
+    else {
+        return;
+    };
+
+    let Ok(placed_vehicles) =
+        with_vehicle_component(world, player_state_guid, |vehicle_component| {
+            vehicle_component.placed_vehicles.clone()
+        })
+    else {
+        return;
+    };
+
+    let vehicles = placed_vehicles
+        .into_iter()
+        .filter_map(|(vehicle_id, guid)| {
+            let actor = world.actors.get(&guid)?;
+
+            Some(SavedVehicle {
+                vehicle_id,
+                map: world.map.name().to_string(),
+                x: actor.position.x,
+                y: actor.position.y,
+                z: actor.position.z,
+                yaw: actor.rotation.yaw(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut progression = PlayerProgression::load_or_create(&unique_id);
+    progression.vehicles.retain(|saved| {
+        !saved.map.is_empty()
+            && saved.map != world.map.name()
+            && !vehicles
+                .iter()
+                .any(|vehicle| vehicle.vehicle_id == saved.vehicle_id)
+    });
+    progression.vehicles.extend(vehicles);
+
+    if let Err(err) = progression.save(&unique_id) {
+        warn!("failed to save vehicles of {unique_id}: {err}");
+    }
+}
@@ -0,0 +1,353 @@
+use std::sync::Mutex;
+
+use fadia_engine::{FNetworkGUID, rotator::FRotator, vector::FVector3d};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use tracing::info;
+
+use crate::{
+    config::{WeatherPreset, WeatherSettings},
+    net::{SpawnActorParams, World},
+};
+
+use super::{actor::NetRole, layout::UltraDynamicWeather};
+
+const WEATHER_CLASS: &[&str] = &[
+    "/Game/UltraDynamicSky/Blueprints/Ultra_Dynamic_Weather",
+    "Default__Ultra_Dynamic_Weather_C",
+];
+
+const SECONDS_PER_DAY: u64 = 24 * 3600;
+const SEASON_COUNT: f64 = 4.0;
+/// Interval at which the weather actor is updated, transitions don't need to replicate every tick.
+const UPDATE_INTERVAL: f64 = 1.0;
+
+/// Preset shown instead of the scheduled weather in every world, index in `WeatherSettings::presets`.
+static FORCED_PRESET: Mutex<Option<usize>> = Mutex::new(None);
+
+#[derive(thiserror::Error, Debug)]
+#[error("weather preset {0} doesn't exist")]
+pub struct UnknownPresetError(pub String);
+
+/// Picks the weather of the world from `WeatherSettings::presets`.
+///
+/// The weather is derived from the server clock rather than kept as state, and forced presets
+/// are global, so every world, and every client in it, sees the same sky.
+pub struct WeatherScheduler {
+    settings: &'static WeatherSettings,
+    /// Forced preset as of the last update, the weather is updated right away when it changes.
+    forced: Option<usize>,
+    next_update_time: f64,
+}
+
+/// Weather parameters at one moment, blended between two presets during transitions.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherState {
+    pub preset_index: u32,
+    pub cloud_coverage: f64,
+    pub fog: f64,
+    pub wind_intensity: f64,
+    pub rain: f64,
+    pub snow: f64,
+    pub thunder_lightning: f64,
+    pub transitioning: bool,
+    /// 0 is the start of spring, 3 the start of winter.
+    pub season: f64,
+}
+
+impl WeatherScheduler {
+    pub fn new(settings: &'static WeatherSettings) -> Self {
+        Self {
+            settings,
+            forced: None,
+            next_update_time: 0.0,
+        }
+    }
+
+    /// Weather at `now`, in seconds of server time. `None` if the weather table is empty.
+    pub fn weather_at(&self, now: u64) -> Option<WeatherState> {
+        let settings = self.settings;
+        let season = season_at(settings, now);
+
+        if let Some(index) = self.forced {
+            return Some(WeatherState::new(&settings.presets[index], season));
+        }
+
+        let interval = settings.change_interval.max(1);
+        let slot = now / interval;
+        let current = self.scheduled_preset(slot)?;
+        let mut state = WeatherState::new(current, season);
+
+        let elapsed = now - slot * interval;
+        if elapsed < settings.transition_time
+            && let Some(previous) = slot
+                .checked_sub(1)
+                .and_then(|slot| self.scheduled_preset(slot))
+        {
+            let alpha = elapsed as f64 / settings.transition_time as f64;
+            state = WeatherState::new(previous, season).blend(&state, smooth_step(alpha));
+        }
+
+        Some(state)
+    }
+
+    /// Picks the preset of a change interval by weight, among the presets of its season.
+    /// The interval seeds the pick, so it's the same whenever it's asked for.
+    fn scheduled_preset(&self, slot: u64) -> Option<&'static WeatherPreset> {
+        let settings = self.settings;
+        let season = season_at(settings, slot * settings.change_interval.max(1)) as u32;
+
+        let presets = settings
+            .presets
+            .iter()
+            .filter(|preset| preset.seasons.is_empty() || preset.seasons.contains(&season))
+            .filter(|preset| preset.weight > 0.0)
+            .collect::<Vec<_>>();
+
+        let total_weight = presets.iter().map(|preset| preset.weight).sum::<f32>();
+        let mut roll = StdRng::seed_from_u64(slot).random::<f32>() * total_weight;
+
+        presets
+            .iter()
+            .find(|preset| {
+                roll -= preset.weight;
+                roll < 0.0
+            })
+            .or(presets.last())
+            .copied()
+    }
+}
+
+impl WeatherState {
+    fn new(preset: &WeatherPreset, season: f64) -> Self {
+        Self {
+            preset_index: preset.index,
+            cloud_coverage: preset.cloud_coverage,
+            fog: preset.fog,
+            wind_intensity: preset.wind_intensity,
+            rain: preset.rain,
+            snow: preset.snow,
+            thunder_lightning: preset.thunder_lightning,
+            transitioning: false,
+            season,
+        }
+    }
+
+    /// Moves from `self` to `target` by `alpha`, from 0 to 1.
+    fn blend(&self, target: &Self, alpha: f64) -> Self {
+        let lerp = |from: f64, to: f64| from + (to - from) * alpha;
+
+        Self {
+            preset_index: target.preset_index,
+            cloud_coverage: lerp(self.cloud_coverage, target.cloud_coverage),
+            fog: lerp(self.fog, target.fog),
+            wind_intensity: lerp(self.wind_intensity, target.wind_intensity),
+            rain: lerp(self.rain, target.rain),
+            snow: lerp(self.snow, target.snow),
+            thunder_lightning: lerp(self.thunder_lightning, target.thunder_lightning),
+            transitioning: true,
+            season: target.season,
+        }
+    }
+}
+
+/// Shows a preset in every world until it's cleared with `None`, the scheduled weather resumes then.
+pub fn force_preset(
+    settings: &WeatherSettings,
+    name: Option<&str>,
+) -> Result<(), UnknownPresetError> {
+    let forced = name
+        .map(|name| {
+            settings
+                .presets
+                .iter()
+                .position(|preset| preset.name == name)
+                .ok_or_else(|| UnknownPresetError(name.to_string()))
+        })
+        .transpose()?;
+
+    *FORCED_PRESET.lock().unwrap() = forced;
+    Ok(())
+}
+
+/// Spawns the weather actor of the world, its channel is opened for every player who joins.
+pub fn spawn_weather(world: &mut World) -> FNetworkGUID {
+    let archetype = world.register_hierarchy_for_static_objects(WEATHER_CLASS);
+    let (guid, weather) =
+        UltraDynamicWeather::new(world, NetRole::SimulatedProxy, NetRole::Authority);
+
+    let guid = world.spawn_actor(
+        SpawnActorParams::Dynamic {
+            guid,
+            pos: FVector3d::default(),
+            rot: FRotator::default(),
+            archetype,
+            archetype_rep: Box::new(weather),
+        },
+        Vec::new(),
+    );
+
+    info!(
+        "spawned weather with {} presets",
+        world.globals.weather.presets.len()
+    );
+
+    guid
+}
+
+/// Applies the current weather to the weather actor of the world.
+pub fn tick_weather(world: &mut World) {
+    let time = world.time_seconds();
+    let forced = *FORCED_PRESET.lock().unwrap();
+
+    // Forced weather is shown right away.
+    if time < world.weather_scheduler.next_update_time && forced == world.weather_scheduler.forced {
+        return;
+    }
+
+    world.weather_scheduler.forced = forced;
+    world.weather_scheduler.next_update_time = time + UPDATE_INTERVAL;
+
+    let now = world.clock.unix_time().as_secs();
+    let Some(state) = world.weather_scheduler.weather_at(now) else {
+        return;
+    };
+
+    if let Some(mut weather) = world.get_actor_object_mut::<UltraDynamicWeather>(world.weather()) {
+        let weather = weather.data_mut();
+        weather.weater_index_replicate.set_value(state.preset_index);
+        weather.cloud_coverage.set_value(state.cloud_coverage);
+        weather.fog.set_value(state.fog);
+        weather.wind_intensity.set_value(state.wind_intensity);
+        weather.rain.set_value(state.rain);
+        weather.snow.set_value(state.snow);
+        weather.thunder_lightning.set_value(state.thunder_lightning);
+        weather.material_snow_coverage.set_value(state.snow);
+        weather.replicated_material_snow.set_value(state.snow);
+        weather
+            .transition_state
+            .set_value(u32::from(state.transitioning));
+        weather.season.set_value(state.season);
+    }
+}
+
+fn season_at(settings: &WeatherSettings, now: u64) -> f64 {
+    let season_length = settings.season_length.max(1) * SECONDS_PER_DAY;
+    (now as f64 / season_length as f64) % SEASON_COUNT
+}
+
+fn smooth_step(alpha: f64) -> f64 {
+    let alpha = alpha.clamp(0.0, 1.0);
+    alpha * alpha * (3.0 - 2.0 * alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = SECONDS_PER_DAY;
+
+    fn preset(name: &str, index: u32, weight: f32, seasons: &[u32]) -> WeatherPreset {
+        WeatherPreset {
+            name: name.to_string(),
+            index,
+            weight,
+            seasons: seasons.to_vec(),
+            cloud_coverage: 0.0,
+            fog: 0.0,
+            wind_intensity: 0.0,
+            rain: 0.0,
+            snow: 0.0,
+            thunder_lightning: 0.0,
+        }
+    }
+
+    fn scheduler(presets: Vec<WeatherPreset>) -> WeatherScheduler {
+        WeatherScheduler::new(Box::leak(Box::new(WeatherSettings {
+            change_interval: 3600,
+            transition_time: 0,
+            season_length: 1,
+            forced_preset: None,
+            presets,
+        })))
+    }
+
+    /// Names of the presets picked for the change intervals of a day.
+    fn picks_of_day(scheduler: &WeatherScheduler, day: u64) -> Vec<&'static str> {
+        let slots_per_day = DAY / scheduler.settings.change_interval;
+
+        (day * slots_per_day..(day + 1) * slots_per_day)
+            .map(|slot| scheduler.scheduled_preset(slot).unwrap().name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn picks_presets_of_the_current_season() {
+        let scheduler = scheduler(vec![
+            preset("Spring", 0, 1.0, &[0]),
+            preset("Summer", 1, 1.0, &[1]),
+            preset("Any", 2, 1.0, &[]),
+        ]);
+
+        let spring = picks_of_day(&scheduler, 0);
+        assert!(spring.iter().all(|&name| name == "Spring" || name == "Any"));
+        assert!(spring.contains(&"Spring"));
+
+        let summer = picks_of_day(&scheduler, 1);
+        assert!(summer.iter().all(|&name| name == "Summer" || name == "Any"));
+        assert!(summer.contains(&"Summer"));
+
+        // Seasons repeat after winter.
+        assert!(
+            picks_of_day(&scheduler, 4)
+                .iter()
+                .all(|&name| name == "Spring" || name == "Any")
+        );
+    }
+
+    #[test]
+    fn picks_presets_by_weight() {
+        let scheduler = scheduler(vec![
+            preset("Common", 0, 3.0, &[]),
+            preset("Rare", 1, 1.0, &[]),
+            preset("Never", 2, 0.0, &[]),
+        ]);
+
+        let picks = (0..4000)
+            .map(|slot| scheduler.scheduled_preset(slot).unwrap().name.as_str())
+            .collect::<Vec<_>>();
+        let common = picks.iter().filter(|&&name| name == "Common").count();
+
+        assert!(!picks.contains(&"Never"));
+        assert!((2800..3200).contains(&common), "{common}");
+    }
+
+    #[test]
+    fn picks_are_stable_for_an_interval() {
+        let scheduler = scheduler(vec![
+            preset("Clear", 0, 1.0, &[]),
+            preset("Rain", 1, 1.0, &[]),
+        ]);
+
+        for slot in 0..100 {
+            assert_eq!(
+                scheduler.scheduled_preset(slot).unwrap().index,
+                scheduler.scheduled_preset(slot).unwrap().index
+            );
+            assert_eq!(
+                scheduler.weather_at(slot * 3600).unwrap().preset_index,
+                scheduler
+                    .weather_at(slot * 3600 + 3599)
+                    .unwrap()
+                    .preset_index
+            );
+        }
+    }
+
+    #[test]
+    fn no_weather_without_presets_of_the_season() {
+        let scheduler = scheduler(vec![preset("Winter", 0, 1.0, &[3])]);
+
+        assert!(scheduler.weather_at(0).is_none());
+        assert!(scheduler.weather_at(3 * DAY).is_some());
+    }
+}
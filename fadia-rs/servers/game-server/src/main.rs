@@ -6,7 +6,7 @@ use std::{
 use assets::{AssetsLoadingError, GameAssets};
 use common::config_util;
 use config::ServerConfig;
-use tracing::error;
+use tracing::{error, info, warn};
 
 mod assets;
 mod config;
@@ -19,26 +19,59 @@ pub enum StartupError {
     BindFailed(SocketAddr, std::io::Error),
     #[error("{0}")]
     AssetsLoading(#[from] AssetsLoadingError),
+    #[error("map {0} isn't in gameplay.maps")]
+    UnknownMap(String),
 }
 
 #[tokio::main]
 async fn main() -> Result<(), StartupError> {
     static ASSETS: OnceLock<GameAssets> = OnceLock::new();
     static CONFIG: LazyLock<ServerConfig> = LazyLock::new(|| {
-        config_util::load_or_create::<ServerConfig>(
+        let mut config = config_util::load_or_create::<ServerConfig>(
             "game_server.toml",
             include_str!("../game_server.default.toml"),
-        )
+        );
+        config.gameplay.insert_legacy_map();
+        config
     });
 
     common::log_util::init_tracing();
 
+    let default_map = CONFIG
+        .gameplay
+        .find_map(&CONFIG.gameplay.map)
+        .ok_or_else(|| StartupError::UnknownMap(CONFIG.gameplay.map.clone()))
+        .inspect_err(|err| error!("{err}"))?;
+
+    if let Some(dungeon) = CONFIG
+        .gameplay
+        .dungeons
+        .iter()
+        .find(|dungeon| CONFIG.gameplay.find_map(&dungeon.map).is_none())
+    {
+        let err = StartupError::UnknownMap(dungeon.map.clone());
+        error!("{err}");
+        return Err(err);
+    }
+
+    info!(
+        "version profile: network version {}, network features {}",
+        CONFIG.version.network_version, CONFIG.version.network_features
+    );
+
+    if let Err(err) = logic::weather::force_preset(
+        &CONFIG.gameplay.weather,
+        CONFIG.gameplay.weather.forced_preset.as_deref(),
+    ) {
+        warn!("failed to force the configured weather: {err}");
+    }
+
     let assets = GameAssets::load().inspect_err(|err| error!("{err}"))?;
     let assets = ASSETS.get_or_init(|| assets);
 
-    let cluster = logic::cluster::allocate_cluster(&CONFIG.gameplay, assets);
+    let cluster = logic::cluster::allocate_cluster(&CONFIG.gameplay, default_map, assets);
 
-    net::serve(CONFIG.udp_addr, &cluster)
+    net::serve(CONFIG.udp_addr, &CONFIG.version, &cluster)
         .await
         .map_err(|err| StartupError::BindFailed(CONFIG.udp_addr, err))
 }
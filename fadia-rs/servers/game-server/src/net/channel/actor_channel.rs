@@ -109,6 +109,12 @@ impl ActorChannel {
             return;
         }
 
+        world
+            .objects
+            .get(&self.actor_guid)
+            .unwrap()
+            .on_channel_tick(self, world);
+
         if self.should_send_bunch(world) {
             let mut bunch_data = Vec::new();
             let mut out = OutBitWriter::new(&mut bunch_data);
@@ -155,22 +161,21 @@ impl ActorChannel {
         objects: HashSet<FNetworkGUID>,
         world: &mut World,
     ) {
-        let actor_archetype_guid = world.actors.get(&self.actor_guid).unwrap().archetype_guid;
+        let replicate_unverified = world.globals.replicate_unverified_layouts;
 
         objects.iter().for_each(|&guid| {
             let object = world.objects.get_mut(&guid).unwrap();
-            if (self.actor_guid != guid || guid == actor_archetype_guid)
-                && (!object.rep_layout.is_empty()
-                    || !object.queued_rpcs.is_empty()
-                    || guid.is_dynamic())
+            if !object.rep_layout.is_empty() || !object.queued_rpcs.is_empty() || guid.is_dynamic()
             {
                 let is_spawn_bunch = self.initial_bunches_sent_for_guids.insert(guid);
+                let properties = replicate_unverified || !object.rep_layout.is_unverified();
                 self.write_object_data(
                     out,
                     guid,
                     object,
-                    guid == actor_archetype_guid,
+                    guid == self.actor_guid,
                     is_spawn_bunch,
+                    properties,
                 );
             }
 
@@ -185,8 +190,10 @@ impl ActorChannel {
         object: &mut Object,
         is_actor: bool,
         send_full_data: bool,
+        properties: bool,
     ) {
-        replication::serialize_object(writer, guid, object, is_actor, send_full_data).unwrap();
+        replication::serialize_object(writer, guid, object, is_actor, send_full_data, properties)
+            .unwrap();
 
         // Clear data that is already serialized
         object.rep_layout.acknowledge_changes();
@@ -303,12 +310,6 @@ impl ChannelImpl for ActorChannel {
 
             let mut r = InBitReader::new(Cursor::new(payload.as_ref()));
 
-            let guid = if guid == self.actor_guid {
-                world.actors.get(&self.actor_guid).unwrap().archetype_guid
-            } else {
-                guid
-            };
-
             let Some(object) = world.objects.get(&guid) else {
                 error!("received RPCs for non-existent object, GUID: {guid:?}");
                 continue;
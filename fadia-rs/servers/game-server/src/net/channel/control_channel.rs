@@ -3,6 +3,7 @@ use super::ChannelImpl;
 use std::borrow::Cow;
 
 use bitstream_io::BitRead;
+use fadia_engine::FNetworkGUID;
 use fadia_engine::net::Bunch;
 use fadia_engine::util::InBitReader;
 use fadia_engine::util::{ReadPrimitivesExt, serialization::BitSerialize};
@@ -81,8 +82,21 @@ macro_rules! discard_and_do {
 define_control_channel_messages! {
     Hello, 0, is_little_endian: u8, network_version: u32, encryption_token: String, network_features: u16;
     Welcome, 1, map: Cow<'static, str>, game_name: Cow<'static, str>, redirect_url: Cow<'static, str>;
+    Upgrade, 2, network_version: u32, network_features: u16;
     Challenge, 3, challenge: String;
     Netspeed, 4, net_speed: u32;
     Login, 5, client_response: String, request_url: String, flags: u8, unique_id: String, online_platform: String;
-    Join, 9
+    Failure, 6, error: String;
+    Join, 9;
+    PCSwap, 15, child_index: i32;
+    ActorChannelFailure, 16, channel_index: i32;
+    DebugText, 17, text: String;
+    NetGUIDAssign, 18, net_guid: FNetworkGUID, path: String;
+    GameSpecific, 20, message_type: u8, message: String;
+    EncryptionAck, 21;
+    CloseReason, 23, reason: String;
+    BeaconWelcome, 25;
+    BeaconJoin, 26, beacon_type: String, flags: u8, unique_id: String;
+    BeaconAssignGUID, 27, net_guid: FNetworkGUID;
+    BeaconNetGUIDAck, 28, beacon_type: String
 }
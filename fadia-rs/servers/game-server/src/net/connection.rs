@@ -3,9 +3,11 @@ use std::{
     cell::OnceCell,
     collections::{BTreeMap, VecDeque},
     io::{self, Cursor},
+    path::Path,
 };
 
 use bitstream_io::{BitRead, BitWrite, BitWriter, LittleEndian};
+use common::{session_ticket::SessionTicket, version_profile::VersionProfile};
 use fadia_engine::{
     FNetworkGUID,
     util::{self, FName, ReadBitsExt, WriteBitsExt},
@@ -16,11 +18,21 @@ use fadia_engine::{
 };
 use tracing::{debug, error};
 
-use crate::logic::{actor::NetPlayerIndex, mode::SessionLoginOptions, replication::InRPC};
+use crate::logic::{
+    actor::NetPlayerIndex,
+    mode::{LoginError, SessionLoginOptions},
+    replication::InRPC,
+    scope::WorldID,
+    travel::TravelDestination,
+};
 
 use super::{
     SessionID,
-    channel::{ActorChannel, Challenge, Channel, ControlChannel, NAME_CONTROL_CHANNEL, Welcome},
+    channel::{
+        ActorChannel, Challenge, Channel, ControlChannel, EncryptionAck, Failure,
+        NAME_CONTROL_CHANNEL, Upgrade, Welcome,
+    },
+    packet_handler::PacketHandler,
     udp_server::PacketSender,
     world::World,
 };
@@ -42,6 +54,23 @@ pub struct NetConnection {
     pub player_controller: Option<FNetworkGUID>,
     pub current_net_speed: u32,
     pub unique_id: OnceCell<String>,
+    /// Encryption token of `NMT_Hello`, empty if the client didn't send one.
+    pub encryption_token: String,
+    /// Ticket the player logged in with, renewed when the player is sent to another server.
+    pub session_ticket: Option<SessionTicket>,
+    /// Options of the login URL, set once the login is approved.
+    pub login_options: SessionLoginOptions,
+    /// World of the scope the connection is in.
+    pub world: WorldID,
+    /// Where the connection moves to once its packets are handled, see `LogicScope::process_travels`.
+    pub pending_travel: Option<TravelDestination>,
+    /// Set once the connection is closed, it's dropped after its pending packets are flushed.
+    pub closed: bool,
+    pub packet_handler: PacketHandler,
+    /// Packet carrying `NMT_EncryptionAck`, encryption is enabled once the client acknowledges it.
+    encryption_ack_seq: Option<u16>,
+    /// Versions the client must report in `NMT_Hello`.
+    pub version: &'static VersionProfile,
     player_index: NetPlayerIndex,
     output: PacketSender,
     packet_notify: FNetPacketNotify,
@@ -56,12 +85,24 @@ impl NetConnection {
         player_index: NetPlayerIndex,
         (out_seq, in_seq): (u16, u16),
         output: PacketSender,
+        world: WorldID,
+        packet_handler: PacketHandler,
+        version: &'static VersionProfile,
     ) -> Self {
         let mut connection = Self {
             session_id,
             player_index,
             output,
             unique_id: OnceCell::new(),
+            encryption_token: String::new(),
+            session_ticket: None,
+            login_options: SessionLoginOptions::default(),
+            world,
+            pending_travel: None,
+            closed: false,
+            packet_handler,
+            encryption_ack_seq: None,
+            version,
             packet_notify: FNetPacketNotify::default(),
             init_in_reliable: 0,
             init_out_reliable: 0,
@@ -127,13 +168,6 @@ impl NetConnection {
         None
     }
 
-    pub fn login_options(&self) -> SessionLoginOptions {
-        SessionLoginOptions {
-            spectator_only: false,
-            splitscreen_count: 0,
-        }
-    }
-
     pub fn received_packet(
         &mut self,
         world: &mut World,
@@ -149,9 +183,32 @@ impl NetConnection {
         let packet_sequence_delta = self.packet_notify.get_sequence_delta(&header);
         if packet_sequence_delta > 0 {
             if packet_sequence_delta > 0 {
-                self.packet_notify.process_received_acks(&header);
+                let mut encryption_ack_delivered = None;
+
+                self.packet_notify
+                    .process_received_acks(&header, |seq, delivered| {
+                        if !delivered {
+                            debug!("packet {seq} was lost");
+                        }
+
+                        if self.encryption_ack_seq == Some(seq) {
+                            encryption_ack_delivered = Some(delivered);
+                        }
+                    });
                 self.packet_notify
                     .internal_update(&header, packet_sequence_delta);
+
+                match encryption_ack_delivered {
+                    Some(true) => {
+                        self.encryption_ack_seq = None;
+                        self.packet_handler.enable_encryption();
+                    }
+                    // Reliable bunches are never resent, send the acknowledgement again.
+                    Some(false) => self
+                        .send_encryption_ack()
+                        .map_err(ReceivePacketError::DispatchPacket)?,
+                    None => (),
+                }
             }
 
             if self.packet_notify.is_waiting_for_sequence_history_flush() {
@@ -226,9 +283,34 @@ impl NetConnection {
     }
 
     pub fn send_challenge_control_message(&mut self) -> io::Result<()> {
+        // Clients answer with a fixed response which doesn't depend on the challenge, logins
+        // are authenticated by session tickets instead.
         Challenge::send(self, String::from("8B69DF87"))
     }
 
+    /// Checks that the player logging in was authenticated by the gamesdk server: the client
+    /// sent the token of its ticket in `NMT_Hello`. The ticket is consumed on success.
+    pub fn check_session_ticket(&mut self, dir: &Path, unique_id: &str) -> Result<(), LoginError> {
+        let ticket = SessionTicket::load(dir, unique_id)
+            .map_err(|err| {
+                error!("failed to load session ticket of {unique_id}: {err}");
+                LoginError("Failed to read session ticket.")
+            })?
+            .ok_or(LoginError("Session ticket is missing or expired."))?;
+
+        if !ticket.matches_token(&self.encryption_token) {
+            return Err(LoginError("Invalid session ticket."));
+        }
+
+        if let Err(err) = ticket.consume(dir) {
+            error!("failed to consume session ticket of {unique_id}: {err}");
+            return Err(LoginError("Failed to read session ticket."));
+        }
+
+        self.session_ticket = Some(ticket);
+        Ok(())
+    }
+
     pub fn send_welcome_control_message(
         &mut self,
         map: Cow<'static, str>,
@@ -238,6 +320,39 @@ impl NetConnection {
         Welcome::send(self, map, game_name, redirect_url)
     }
 
+    /// Tells the client why it can't play, it shows the message and disconnects.
+    pub fn send_failure_control_message(&mut self, error: &str) -> io::Result<()> {
+        Failure::send(self, error.to_string())
+    }
+
+    /// Tells the client its network version doesn't match the server one.
+    pub fn send_upgrade_control_message(&mut self) -> io::Result<()> {
+        Upgrade::send(
+            self,
+            self.version.network_version,
+            self.version.network_features,
+        )
+    }
+
+    /// Tells the client its encryption token was accepted, packets are encrypted with `key`
+    /// once the client acknowledges the packet carrying the message.
+    pub fn send_encryption_ack_control_message(&mut self, key: &[u8; 32]) -> io::Result<()> {
+        self.packet_handler.set_encryption_key(key);
+
+        self.send_encryption_ack()
+    }
+
+    /// Sends `NMT_EncryptionAck` alone in an unencrypted packet and remembers its sequence.
+    fn send_encryption_ack(&mut self) -> io::Result<()> {
+        if self.has_awaiting_send_packets() {
+            self.flush_net()?;
+        }
+
+        EncryptionAck::send(self)?;
+        self.encryption_ack_seq = Some(self.packet_notify.get_out_seq());
+        self.flush_net()
+    }
+
     pub fn send_control_channel_reliable_bunch(
         &mut self,
         buffer_terminated: &[u8],
@@ -327,12 +442,19 @@ impl NetConnection {
 
     pub fn flush_net(&mut self) -> io::Result<()> {
         loop {
+            let mut packet = Vec::new();
+            let mut w = BitWriter::endian(Cursor::new(&mut packet), LittleEndian);
+            self.flush_outgoing_packet(&mut w).unwrap();
+            w.byte_align()?;
+
+            let packet_bits = util::get_bits_from_terminated_stream(&packet).unwrap() + 1;
+
             let mut buf = Vec::new();
             let mut w = BitWriter::endian(Cursor::new(&mut buf), LittleEndian);
             w.write(2, self.session_id.session_id)?;
             w.write(3, self.session_id.client_id)?;
             w.write_bit(false)?;
-            self.flush_outgoing_packet(&mut w).unwrap();
+            self.packet_handler.outgoing(&mut w, &packet, packet_bits)?;
             w.write_bit(true)?;
             w.byte_align()?;
 
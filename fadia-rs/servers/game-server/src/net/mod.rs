@@ -1,5 +1,6 @@
 mod channel;
 mod connection;
+mod packet_handler;
 mod stateless_connect_handler;
 mod udp_server;
 mod world;
@@ -10,6 +11,7 @@ use std::{fmt, net::SocketAddr};
 pub use channel::*;
 
 pub use connection::NetConnection;
+pub use packet_handler::{IncomingPacket, PacketHandler};
 pub use world::*;
 
 pub use udp_server::{ConnectParams, NetworkEventListener, ReceiveParams, serve};
@@ -0,0 +1,305 @@
+use std::io;
+
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce, Tag, aead::AeadInPlace};
+use bitstream_io::{BitRead, BitReader, BitWrite, LittleEndian};
+use fadia_engine::util::{self, ReadBitsExt, UnterminatedBitsError, WriteBitsExt};
+use rand::RngCore;
+
+const IV_SIZE: usize = 12;
+const AUTH_TAG_SIZE: usize = 16;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PacketHandlerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    UnterminatedBits(#[from] UnterminatedBitsError),
+    #[error("received an encrypted packet before encryption was enabled")]
+    EncryptionDisabled,
+    #[error("received an unencrypted packet after encryption was enabled")]
+    Unencrypted,
+    #[error("encrypted packet is truncated")]
+    Truncated,
+    #[error("failed to decrypt packet")]
+    Decrypt,
+}
+
+/// Packet handler components between the stateless connect handler and the connection,
+/// like `PacketHandler` of the engine. The only one is `AESGCMHandlerComponent`.
+pub struct PacketHandler {
+    aes_gcm: Option<AesGcmHandler>,
+}
+
+/// `AESGCMHandlerComponent`, every packet starts with a bit telling if it's encrypted.
+/// Encrypted packets are followed by the IV, the auth tag and the AES-256-GCM ciphertext
+/// of the packet, padded to bytes.
+#[derive(Default)]
+struct AesGcmHandler {
+    /// Set once the client sent a valid encryption token, decrypts its packets from then on.
+    cipher: Option<Aes256Gcm>,
+    /// Whether the client got `NMT_EncryptionAck`, it only sends encrypted packets afterwards.
+    enabled: bool,
+    /// Random prefix of the IVs of the connection, followed by `next_iv`,
+    /// so an IV is never used twice with the same key.
+    iv_prefix: [u8; 4],
+    next_iv: u64,
+}
+
+/// Packet passed to the connection, `data` is read from `offset_in_bits` to `size_in_bits`.
+pub struct IncomingPacket {
+    pub data: Box<[u8]>,
+    pub offset_in_bits: usize,
+    pub size_in_bits: usize,
+}
+
+impl PacketHandler {
+    /// `encryption` adds the AES-GCM component, the client must have it too.
+    pub fn new(encryption: bool) -> Self {
+        Self {
+            aes_gcm: encryption.then(AesGcmHandler::default),
+        }
+    }
+
+    pub fn supports_encryption(&self) -> bool {
+        self.aes_gcm.is_some()
+    }
+
+    /// Decrypts the packets of the client with `key`, the ones sent stay unencrypted
+    /// until `enable_encryption` since the client can't read them before `NMT_EncryptionAck`.
+    pub fn set_encryption_key(&mut self, key: &[u8; 32]) {
+        if let Some(aes_gcm) = self.aes_gcm.as_mut() {
+            aes_gcm.cipher = Some(Aes256Gcm::new(key.into()));
+            aes_gcm.enabled = false;
+            rand::rng().fill_bytes(&mut aes_gcm.iv_prefix);
+            aes_gcm.next_iv = 0;
+        }
+    }
+
+    /// Encrypts the packets sent from now on and rejects unencrypted ones,
+    /// once the client acknowledged the packet with `NMT_EncryptionAck`.
+    pub fn enable_encryption(&mut self) {
+        if let Some(aes_gcm) = self
+            .aes_gcm
+            .as_mut()
+            .filter(|aes_gcm| aes_gcm.cipher.is_some())
+        {
+            aes_gcm.enabled = true;
+        }
+    }
+
+    pub fn incoming(&self, packet: IncomingPacket) -> Result<IncomingPacket, PacketHandlerError> {
+        match self.aes_gcm.as_ref() {
+            Some(aes_gcm) => aes_gcm.incoming(packet),
+            None => Ok(packet),
+        }
+    }
+
+    /// Writes a terminated packet of the connection, `size_in_bits` includes its termination bit.
+    pub fn outgoing<W: BitWrite>(
+        &mut self,
+        w: &mut W,
+        packet: &[u8],
+        size_in_bits: usize,
+    ) -> io::Result<()> {
+        match self.aes_gcm.as_mut() {
+            Some(aes_gcm) => aes_gcm.outgoing(w, packet, size_in_bits),
+            None => w.write_bits(packet, size_in_bits),
+        }
+    }
+}
+
+impl AesGcmHandler {
+    fn incoming(&self, packet: IncomingPacket) -> Result<IncomingPacket, PacketHandlerError> {
+        let mut r = BitReader::endian(io::Cursor::new(packet.data.as_ref()), LittleEndian);
+        r.seek_bits(io::SeekFrom::Start(packet.offset_in_bits as u64))?;
+
+        if !r.read_bit()? {
+            if self.enabled {
+                return Err(PacketHandlerError::Unencrypted);
+            }
+
+            return Ok(IncomingPacket {
+                offset_in_bits: packet.offset_in_bits + 1,
+                ..packet
+            });
+        }
+
+        let cipher = self
+            .cipher
+            .as_ref()
+            .ok_or(PacketHandlerError::EncryptionDisabled)?;
+
+        // The size excludes the termination bits of the connection and the handler,
+        // the ciphertext ends right before the latter.
+        let header_bits = 1 + (IV_SIZE + AUTH_TAG_SIZE) * 8;
+        let ciphertext_bits = (packet.size_in_bits + 1)
+            .checked_sub(packet.offset_in_bits + header_bits)
+            .filter(|bits| bits % 8 == 0)
+            .ok_or(PacketHandlerError::Truncated)?;
+
+        let iv = r.read_bits(IV_SIZE * 8)?;
+        let tag = r.read_bits(AUTH_TAG_SIZE * 8)?;
+        let mut plaintext = r.read_bits(ciphertext_bits)?;
+
+        cipher
+            .decrypt_in_place_detached(
+                Nonce::from_slice(&iv),
+                &[],
+                &mut plaintext,
+                Tag::from_slice(&tag),
+            )
+            .map_err(|_| PacketHandlerError::Decrypt)?;
+
+        // The plaintext ends with the termination bit of the connection.
+        let size_in_bits = util::get_bits_from_terminated_stream(&plaintext)?;
+
+        Ok(IncomingPacket {
+            data: plaintext.into_boxed_slice(),
+            offset_in_bits: 0,
+            size_in_bits,
+        })
+    }
+
+    fn outgoing<W: BitWrite>(
+        &mut self,
+        w: &mut W,
+        packet: &[u8],
+        size_in_bits: usize,
+    ) -> io::Result<()> {
+        let Some(cipher) = self.cipher.as_ref().filter(|_| self.enabled) else {
+            w.write_bit(false)?;
+            return w.write_bits(packet, size_in_bits);
+        };
+
+        let mut iv = [0u8; IV_SIZE];
+        iv[..4].copy_from_slice(&self.iv_prefix);
+        iv[4..].copy_from_slice(&self.next_iv.to_le_bytes());
+        self.next_iv += 1;
+
+        let mut ciphertext = packet[..size_in_bits.div_ceil(8)].to_vec();
+        let tag = cipher
+            .encrypt_in_place_detached(Nonce::from_slice(&iv), &[], &mut ciphertext)
+            .map_err(|_| io::Error::other("failed to encrypt packet"))?;
+
+        w.write_bit(true)?;
+        w.write_bytes(&iv)?;
+        w.write_bytes(&tag)?;
+        w.write_bytes(&ciphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitstream_io::BitWriter;
+
+    use super::*;
+
+    /// Terminated packet of the connection with a 16 bit payload, its size includes the termination bit.
+    fn connection_packet(payload: u16) -> (Vec<u8>, usize) {
+        let mut packet = Vec::new();
+        let mut w = BitWriter::endian(io::Cursor::new(&mut packet), LittleEndian);
+        w.write::<u16>(16, payload).unwrap();
+        w.write_bit(true).unwrap();
+        w.byte_align().unwrap();
+
+        let size_in_bits = util::get_bits_from_terminated_stream(&packet).unwrap() + 1;
+        (packet, size_in_bits)
+    }
+
+    /// Sends a packet through `sender` and returns it as received by the UDP server.
+    fn send(sender: &mut PacketHandler, payload: u16) -> IncomingPacket {
+        let (packet, size_in_bits) = connection_packet(payload);
+
+        let mut buf = Vec::new();
+        let mut w = BitWriter::endian(io::Cursor::new(&mut buf), LittleEndian);
+        sender.outgoing(&mut w, &packet, size_in_bits).unwrap();
+        w.write_bit(true).unwrap();
+        w.byte_align().unwrap();
+
+        let size_in_bits = util::get_bits_from_terminated_stream(&buf).unwrap() - 1;
+        IncomingPacket {
+            data: buf.into_boxed_slice(),
+            offset_in_bits: 0,
+            size_in_bits,
+        }
+    }
+
+    fn read_payload(packet: &IncomingPacket) -> u16 {
+        let mut r = BitReader::endian(io::Cursor::new(packet.data.as_ref()), LittleEndian);
+        r.seek_bits(io::SeekFrom::Start(packet.offset_in_bits as u64))
+            .unwrap();
+        r.read::<u16>(16).unwrap()
+    }
+
+    fn encrypted_handler(key: &[u8; 32]) -> PacketHandler {
+        let mut handler = PacketHandler::new(true);
+        handler.set_encryption_key(key);
+        handler.enable_encryption();
+        handler
+    }
+
+    #[test]
+    fn encrypted_packet_round_trip() {
+        let key = [1; 32];
+        let mut client = encrypted_handler(&key);
+        let server = encrypted_handler(&key);
+
+        for payload in [0xABCD, 0x1234] {
+            let packet = server.incoming(send(&mut client, payload)).unwrap();
+
+            assert_eq!(packet.offset_in_bits, 0);
+            assert_eq!(packet.size_in_bits, 16);
+            assert_eq!(read_payload(&packet), payload);
+        }
+    }
+
+    #[test]
+    fn rejects_packet_of_another_key() {
+        let mut client = encrypted_handler(&[1; 32]);
+        let server = encrypted_handler(&[2; 32]);
+
+        assert!(matches!(
+            server.incoming(send(&mut client, 0xABCD)),
+            Err(PacketHandlerError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn unencrypted_until_enabled() {
+        let key = [1; 32];
+        let mut server = PacketHandler::new(true);
+        server.set_encryption_key(&key);
+
+        let mut client = PacketHandler::new(true);
+
+        // Until the client acknowledges `NMT_EncryptionAck`, packets go both ways unencrypted.
+        let packet = client.incoming(send(&mut server, 0xABCD)).unwrap();
+        assert_eq!(packet.offset_in_bits, 1);
+        assert_eq!(read_payload(&packet), 0xABCD);
+
+        let packet = server.incoming(send(&mut client, 0x1234)).unwrap();
+        assert_eq!(read_payload(&packet), 0x1234);
+
+        server.enable_encryption();
+        client.set_encryption_key(&key);
+
+        assert!(matches!(
+            server.incoming(send(&mut client, 0x1234)),
+            Err(PacketHandlerError::Unencrypted)
+        ));
+
+        let packet = client.incoming(send(&mut server, 0xABCD)).unwrap();
+        assert_eq!(packet.offset_in_bits, 0);
+        assert_eq!(read_payload(&packet), 0xABCD);
+    }
+
+    #[test]
+    fn rejects_encrypted_packet_without_key() {
+        let mut client = encrypted_handler(&[1; 32]);
+
+        assert!(matches!(
+            PacketHandler::new(true).incoming(send(&mut client, 0xABCD)),
+            Err(PacketHandlerError::EncryptionDisabled)
+        ));
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use bitstream_io::{BitRead, BitWrite, BitWriter, LittleEndian};
+use common::version_profile::VersionProfile;
 use hmac::{Hmac, Mac};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use rand::RngCore;
@@ -13,10 +14,6 @@ use tracing::{debug, info};
 
 use super::SessionID;
 
-const HANDSHAKE_MIN_VERSION: u8 = 3;
-const LOCAL_NETWORK_VERSION: u32 = 2566650454;
-const LOCAL_NETWORK_FEATURES: u16 = 0;
-
 // TODO: regenerate it every 15 seconds
 static HANDSHAKE_SECRET: LazyLock<[u8; 64]> = LazyLock::new(|| {
     let mut key = [0u8; 64];
@@ -35,6 +32,7 @@ pub fn on_receive<R: BitRead>(
     session_id: &SessionID,
     mut r: R,
     addr: SocketAddr,
+    version: &VersionProfile,
 ) -> io::Result<HandshakeResult> {
     use HandshakeResult::*;
 
@@ -65,6 +63,30 @@ pub fn on_receive<R: BitRead>(
         "received handshake packet from {session_id}: min_version: {min_version}, cur_version: {cur_version}, type: {handshake_packet_type:?}, sent_count: {sent_handshake_packet_count_local_or_remote}, net_version: {local_network_version}, net_features: {local_network_features}"
     );
 
+    if cur_version < version.handshake_min_version {
+        info!(
+            "handshake version {cur_version} of {session_id} is older than {}, sending version upgrade",
+            version.handshake_min_version
+        );
+
+        let mut out_buf = Vec::new();
+        let mut w = BitWriter::endian(Cursor::new(&mut out_buf), LittleEndian);
+
+        begin_handshake_packet(
+            &mut w,
+            session_id,
+            HandshakePacketType::VersionUpgrade,
+            version,
+            cur_version,
+            sent_handshake_packet_count_local_or_remote,
+        )?;
+
+        finish_handshake_packet(&mut w)?;
+        w.byte_align()?;
+
+        return Ok(Send(out_buf));
+    }
+
     match handshake_packet_type {
         HandshakePacketType::InitialPacket => {
             let secret_id_bit = false;
@@ -78,6 +100,7 @@ pub fn on_receive<R: BitRead>(
                 &mut w,
                 session_id,
                 HandshakePacketType::Challenge,
+                version,
                 cur_version,
                 sent_handshake_packet_count_local_or_remote,
             )?;
@@ -111,6 +134,7 @@ pub fn on_receive<R: BitRead>(
                 &mut w,
                 session_id,
                 HandshakePacketType::Ack,
+                version,
                 cur_version,
                 sent_handshake_packet_count_local_or_remote,
             )?;
@@ -178,17 +202,18 @@ fn begin_handshake_packet<W: BitWrite>(
     w: &mut W,
     id: &SessionID,
     ty: HandshakePacketType,
-    version: u8,
+    version: &VersionProfile,
+    cur_version: u8,
     sent_count: u8,
 ) -> io::Result<()> {
     w.write(2, id.session_id)?;
     w.write(3, id.client_id)?;
     w.write_bit(true)?; // is_handshake
     w.write_bit(false)?; // restart_handshake
-    w.write(8, HANDSHAKE_MIN_VERSION)?; // min_version
-    w.write(8, version)?; // cur_version
+    w.write(8, version.handshake_min_version)?; // min_version
+    w.write(8, cur_version)?; // cur_version
     w.write::<u8>(8, ty.into())?;
     w.write(8, sent_count)?;
-    w.write(32, LOCAL_NETWORK_VERSION)?;
-    w.write(16, LOCAL_NETWORK_FEATURES)
+    w.write(32, version.network_version)?;
+    w.write(16, version.network_features)
 }
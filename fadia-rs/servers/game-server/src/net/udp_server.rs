@@ -5,6 +5,7 @@ use std::{
 };
 
 use bitstream_io::{BitRead, BitReader, LittleEndian};
+use common::version_profile::VersionProfile;
 use fadia_engine::util::{self, UnterminatedBitsError};
 use tokio::{net::UdpSocket, sync::mpsc};
 use tracing::{debug, error, info};
@@ -18,6 +19,7 @@ struct UdpServer<'listener> {
     socket: Arc<UdpSocket>,
     output_tx: mpsc::Sender<(SessionID, Box<[u8]>)>,
     listener: &'listener dyn NetworkEventListener,
+    version: &'static VersionProfile,
 }
 
 pub async fn send_task(socket: Arc<UdpSocket>, mut rx: mpsc::Receiver<(SessionID, Box<[u8]>)>) {
@@ -26,7 +28,11 @@ pub async fn send_task(socket: Arc<UdpSocket>, mut rx: mpsc::Receiver<(SessionID
     }
 }
 
-pub async fn serve(addr: SocketAddr, listener: &dyn NetworkEventListener) -> io::Result<()> {
+pub async fn serve(
+    addr: SocketAddr,
+    version: &'static VersionProfile,
+    listener: &dyn NetworkEventListener,
+) -> io::Result<()> {
     let socket = UdpSocket::bind(addr).await?;
     let mut buf = [0u8; 1200];
 
@@ -38,6 +44,7 @@ pub async fn serve(addr: SocketAddr, listener: &dyn NetworkEventListener) -> io:
         socket: Arc::new(socket),
         output_tx: tx,
         listener,
+        version,
     };
 
     let socket = server.socket.clone();
@@ -49,10 +56,9 @@ pub async fn serve(addr: SocketAddr, listener: &dyn NetworkEventListener) -> io:
             .recv_from(&mut buf)
             .await
             .inspect_err(|err| debug!("recv_from failed: {err}"))
+            && let Err(err) = server.on_receive(&buf[..len], client_addr).await
         {
-            if let Err(err) = server.on_receive(&buf[..len], client_addr).await {
-                error!("on_receive failed: {err}");
-            }
+            error!("on_receive failed: {err}");
         }
     }
 }
@@ -67,6 +73,7 @@ pub struct ConnectParams {
     pub output: PacketSender,
     pub server_seq: u16,
     pub client_seq: u16,
+    pub version: &'static VersionProfile,
 }
 
 pub struct ReceiveParams {
@@ -105,7 +112,7 @@ impl UdpServer<'_> {
         let is_handshake = r.read_bit()?;
 
         if is_handshake {
-            match stateless_connect_handler::on_receive(&session_id, r, addr)? {
+            match stateless_connect_handler::on_receive(&session_id, r, addr, self.version)? {
                 HandshakeResult::None => (),
                 HandshakeResult::Send(buf) => {
                     self.socket.send_to(&buf, addr).await?;
@@ -118,6 +125,7 @@ impl UdpServer<'_> {
                         output: PacketSender(session_id, self.output_tx.clone()),
                         server_seq,
                         client_seq,
+                        version: self.version,
                     });
                 }
             }
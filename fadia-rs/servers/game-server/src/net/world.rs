@@ -4,9 +4,10 @@ use std::{
     collections::{HashMap, HashSet},
     io,
     rc::Rc,
+    time::{Duration, Instant},
 };
 
-use common::time_util;
+use common::{session_ticket::SessionTicket, time_util::ServerClock};
 use tracing::{error, info, warn};
 
 use super::{
@@ -15,25 +16,33 @@ use super::{
 };
 use crate::{
     assets::GameAssets,
-    config::GameplayGlobals,
+    config::{GameplayGlobals, MapSettings},
     logic::{
-        MutObjectWrap, Object, ObjectLayout, RefObjectWrap,
+        MutObjectWrap, Object, ObjectLayout, RefObjectWrap, SubObjectList,
         actor::{Actor, NetPlayerIndex, NetRole},
+        clock::{self, ClockSchedule},
+        data_layers,
+        dungeon::DungeonInstance,
         layout::WorldDataLayers,
-        mode::{GameModeBase, LoginError, NewGameMode},
+        mode::{GameModeBase, LoginError, LoginUrl, NewGameMode},
+        npc::{self, FIRST_MONSTER_CHANNEL_INDEX},
         rpc::RpcContext,
         state::HTGameState,
+        travel::{self, TravelDestination},
+        weather::{self, WeatherScheduler},
     },
     net::channel,
 };
+use fadia_engine::NetGUIDCache;
 use fadia_engine::vector::FVector3d;
 use fadia_engine::{FNetworkGUID, rotator::FRotator};
-use fadia_engine::{NetGUIDCache, replication::NullLayout};
 
 pub struct World {
     // settings
     pub assets: &'static GameAssets,
     pub globals: &'static GameplayGlobals,
+    pub map: &'static MapSettings,
+    pub clock: ServerClock,
     // logic
     pub actors: HashMap<FNetworkGUID, Actor>,
     pub objects: HashMap<FNetworkGUID, Object>,
@@ -43,6 +52,13 @@ pub struct World {
     game_mode: OnceCell<Rc<dyn GameModeBase>>,
     game_state: OnceCell<FNetworkGUID>,
     world_data_layers: OnceCell<FNetworkGUID>,
+    weather: OnceCell<FNetworkGUID>,
+    monsters: Vec<FNetworkGUID>,
+    /// Set if the world is the instance of a dungeon.
+    pub dungeon: Option<DungeonInstance>,
+    pub clock_schedule: ClockSchedule,
+    pub weather_scheduler: WeatherScheduler,
+    start_time: Instant,
 }
 
 pub enum SpawnActorParams {
@@ -76,18 +92,29 @@ impl World {
     pub fn new<GameMode: NewGameMode + 'static>(
         assets: &'static GameAssets,
         globals: &'static GameplayGlobals,
+        map: &'static MapSettings,
     ) -> Self {
+        let clock = ServerClock::new(Duration::from_secs(globals.clock.offset));
+
         let mut world = Self {
             assets,
             globals,
+            map,
+            clock,
             actors: HashMap::new(),
             objects: HashMap::new(),
             class_hierarchy: HashMap::new(),
-            net_guid_cache: NetGUIDCache::new(&globals.map),
+            net_guid_cache: NetGUIDCache::new(&map.path),
             player_controller_map: HashMap::new(),
             game_mode: OnceCell::new(),
             game_state: OnceCell::new(),
             world_data_layers: OnceCell::new(),
+            weather: OnceCell::new(),
+            monsters: Vec::new(),
+            dungeon: None,
+            clock_schedule: ClockSchedule::new(&globals.clock, clock.unix_timestamp_ms()),
+            weather_scheduler: WeatherScheduler::new(&globals.weather),
+            start_time: Instant::now(),
         };
 
         let game_mode = GameMode::new(&mut world);
@@ -107,14 +134,7 @@ impl World {
             HTGameState::new(self, NetRole::SimulatedProxy, NetRole::Authority);
 
         game_mode.init_game_state(&mut game_state);
-
-        game_state
-            .hotta_server_date_time_now
-            .set_value(time_util::unix_timestamp_ms());
-
-        game_state
-            .hotta_server_date_time_utc_now
-            .set_value(time_util::unix_utc_timestamp_ms());
+        clock::sync_game_state(&mut game_state, &clock::snapshot(self), self.time_seconds());
 
         let game_state_guid = self.spawn_actor(
             SpawnActorParams::Dynamic {
@@ -132,8 +152,8 @@ impl World {
 
     fn init_level(&mut self) {
         self.register_hierarchy_for_static_objects(&[
-            "/Game/Maps/Map_bigworld/XL_map_bigworld_test",
-            "XL_map_bigworld_test",
+            &self.map.path,
+            self.map.name(),
             "PersistentLevel",
         ]);
 
@@ -146,12 +166,39 @@ impl World {
         );
 
         let _ = self.world_data_layers.set(data_layers_guid);
+        data_layers::init_data_layers(self);
+
+        let weather_guid = weather::spawn_weather(self);
+        let _ = self.weather.set(weather_guid);
+
+        self.monsters = npc::spawn_monsters(self);
     }
 
     pub fn game_mode(&self) -> Rc<dyn GameModeBase> {
         Rc::clone(self.game_mode.get().unwrap())
     }
 
+    pub fn game_state(&self) -> FNetworkGUID {
+        self.game_state.get().copied().unwrap_or_default()
+    }
+
+    pub fn world_data_layers(&self) -> FNetworkGUID {
+        self.world_data_layers.get().copied().unwrap_or_default()
+    }
+
+    pub fn weather(&self) -> FNetworkGUID {
+        self.weather.get().copied().unwrap_or_default()
+    }
+
+    pub fn monsters(&self) -> &[FNetworkGUID] {
+        &self.monsters
+    }
+
+    /// Seconds elapsed since the world was created, `UWorld::GetTimeSeconds`.
+    pub fn time_seconds(&self) -> f64 {
+        self.start_time.elapsed().as_secs_f64()
+    }
+
     pub fn register_hierarchy(&mut self, hierarchy: ClassHierarchy) {
         let mut outer_guids = hierarchy.0.iter();
         let mut inner_guids = hierarchy.0.iter().skip(1);
@@ -210,13 +257,46 @@ impl World {
             Hello(channel::Hello(
                 is_little_endian,
                 remote_network_version,
-                _encryption_token,
+                encryption_token,
                 remote_network_features,
             )) => {
                 info!(
                     "received hello from client: little_endian: {is_little_endian}, network version: {remote_network_version}, network features: {remote_network_features}"
                 );
 
+                if !connection
+                    .version
+                    .is_compatible(remote_network_version, remote_network_features)
+                {
+                    warn!(
+                        "client network version {remote_network_version} with features {remote_network_features} doesn't match version {} with features {}, sending NMT_Upgrade",
+                        connection.version.network_version, connection.version.network_features
+                    );
+                    connection.send_upgrade_control_message()?;
+                    connection.closed = true;
+                    return Ok(());
+                }
+
+                connection.encryption_token = encryption_token;
+
+                if !connection.encryption_token.is_empty() {
+                    if connection.packet_handler.supports_encryption() {
+                        match SessionTicket::find_by_token(
+                            &self.globals.session_ticket_dir,
+                            &connection.encryption_token,
+                        ) {
+                            Ok(Some(ticket)) => connection
+                                .send_encryption_ack_control_message(&ticket.encryption_key())?,
+                            Ok(None) => warn!(
+                                "no session ticket matches the encryption token, packets stay unencrypted"
+                            ),
+                            Err(err) => error!("failed to look up session ticket: {err}"),
+                        }
+                    } else {
+                        warn!("client sent an encryption token, but packet encryption is disabled");
+                    }
+                }
+
                 connection.send_challenge_control_message().unwrap();
             }
             Netspeed(channel::Netspeed(net_speed)) => {
@@ -230,15 +310,37 @@ impl World {
                 unique_id,
                 online_platform,
             )) => {
+                // The request URL may carry the encryption key, only its parsed options are logged.
+                let login_url = LoginUrl::parse(&request_url);
                 info!(
-                    "Login: response: {client_response} request_url: {request_url} unique_id: {unique_id} online_platform: {online_platform}"
+                    "Login: response: {client_response} map: {} options: {:?} unique_id: {unique_id} online_platform: {online_platform}",
+                    login_url.map, login_url.options
                 );
 
-                if let Err(err) = self.game_mode().pre_login(connection.login_options()) {
+                if self.globals.require_session_ticket
+                    && let Err(err) = connection
+                        .check_session_ticket(&self.globals.session_ticket_dir, &unique_id)
+                {
+                    warn!("rejected login of {unique_id}: {err}");
+                    return connection.send_failure_control_message(err.0);
+                }
+
+                if let Err(err) = self.game_mode().pre_login(&login_url.options) {
                     error!("GameMode::pre_login failed: {err}");
+                    connection.send_failure_control_message(err.0)?;
                 } else {
                     let _ = connection.unique_id.set(unique_id);
-                    self.welcome_player(connection);
+
+                    // Players coming back from a `ClientTravel` ask for the map they travel to.
+                    let requested_map = travel::requested_map(self.globals, &login_url);
+                    connection.login_options = login_url.options;
+
+                    match requested_map {
+                        Some(map) if map.name() != self.map.name() => {
+                            connection.pending_travel = Some(TravelDestination::Map(map));
+                        }
+                        _ => self.welcome_player(connection),
+                    }
                 }
             }
             Join(channel::Join()) => {
@@ -255,25 +357,65 @@ impl World {
                         connection.net_player_index(),
                     ) {
                         error!("spawn_play_actor failed: {err}");
-                        return Ok(());
+                        return connection.send_failure_control_message(err.0);
                     }
 
-                    // Open channels for GameState and WorldDataLayers
+                    // Open channels for GameState, WorldDataLayers and the weather
 
-                    self.open_actor_channel_at(
-                        connection,
-                        6,
-                        self.game_state.get().copied().unwrap(),
-                    );
+                    self.open_actor_channel_at(connection, 6, self.game_state());
 
-                    self.open_actor_channel_at(
-                        connection,
-                        7,
-                        self.world_data_layers.get().copied().unwrap(),
-                    );
+                    self.open_actor_channel_at(connection, 7, self.world_data_layers());
+
+                    self.open_actor_channel_at(connection, 8, self.weather());
+
+                    for (index, monster_guid) in
+                        (FIRST_MONSTER_CHANNEL_INDEX..).zip(self.monsters.clone())
+                    {
+                        self.open_actor_channel_at(connection, index, monster_guid);
+                    }
                 }
             }
-            Welcome(_) | Challenge(_) => {
+            Failure(channel::Failure(error)) => {
+                warn!("client failed: {error}");
+                self.remove_player(connection);
+            }
+            CloseReason(channel::CloseReason(reason)) => {
+                info!("client is closing the connection: {reason}");
+                self.remove_player(connection);
+            }
+            DebugText(channel::DebugText(text)) => {
+                info!("debug text from client: {text}");
+            }
+            GameSpecific(channel::GameSpecific(message_type, message)) => {
+                info!(
+                    "game-specific message from client: type: {message_type}, message: {message}"
+                );
+            }
+            ActorChannelFailure(channel::ActorChannelFailure(channel_index)) => {
+                let actor = u32::try_from(channel_index)
+                    .ok()
+                    .and_then(|index| connection.get_channel_actor(index));
+
+                warn!("client failed to open actor channel {channel_index} of actor {actor:?}");
+            }
+            PCSwap(channel::PCSwap(child_index)) => {
+                // Player controllers are never swapped, seamless travel isn't supported.
+                warn!(
+                    "received NMT_PCSwap with child index {child_index}, but no player controller is being swapped"
+                );
+            }
+            BeaconJoin(channel::BeaconJoin(beacon_type, _uid_flags, unique_id)) => {
+                // Worlds don't host online beacons, the client gives up on the beacon.
+                warn!("{unique_id} tried to join beacon {beacon_type}, which isn't hosted");
+                connection.send_failure_control_message(
+                    "Join failure, Couldn't spawn client beacon actor.",
+                )?;
+            }
+            BeaconNetGUIDAck(channel::BeaconNetGUIDAck(beacon_type)) => {
+                warn!("received NMT_BeaconNetGUIDAck for beacon {beacon_type}, which isn't hosted");
+            }
+            Welcome(_) | Upgrade(_) | Challenge(_) | NetGUIDAssign(_) | EncryptionAck(_)
+            | BeaconWelcome(_) | BeaconAssignGUID(_) => {
                 error!("received server-side control channel message from client!");
             }
             Unknown(unknown) => {
@@ -284,10 +426,10 @@ impl World {
         Ok(())
     }
 
-    fn welcome_player(&self, connection: &mut NetConnection) {
+    pub fn welcome_player(&self, connection: &mut NetConnection) {
         connection
             .send_welcome_control_message(
-                Cow::Borrowed(&self.globals.map),
+                Cow::Borrowed(&self.map.path),
                 Cow::Borrowed(&self.globals.game_name),
                 Cow::Borrowed(&self.globals.redirect_url),
             )
@@ -302,16 +444,17 @@ impl World {
         player_index: NetPlayerIndex,
     ) -> Result<FNetworkGUID, LoginError> {
         let game_mode = self.game_mode();
-        let login_options = connection.login_options();
+        let login_options = connection.login_options.clone();
 
         let player_controller_guid =
-            game_mode.login(connection, remote_role, self, login_options, unique_id)?;
+            game_mode.login(connection, remote_role, self, &login_options, unique_id)?;
 
         let player_controller_actor = self.actors.get_mut(&player_controller_guid).unwrap();
         player_controller_actor.set_controlled_player(player_index);
 
         self.player_controller_map
             .insert(player_index, player_controller_guid);
+        connection.player_controller = Some(player_controller_guid);
 
         self.game_mode()
             .post_login(connection, player_controller_guid, self);
@@ -319,12 +462,50 @@ impl World {
         Ok(player_controller_guid)
     }
 
+    /// Logs out the player of a connection and destroys its actors, e.g. when it travels
+    /// to another map. Channels of the connection are dropped, the client closes them
+    /// once it loads the next map.
+    pub fn remove_player(&mut self, connection: &mut NetConnection) {
+        let Some(player_controller_guid) = connection.player_controller.take() else {
+            return;
+        };
+
+        self.game_mode()
+            .logout(connection, player_controller_guid, self);
+
+        let shared_actors = [self.game_state(), self.world_data_layers(), self.weather()];
+        let player_actors = connection
+            .actor_channels
+            .values()
+            .map(|channel| channel.channel_impl.actor_guid)
+            .filter(|guid| !shared_actors.contains(guid) && !self.monsters.contains(guid))
+            .collect::<Vec<_>>();
+
+        for guid in player_actors {
+            self.destroy_actor(guid);
+        }
+
+        connection.actor_channels.clear();
+        self.player_controller_map
+            .remove(&connection.net_player_index());
+    }
+
+    pub fn destroy_actor(&mut self, guid: FNetworkGUID) {
+        self.actors.remove(&guid);
+
+        if let Some(object) = self.objects.remove(&guid) {
+            for sub_object_guid in object.sub_objects {
+                self.objects.remove(&sub_object_guid);
+            }
+        }
+    }
+
     pub fn spawn_actor(
         &mut self,
         params: SpawnActorParams,
-        sub_objects: Vec<(FNetworkGUID, Box<dyn ObjectLayout>)>,
+        sub_objects: SubObjectList,
     ) -> FNetworkGUID {
-        let mut sub_object_set = sub_objects
+        let sub_object_set = sub_objects
             .iter()
             .map(|(guid, _)| *guid)
             .collect::<HashSet<_>>();
@@ -356,21 +537,13 @@ impl World {
 
                 self.actors.insert(guid, actor);
 
-                sub_object_set.insert(archetype);
+                // Properties are stored under the actor's GUID rather than the archetype's,
+                // since every monster of a kind shares the same class default object.
                 self.objects.insert(
                     guid,
-                    Object {
-                        rep_layout: Box::new(NullLayout),
-                        sub_objects: sub_object_set,
-                        queued_rpcs: Vec::new(),
-                    },
-                );
-
-                self.objects.insert(
-                    archetype,
                     Object {
                         rep_layout: archetype_rep,
-                        sub_objects: HashSet::new(),
+                        sub_objects: sub_object_set,
                         queued_rpcs: Vec::new(),
                     },
                 );
@@ -412,6 +585,14 @@ impl World {
             .create_actor_channel(index, guid, NAME_ACTOR_CHANNEL)
             .unwrap();
 
+        // The spawn bunch references the archetype, the client has to know its path.
+        let archetype_guid = self.actors.get(&guid).unwrap().archetype_guid;
+        if archetype_guid != guid {
+            channel
+                .channel_impl
+                .export_net_guid(self.export_guid(archetype_guid));
+        }
+
         let object = self.objects.get(&guid).unwrap();
         object.on_channel_open(guid, &mut channel.channel_impl, self);
     }
@@ -503,34 +684,36 @@ impl World {
 
     // Getters
 
-    pub fn get_actor_archetype_new<T: ObjectLayout>(
+    /// Replicated properties of an actor, `None` if the GUID isn't an actor.
+    pub fn get_actor_object<T: ObjectLayout>(
         &self,
         actor_guid: FNetworkGUID,
-    ) -> Option<RefObjectWrap<T>> {
+    ) -> Option<RefObjectWrap<'_, T>> {
         self.actors
-            .get(&actor_guid)
-            .map(|a| a.archetype_guid)
-            .and_then(|guid| self.get_object(guid))
+            .contains_key(&actor_guid)
+            .then(|| self.get_object(actor_guid))
+            .flatten()
     }
 
-    pub fn get_actor_archetype_mut_new<T: ObjectLayout>(
+    pub fn get_actor_object_mut<T: ObjectLayout>(
         &mut self,
         actor_guid: FNetworkGUID,
-    ) -> Option<MutObjectWrap<T>> {
-        self.actors
-            .get(&actor_guid)
-            .map(|a| a.archetype_guid)
-            .and_then(|guid| self.get_object_mut(guid))
+    ) -> Option<MutObjectWrap<'_, T>> {
+        if !self.actors.contains_key(&actor_guid) {
+            return None;
+        }
+
+        self.get_object_mut(actor_guid)
     }
 
-    pub fn get_object<T: ObjectLayout>(&self, guid: FNetworkGUID) -> Option<RefObjectWrap<T>> {
+    pub fn get_object<T: ObjectLayout>(&self, guid: FNetworkGUID) -> Option<RefObjectWrap<'_, T>> {
         self.objects.get(&guid).map(RefObjectWrap::new)
     }
 
     pub fn get_object_mut<T: ObjectLayout>(
         &mut self,
         guid: FNetworkGUID,
-    ) -> Option<MutObjectWrap<T>> {
+    ) -> Option<MutObjectWrap<'_, T>> {
         self.objects.get_mut(&guid).map(MutObjectWrap::new)
     }
 }
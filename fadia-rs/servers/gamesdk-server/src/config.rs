@@ -1,8 +1,36 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
+use common::version_profile::VersionProfile;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct ServerConfig {
     pub tcp_addr: SocketAddr,
+    /// Address of the game server clients travel to after logging in.
+    #[serde(default = "default_game_server_addr")]
+    pub game_server_addr: String,
+    /// Directory of the session tickets, the same as `session_ticket_dir` of the game server.
+    #[serde(default = "default_session_ticket_dir")]
+    pub session_ticket_dir: PathBuf,
+    /// Seconds a ticket stays valid, it only has to last until the client joins the game server.
+    #[serde(default = "default_session_ticket_lifetime")]
+    pub session_ticket_lifetime: u64,
+    /// Sends the packet encryption key of the ticket along with its token, for game servers
+    /// with `packet_encryption`. Only clients patched to read it can connect, see the README.
+    #[serde(default)]
+    pub packet_encryption: bool,
+    #[serde(default)]
+    pub version: VersionProfile,
+}
+
+fn default_game_server_addr() -> String {
+    String::from("127.0.0.1:30150")
+}
+
+fn default_session_ticket_dir() -> PathBuf {
+    PathBuf::from("session_tickets")
+}
+
+fn default_session_ticket_lifetime() -> u64 {
+    120
 }
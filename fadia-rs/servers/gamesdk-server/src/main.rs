@@ -1,14 +1,13 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::LazyLock};
 
 use config::ServerConfig;
-use tracing::error;
 
 mod config;
 mod net;
 mod packet;
 mod packet_processing;
 
-#[allow(unused, unsafe_op_in_unsafe_fn)]
+#[allow(unused, unsafe_op_in_unsafe_fn, clippy::extra_unused_lifetimes)]
 #[path = "../gen_flatbuffers/data_generated.rs"]
 mod data;
 
@@ -20,14 +19,16 @@ pub enum StartupError {
 
 #[tokio::main]
 async fn main() -> Result<(), StartupError> {
-    common::log_util::init_tracing();
+    static CONFIG: LazyLock<ServerConfig> = LazyLock::new(|| {
+        common::config_util::load_or_create::<ServerConfig>(
+            "gamesdk_server.toml",
+            include_str!("../gamesdk_server.default.toml"),
+        )
+    });
 
-    let config = common::config_util::load_or_create::<ServerConfig>(
-        "gamesdk_server.toml",
-        include_str!("../gamesdk_server.default.toml"),
-    );
+    common::log_util::init_tracing();
 
-    net::serve(config.tcp_addr)
+    net::serve(CONFIG.tcp_addr, &CONFIG)
         .await
-        .map_err(|err| StartupError::BindFailed(config.tcp_addr, err))
+        .map_err(|err| StartupError::BindFailed(CONFIG.tcp_addr, err))
 }
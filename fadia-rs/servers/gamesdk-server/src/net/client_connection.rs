@@ -10,12 +10,14 @@ use tokio::{
 use tracing::{debug, error, info};
 
 use crate::{
+    config::ServerConfig,
     packet::{MessageID, Packet},
     packet_processing,
 };
 
 pub struct ClientConnection {
     pub addr: SocketAddr,
+    pub config: &'static ServerConfig,
     output: mpsc::Sender<Packet>,
 }
 
@@ -28,12 +30,20 @@ impl ClientConnection {
     }
 }
 
-pub(crate) async fn process_connection(stream: TcpStream, addr: SocketAddr) {
+pub(crate) async fn process_connection(
+    stream: TcpStream,
+    addr: SocketAddr,
+    config: &'static ServerConfig,
+) {
     let (read, write) = stream.into_split();
     let (tx, rx) = mpsc::channel(8);
     tokio::spawn(send_loop(write, rx));
 
-    let client_connection = ClientConnection { addr, output: tx };
+    let client_connection = ClientConnection {
+        addr,
+        config,
+        output: tx,
+    };
 
     let (tx, mut rx) = mpsc::channel(8);
     tokio::spawn(recv_loop(read, tx));
@@ -1,12 +1,14 @@
 use std::{io, net::SocketAddr};
 
+use crate::config::ServerConfig;
+
 use tokio::net::TcpListener;
 use tracing::info;
 
 mod client_connection;
 pub use client_connection::ClientConnection;
 
-pub async fn serve(host: SocketAddr) -> io::Result<()> {
+pub async fn serve(host: SocketAddr, config: &'static ServerConfig) -> io::Result<()> {
     let listener = TcpListener::bind(host).await?;
     info!("listening at tcp://{host}");
 
@@ -16,6 +18,6 @@ pub async fn serve(host: SocketAddr) -> io::Result<()> {
         };
 
         info!("new connection from {addr}");
-        tokio::spawn(client_connection::process_connection(client, addr));
+        tokio::spawn(client_connection::process_connection(client, addr, config));
     }
 }
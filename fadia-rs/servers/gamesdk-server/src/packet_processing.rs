@@ -1,8 +1,8 @@
-use std::io;
+use std::{io, time::Duration};
 
-use common::time_util;
+use common::{session_ticket::SessionTicket, time_util};
 use flatbuffers::FlatBufferBuilder;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     data::{
@@ -50,10 +50,43 @@ async fn on_client_login_req(
     let client_login_req = flatbuffers::root::<ClientLoginReq>(packet.payload())?;
     info!("{client_login_req:?}");
 
+    // TODO: there are no accounts yet, every username is let in without checking the password.
+    let Some(username) = client_login_req.username().filter(|name| !name.is_empty()) else {
+        warn!("login request without username");
+        return Ok(());
+    };
+
+    let config = client_connection.config;
+
+    // The client carries URL options of the travel address into `NMT_Hello`,
+    // the game server checks the token against the ticket. No field of ClientTravelCmd is
+    // known to carry the encryption key, so it's sent as a URL option which the stock client
+    // ignores, see the README.
+    let server_addr = match SessionTicket::issue(
+        &config.session_ticket_dir,
+        username,
+        Duration::from_secs(config.session_ticket_lifetime),
+    ) {
+        Ok(ticket) if config.packet_encryption => format!(
+            "{}?EncryptionToken={}?EncryptionKey={}",
+            config.game_server_addr,
+            ticket.token,
+            hex::encode_upper(ticket.encryption_key())
+        ),
+        Ok(ticket) => format!(
+            "{}?EncryptionToken={}",
+            config.game_server_addr, ticket.token
+        ),
+        Err(err) => {
+            error!("failed to issue session ticket for {username}: {err}");
+            config.game_server_addr.clone()
+        }
+    };
+
     let mut builder = FlatBufferBuilder::new();
-    let player_uid = builder.create_string("9_1337");
+    let player_uid = builder.create_string(username);
     let empty_1 = builder.create_string("");
-    let server_addr = builder.create_string("127.0.0.1:30150");
+    let server_addr = builder.create_string(&server_addr);
     let empty_2 = builder.create_string("");
     let empty_3 = builder.create_string("");
     let player_character_bp = builder
@@ -108,7 +141,7 @@ async fn on_client_keep_alive_cmd(
 pub async fn send_server_version_cmd(client_connection: &ClientConnection) {
     let mut builder = FlatBufferBuilder::new();
 
-    let server_version = builder.create_string("20200704");
+    let server_version = builder.create_string(&client_connection.config.version.server_version);
     let client_wan_ip = builder.create_string(&client_connection.addr.ip().to_string());
 
     let server_version_cmd = ServerVersionCmd::create(
@@ -120,7 +153,7 @@ pub async fn send_server_version_cmd(client_connection: &ClientConnection) {
             heart_type: 1, // Disable = 0, Check_Alive = 1, Tracert = 2
             heart_client_interval: 30,
             check_server_interval: u32::MAX,
-            server_id: 11000,
+            server_id: client_connection.config.version.server_id,
             server_time_utc: time_util::unix_utc_timestamp_ms(),
             server_time: time_util::unix_timestamp_ms(),
         },